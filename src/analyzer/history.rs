@@ -0,0 +1,224 @@
+use crate::ingestion::Ingestion;
+use crate::substance::route_of_administration::dosage::Dosage;
+use chrono::Datelike;
+use chrono::Days;
+use chrono::Months;
+use chrono::NaiveDate;
+use chrono::Timelike;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Length of a single bucket used to aggregate ingestion history.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Period
+{
+    #[default]
+    Day,
+    /// Week starting on Monday
+    Week,
+    Month,
+}
+
+impl Period
+{
+    /// First day of the period which contains given date.
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate
+    {
+        match self
+        {
+            | Period::Day => date,
+            | Period::Week => date - Days::new(date.weekday().num_days_from_monday() as u64),
+            | Period::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// First day of the period that follows the one starting at given date.
+    pub fn next(&self, start: NaiveDate) -> NaiveDate
+    {
+        match self
+        {
+            | Period::Day => start + Days::new(1),
+            | Period::Week => start + Days::new(7),
+            | Period::Month => start + Months::new(1),
+        }
+    }
+}
+
+/// Sum of dosages ingested in every period between the first and the last
+/// ingestion, periods without any ingestion are included with zero dosage so
+/// the result can be plotted as a continuous series. Dosage ranges count with
+/// their middle and unknown dosages are left out.
+pub fn dosage_over_time(ingestions: &[Ingestion], period: Period) -> Vec<(NaiveDate, Dosage)>
+{
+    let mut totals: BTreeMap<NaiveDate, Dosage> = BTreeMap::new();
+
+    for ingestion in ingestions
+    {
+        let bucket = period.start_of(ingestion.ingestion_date.date_naive());
+        let total = totals.entry(bucket).or_default();
+        *total = *total + ingestion.dosage.value().unwrap_or_default();
+    }
+
+    let (Some(first), Some(last)) = (
+        totals.keys().next().copied(),
+        totals.keys().next_back().copied(),
+    )
+    else
+    {
+        return vec![];
+    };
+
+    let mut series = Vec::new();
+    let mut bucket = first;
+
+    while bucket <= last
+    {
+        series.push((bucket, totals.get(&bucket).copied().unwrap_or_default()));
+        bucket = period.next(bucket);
+    }
+
+    series
+}
+
+/// Ingestions logged on a single calendar day.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DailyActivity
+{
+    pub ingestion_count: usize,
+    pub total_dosage: Dosage,
+}
+
+/// Group ingestions by calendar day (in local time), days without any
+/// ingestion are not present in the result.
+pub fn daily_activity(ingestions: &[Ingestion]) -> BTreeMap<NaiveDate, DailyActivity>
+{
+    let mut activity: BTreeMap<NaiveDate, DailyActivity> = BTreeMap::new();
+
+    for ingestion in ingestions
+    {
+        let day = activity
+            .entry(ingestion.ingestion_date.date_naive())
+            .or_default();
+        day.ingestion_count += 1;
+        day.total_dosage = day.total_dosage + ingestion.dosage.value().unwrap_or_default();
+    }
+
+    activity
+}
+
+/// Amount of ingestions by day of week (rows, starting from Monday) and hour
+/// of day (columns) in local time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UsageGrid(pub [[usize; 24]; 7]);
+
+impl UsageGrid
+{
+    pub fn from_ingestions(ingestions: &[Ingestion]) -> Self
+    {
+        let mut grid = [[0usize; 24]; 7];
+
+        for ingestion in ingestions
+        {
+            let date = ingestion.ingestion_date;
+            let weekday = date.weekday().num_days_from_monday() as usize;
+            grid[weekday][date.hour() as usize] += 1;
+        }
+
+        Self(grid)
+    }
+
+    pub fn max(&self) -> usize { self.0.iter().flatten().copied().max().unwrap_or(0) }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::substance::route_of_administration::dosage::DosageEstimate;
+    use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+    use chrono::Local;
+    use chrono::TimeZone;
+    use std::str::FromStr;
+
+    fn ingestion(dosage: &str, date: (i32, u32, u32, u32)) -> Ingestion
+    {
+        Ingestion {
+            id: None,
+            substance_name: "caffeine".to_string(),
+            dosage: DosageEstimate::from_str(dosage).unwrap(),
+            route: RouteOfAdministrationClassification::Oral,
+            ingestion_date: Local
+                .with_ymd_and_hms(date.0, date.1, date.2, date.3, 0, 0)
+                .unwrap(),
+            dosage_classification: None,
+            dosage_classification_max: None,
+            amount: None,
+            body_weight: None,
+            bioavailability: None,
+            substance: None,
+            phases: vec![],
+        }
+    }
+
+    #[test]
+    fn test_dosage_over_time()
+    {
+        let ingestions = vec![
+            ingestion("100mg", (2025, 1, 6, 8)),
+            ingestion("50mg", (2025, 1, 6, 14)),
+            ingestion("200mg", (2025, 1, 8, 8)),
+            ingestion("100mg", (2025, 1, 13, 8)),
+        ];
+
+        let daily = dosage_over_time(&ingestions, Period::Day);
+        assert_eq!(daily.len(), 8);
+        assert_eq!(daily[0].1.to_string(), "150 mg");
+        assert_eq!(daily[1].1, Dosage::default());
+
+        let weekly = dosage_over_time(&ingestions, Period::Week);
+        assert_eq!(weekly.len(), 2);
+        assert_eq!(weekly[0].0, NaiveDate::from_ymd_opt(2025, 1, 6).unwrap());
+        assert_eq!(weekly[0].1.to_string(), "350 mg");
+
+        let monthly = dosage_over_time(&ingestions, Period::Month);
+        assert_eq!(monthly.len(), 1);
+        assert_eq!(monthly[0].1.to_string(), "450 mg");
+    }
+
+    #[test]
+    fn test_daily_activity()
+    {
+        let ingestions = vec![
+            ingestion("100mg", (2025, 1, 6, 8)),
+            ingestion("50mg", (2025, 1, 6, 23)),
+            ingestion("200mg", (2025, 1, 8, 0)),
+        ];
+
+        let activity = daily_activity(&ingestions);
+
+        assert_eq!(activity.len(), 2);
+
+        let monday = &activity[&NaiveDate::from_ymd_opt(2025, 1, 6).unwrap()];
+        assert_eq!(monday.ingestion_count, 2);
+        assert_eq!(monday.total_dosage.to_string(), "150 mg");
+    }
+
+    #[test]
+    fn test_usage_grid()
+    {
+        // 2025-01-06 is a Monday
+        let ingestions = vec![
+            ingestion("100mg", (2025, 1, 6, 8)),
+            ingestion("100mg", (2025, 1, 13, 8)),
+            ingestion("100mg", (2025, 1, 12, 23)),
+        ];
+
+        let grid = UsageGrid::from_ingestions(&ingestions);
+
+        assert_eq!(grid.0[0][8], 2);
+        assert_eq!(grid.0[6][23], 1);
+        assert_eq!(grid.max(), 2);
+    }
+}
@@ -1 +1,3 @@
-mod model;
\ No newline at end of file
+pub mod history;
+mod model;
+pub mod statistics;
@@ -0,0 +1,313 @@
+use crate::ingestion::Ingestion;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::DosageClassification;
+use chrono::Duration;
+use chrono::NaiveDate;
+use hashbrown::HashMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Amount of ingestions in each of dosage classifications, ingestions which
+/// couldn't be classified (e.g. substance is not known to the application) are
+/// counted as `unclassified`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DosageDistribution
+{
+    pub threshold: usize,
+    pub light: usize,
+    pub common: usize,
+    pub strong: usize,
+    pub heavy: usize,
+    pub unclassified: usize,
+}
+
+impl DosageDistribution
+{
+    pub fn record(&mut self, classification: Option<DosageClassification>)
+    {
+        match classification
+        {
+            | Some(DosageClassification::Threshold) => self.threshold += 1,
+            | Some(DosageClassification::Light) => self.light += 1,
+            | Some(DosageClassification::Common) => self.common += 1,
+            | Some(DosageClassification::Strong) => self.strong += 1,
+            | Some(DosageClassification::Heavy) => self.heavy += 1,
+            | None => self.unclassified += 1,
+        }
+    }
+
+    /// Non-empty buckets ordered from the lowest to the highest dosage
+    /// classification.
+    pub fn entries(&self) -> Vec<(&'static str, usize)>
+    {
+        [
+            ("Threshold", self.threshold),
+            ("Light", self.light),
+            ("Common", self.common),
+            ("Strong", self.strong),
+            ("Heavy", self.heavy),
+            ("Unclassified", self.unclassified),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .collect()
+    }
+}
+
+/// Aggregated information about every ingestion of a single substance.
+#[derive(Debug, Clone)]
+pub struct SubstanceStatistics
+{
+    pub substance_name: String,
+    pub ingestion_count: usize,
+    pub total_dosage: Dosage,
+    pub mean_dosage: Dosage,
+    /// Sum of effective dosages (see `Ingestion::effective_dosage`), available
+    /// only when bioavailability of every ingestion with known dosage is known.
+    pub total_effective_dosage: Option<Dosage>,
+    pub mean_effective_dosage: Option<Dosage>,
+    pub dosage_distribution: DosageDistribution,
+    pub most_common_route: Option<RouteOfAdministrationClassification>,
+    /// Mean time between two consecutive ingestions, not available for
+    /// substances ingested only once.
+    pub average_interval: Option<Duration>,
+    /// Longest amount of consecutive calendar days (in local time) on which
+    /// substance was ingested at least once.
+    pub longest_streak: usize,
+}
+
+impl SubstanceStatistics
+{
+    /// Compute statistics from ingestions of the same substance, order of
+    /// ingestions doesn't matter.
+    pub fn compute(substance_name: impl Into<String>, ingestions: &[Ingestion]) -> Self
+    {
+        let mut dates: Vec<_> = ingestions.iter().map(|i| i.ingestion_date).collect();
+        dates.sort();
+
+        // Unknown dosages are left out of sums and means, dosage ranges count
+        // with their middle.
+        let known: Vec<&Ingestion> = ingestions.iter().filter(|i| i.dosage.is_known()).collect();
+
+        let total_dosage: Dosage = known.iter().filter_map(|i| i.dosage.value()).sum();
+        let mean_dosage = if known.is_empty()
+        {
+            Dosage::default()
+        }
+        else
+        {
+            total_dosage / known.len() as f64
+        };
+
+        let total_effective_dosage: Option<Dosage> = known
+            .iter()
+            .map(|i| i.effective_dosage().and_then(|dosage| dosage.value()))
+            .sum::<Option<Dosage>>()
+            .filter(|_| !known.is_empty());
+        let mean_effective_dosage =
+            total_effective_dosage.map(|total| total / known.len() as f64);
+
+        let mut dosage_distribution = DosageDistribution::default();
+        ingestions
+            .iter()
+            .for_each(|i| dosage_distribution.record(i.dosage_classification));
+
+        Self {
+            substance_name: substance_name.into(),
+            ingestion_count: ingestions.len(),
+            total_dosage,
+            mean_dosage,
+            total_effective_dosage,
+            mean_effective_dosage,
+            dosage_distribution,
+            most_common_route: most_common_route(ingestions),
+            average_interval: average_interval(&dates),
+            longest_streak: longest_streak(dates.iter().map(|date| date.date_naive())),
+        }
+    }
+
+    /// Group ingestions by substance and compute statistics for each of them,
+    /// results are ordered by substance name.
+    pub fn by_substance(ingestions: &[Ingestion]) -> Vec<Self>
+    {
+        let mut groups: BTreeMap<&str, Vec<Ingestion>> = BTreeMap::new();
+
+        for ingestion in ingestions
+        {
+            groups
+                .entry(ingestion.substance_name.as_str())
+                .or_default()
+                .push(ingestion.clone());
+        }
+
+        groups
+            .into_iter()
+            .map(|(name, ingestions)| Self::compute(name, &ingestions))
+            .collect()
+    }
+}
+
+fn most_common_route(ingestions: &[Ingestion]) -> Option<RouteOfAdministrationClassification>
+{
+    let mut occurrences: HashMap<RouteOfAdministrationClassification, usize> = HashMap::new();
+
+    for ingestion in ingestions
+    {
+        *occurrences.entry(ingestion.route).or_default() += 1;
+    }
+
+    // Ties are resolved by route name to keep output stable between runs.
+    occurrences
+        .into_iter()
+        .max_by(|(route_a, count_a), (route_b, count_b)| {
+            count_a
+                .cmp(count_b)
+                .then_with(|| route_b.to_string().cmp(&route_a.to_string()))
+        })
+        .map(|(route, _)| route)
+}
+
+fn average_interval<T: chrono::TimeZone>(sorted_dates: &[chrono::DateTime<T>]) -> Option<Duration>
+{
+    if sorted_dates.len() < 2
+    {
+        return None;
+    }
+
+    let span = sorted_dates
+        .last()?
+        .clone()
+        .signed_duration_since(sorted_dates.first()?.clone());
+
+    Some(span / (sorted_dates.len() as i32 - 1))
+}
+
+fn longest_streak(days: impl Iterator<Item = NaiveDate>) -> usize
+{
+    let mut days: Vec<NaiveDate> = days.collect();
+    days.sort();
+    days.dedup();
+
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for day in days
+    {
+        current = match previous
+        {
+            | Some(previous) if previous.succ_opt() == Some(day) => current + 1,
+            | _ => 1,
+        };
+        longest = longest.max(current);
+        previous = Some(day);
+    }
+
+    longest
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::substance::route_of_administration::dosage::DosageEstimate;
+    use chrono::Local;
+    use chrono::TimeZone;
+    use std::str::FromStr;
+
+    fn ingestion(name: &str, dosage: &str, date: (i32, u32, u32, u32)) -> Ingestion
+    {
+        Ingestion {
+            id: None,
+            substance_name: name.to_string(),
+            dosage: DosageEstimate::from_str(dosage).unwrap(),
+            route: RouteOfAdministrationClassification::Oral,
+            ingestion_date: Local
+                .with_ymd_and_hms(date.0, date.1, date.2, date.3, 0, 0)
+                .unwrap(),
+            dosage_classification: Some(DosageClassification::Common),
+            dosage_classification_max: None,
+            amount: None,
+            body_weight: None,
+            bioavailability: None,
+            substance: None,
+            phases: vec![],
+        }
+    }
+
+    #[test]
+    fn test_compute_statistics()
+    {
+        let ingestions = vec![
+            ingestion("caffeine", "100mg", (2025, 1, 1, 8)),
+            ingestion("caffeine", "200mg", (2025, 1, 2, 8)),
+            ingestion("caffeine", "300mg", (2025, 1, 3, 8)),
+            ingestion("caffeine", "100mg", (2025, 1, 10, 8)),
+        ];
+
+        let statistics = SubstanceStatistics::compute("caffeine", &ingestions);
+
+        assert_eq!(statistics.ingestion_count, 4);
+        assert_eq!(statistics.total_dosage.to_string(), "700 mg");
+        assert_eq!(statistics.mean_dosage.to_string(), "175 mg");
+        assert_eq!(statistics.dosage_distribution.common, 4);
+        assert_eq!(
+            statistics.most_common_route,
+            Some(RouteOfAdministrationClassification::Oral)
+        );
+        assert_eq!(statistics.average_interval, Some(Duration::days(3)));
+        assert_eq!(statistics.longest_streak, 3);
+        assert!(statistics.total_effective_dosage.is_none());
+    }
+
+    #[test]
+    fn test_effective_dosage_across_routes()
+    {
+        let mut oral = ingestion("ketamine", "100mg", (2025, 1, 1, 8));
+        oral.bioavailability = Some(0.2);
+        let mut insufflated = ingestion("ketamine", "50mg", (2025, 1, 2, 8));
+        insufflated.route = RouteOfAdministrationClassification::Insufflated;
+        insufflated.bioavailability = Some(0.4);
+
+        let statistics = SubstanceStatistics::compute("ketamine", &[oral, insufflated]);
+
+        assert_eq!(statistics.total_dosage.to_string(), "150 mg");
+        assert_eq!(statistics.total_effective_dosage.unwrap().to_string(), "40.0 mg");
+        assert_eq!(statistics.mean_effective_dosage.unwrap().to_string(), "20.0 mg");
+    }
+
+    #[test]
+    fn test_statistics_of_uncertain_dosages()
+    {
+        let ingestions = vec![
+            ingestion("caffeine", "100-200mg", (2025, 1, 1, 8)),
+            ingestion("caffeine", "~50mg", (2025, 1, 2, 8)),
+            ingestion("caffeine", "unknown", (2025, 1, 3, 8)),
+        ];
+
+        let statistics = SubstanceStatistics::compute("caffeine", &ingestions);
+
+        assert_eq!(statistics.ingestion_count, 3);
+        assert_eq!(statistics.total_dosage.to_string(), "200 mg");
+        assert_eq!(statistics.mean_dosage.to_string(), "100 mg");
+    }
+
+    #[test]
+    fn test_statistics_by_substance()
+    {
+        let ingestions = vec![
+            ingestion("caffeine", "100mg", (2025, 1, 1, 8)),
+            ingestion("l-theanine", "200mg", (2025, 1, 1, 8)),
+            ingestion("caffeine", "100mg", (2025, 1, 1, 12)),
+        ];
+
+        let statistics = SubstanceStatistics::by_substance(&ingestions);
+
+        assert_eq!(statistics.len(), 2);
+        assert_eq!(statistics[0].substance_name, "caffeine");
+        assert_eq!(statistics[0].ingestion_count, 2);
+        assert_eq!(statistics[0].longest_streak, 1);
+        assert_eq!(statistics[1].average_interval, None);
+    }
+}
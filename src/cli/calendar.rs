@@ -0,0 +1,338 @@
+use crate::analyzer::history::DailyActivity;
+use crate::analyzer::history::daily_activity;
+use crate::cli::MessageFormat;
+use crate::cli::chart::heat_cell;
+use crate::cli::formatter::Formatter;
+use crate::core::CommandHandler;
+use crate::database::entities::ingestion;
+use crate::ingestion::query::ingested_between;
+use crate::utils::AppContext;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+use crate::substance::route_of_administration::dosage::MassUnit;
+use async_trait::async_trait;
+use chrono::Datelike;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::TimeZone;
+use clap::Parser;
+use miette::IntoDiagnostic;
+use miette::miette;
+use owo_colors::OwoColorize;
+use sea_orm::ColumnTrait;
+use sea_orm::QueryFilter;
+use serde::Serialize;
+use std::borrow::Cow;
+use tabled::Tabled;
+
+/// Value used to shade days of the calendar.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarMetric
+{
+    /// Amount of ingestions logged on a day
+    #[default]
+    Ingestions,
+    /// Total dosage ingested on a day, requires a substance
+    Dosage,
+}
+
+/// Show a calendar of days on which ingestions were logged.
+///
+/// Every day of the year is shaded by the amount of ingestions or, for a
+/// single substance, by total dosage ingested on that day.
+#[derive(Parser, Debug)]
+#[command(version, about = "Show calendar of ingestion days", long_about, aliases = vec!["heatmap"])]
+pub struct ViewCalendar
+{
+    /// Year to show, defaults to the current one
+    #[arg(short = 'y', long)]
+    pub year: Option<i32>,
+
+    /// Show only ingestions of given substance
+    #[arg(short = 's', long = "substance", value_name = "SUBSTANCE_NAME")]
+    pub substance_name: Option<String>,
+
+    /// Value used to shade days of the calendar
+    #[arg(short = 'm', long, value_enum, default_value_t = CalendarMetric::default())]
+    pub metric: CalendarMetric,
+}
+
+#[async_trait]
+impl CommandHandler for ViewCalendar
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        let year = self.year.unwrap_or_else(|| Local::now().year());
+        let substance_name = self
+            .substance_name
+            .as_ref()
+            .map(|name| name.trim().to_lowercase());
+
+        if self.metric == CalendarMetric::Dosage && substance_name.is_none()
+        {
+            return Err(miette!(
+                help = "Pass substance name with --substance or use --metric ingestions",
+                "Total dosage can be compared only between ingestions of the same substance"
+            ));
+        }
+
+        let (Some(first_day), Some(last_day)) = (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year, 12, 31),
+        )
+        else
+        {
+            return Err(miette!("Year {} is out of supported range", year));
+        };
+
+        let start = Local
+            .from_local_datetime(&first_day.and_hms_opt(0, 0, 0).unwrap())
+            .earliest()
+            .ok_or_else(|| miette!("Start of year {} doesn't exist in local time", year))?;
+        let end = Local
+            .from_local_datetime(&last_day.and_hms_opt(23, 59, 59).unwrap())
+            .latest()
+            .ok_or_else(|| miette!("End of year {} doesn't exist in local time", year))?;
+
+        let mut query = ingested_between(start, end);
+
+        if let Some(substance_name) = &substance_name
+        {
+            query = query.filter(ingestion::Column::SubstanceName.eq(substance_name.clone()));
+        }
+
+        let ingestions: Vec<crate::ingestion::Ingestion> = query
+            .all(ctx.database_connection)
+            .await
+            .into_diagnostic()?
+            .into_iter()
+            .map(crate::ingestion::Ingestion::from)
+            .collect();
+
+        let activity = daily_activity(&ingestions);
+        let include_dosage = substance_name.is_some();
+
+        let view_model = CalendarViewModel {
+            year,
+            substance_name,
+            metric: self.metric,
+            days: first_day
+                .iter_days()
+                .take_while(|date| *date <= last_day)
+                .map(|date| {
+                    CalendarDay::new(
+                        date,
+                        activity.get(&date).copied().unwrap_or_default(),
+                        include_dosage,
+                        ctx.stdout_format.mass_unit(),
+                    )
+                })
+                .collect(),
+        };
+
+        println!("{}", view_model.format(ctx.stdout_format));
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CalendarDay
+{
+    pub date: NaiveDate,
+    pub ingestion_count: usize,
+    /// Total dosage is available only when calendar is limited to a single
+    /// substance.
+    pub total_dosage: Option<String>,
+    pub total_dosage_mg: Option<f64>,
+}
+
+impl CalendarDay
+{
+    fn new(date: NaiveDate, activity: DailyActivity, include_dosage: bool, unit: MassUnit) -> Self
+    {
+        let total = include_dosage.then_some(activity.total_dosage);
+
+        Self {
+            date,
+            ingestion_count: activity.ingestion_count,
+            total_dosage: total.map(|dosage| dosage.display_in(unit)),
+            total_dosage_mg: total
+                .map(|dosage| (dosage.as_base_units() * 1_000_000_000.0).round() / 1000.0),
+        }
+    }
+
+    fn value(&self, metric: CalendarMetric) -> f64
+    {
+        match metric
+        {
+            | CalendarMetric::Ingestions => self.ingestion_count as f64,
+            | CalendarMetric::Dosage => self.total_dosage_mg.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CalendarViewModel
+{
+    pub year: i32,
+    pub substance_name: Option<String>,
+    pub metric: CalendarMetric,
+    /// Every day of the year, including days without any ingestion.
+    pub days: Vec<CalendarDay>,
+}
+
+impl Tabled for CalendarViewModel
+{
+    const LENGTH: usize = 3;
+
+    fn fields(&self) -> Vec<Cow<'_, str>>
+    {
+        vec![
+            Cow::Owned(self.year.to_string()),
+            Cow::Owned(self.active_days().to_string()),
+            Cow::Owned(self.ingestion_count().to_string()),
+        ]
+    }
+
+    fn headers() -> Vec<Cow<'static, str>>
+    {
+        vec![
+            Cow::Borrowed("Year"),
+            Cow::Borrowed("Active Days"),
+            Cow::Borrowed("Ingestions"),
+        ]
+    }
+}
+
+impl CalendarViewModel
+{
+    fn active_days(&self) -> usize { self.days.iter().filter(|d| d.ingestion_count > 0).count() }
+
+    fn ingestion_count(&self) -> usize { self.days.iter().map(|d| d.ingestion_count).sum() }
+
+    /// Index of the week column in which given day is drawn, weeks start on
+    /// Monday and the first column contains the first day of the year.
+    fn week_of(&self, date: NaiveDate) -> usize
+    {
+        let offset = self
+            .days
+            .first()
+            .map(|day| day.date.weekday().num_days_from_monday())
+            .unwrap_or(0);
+
+        (date.ordinal0() + offset) as usize / 7
+    }
+
+    fn render_calendar(&self) -> String
+    {
+        const WEEKDAYS: [&str; 7] = ["Mon", "", "Wed", "", "Fri", "", "Sun"];
+
+        let weeks = self.days.last().map(|d| self.week_of(d.date) + 1).unwrap_or(0);
+        let max = self
+            .days
+            .iter()
+            .map(|day| day.value(self.metric))
+            .fold(0.0, f64::max);
+        let today = Local::now().date_naive();
+
+        let mut months = vec![' '; weeks + 3];
+        for day in self.days.iter().filter(|day| day.date.day() == 1)
+        {
+            let column = self.week_of(day.date);
+            for (index, character) in day.date.format("%b").to_string().chars().enumerate()
+            {
+                months[column + index] = character;
+            }
+        }
+
+        let mut grid = vec![vec![" ".to_string(); weeks]; 7];
+        for day in &self.days
+        {
+            let weekday = day.date.weekday().num_days_from_monday() as usize;
+            grid[weekday][self.week_of(day.date)] = match day.date > today
+            {
+                | true => " ".to_string(),
+                | false => heat_cell(day.value(self.metric), max, 1),
+            };
+        }
+
+        let mut output = format!("    {}\n", months.iter().collect::<String>().trim_end());
+        for (weekday, row) in WEEKDAYS.iter().zip(grid)
+        {
+            output.push_str(&format!("{:<4}{}\n", weekday, row.concat()));
+        }
+
+        output
+    }
+
+    fn render_legend(&self) -> String
+    {
+        let shades: String = (0..=4)
+            .map(|level| heat_cell(level as f64, 4.0, 1))
+            .collect();
+
+        let scale = match self.metric
+        {
+            | CalendarMetric::Ingestions =>
+            {
+                let max = self.days.iter().map(|d| d.ingestion_count).max().unwrap_or(0);
+                format!("at most {} ingestions per day", max)
+            }
+            | CalendarMetric::Dosage =>
+            {
+                let max = self
+                    .days
+                    .iter()
+                    .filter_map(|d| d.total_dosage.as_ref().map(|total| (d.value(self.metric), total)))
+                    .max_by(|(a, _), (b, _)| a.total_cmp(b))
+                    .map(|(_, total)| total.clone())
+                    .unwrap_or_default();
+                format!("at most {} per day", max)
+            }
+        };
+
+        format!("    Less {} More ({})", shades, scale.dimmed())
+    }
+}
+
+impl Formatter for CalendarViewModel
+{
+    fn format(&self, format: MessageFormat) -> String
+    {
+        match format
+        {
+            | MessageFormat::Pretty => self.pretty(),
+            | MessageFormat::Json => self.json(),
+            | MessageFormat::Ndjson => self
+                .days
+                .iter()
+                .map(|day| serde_json::to_string(day).unwrap())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    fn pretty(&self) -> String
+    {
+        let subject = match &self.substance_name
+        {
+            | Some(substance_name) => format!(" of {}", substance_name),
+            | None => String::new(),
+        };
+
+        format!(
+            "{}\n\n{}\n{}",
+            format!(
+                "{} ingestions{} on {} days in {}",
+                self.ingestion_count(),
+                subject,
+                self.active_days(),
+                self.year
+            )
+            .bold(),
+            self.render_calendar(),
+            self.render_legend()
+        )
+    }
+}
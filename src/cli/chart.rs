@@ -0,0 +1,296 @@
+use crate::analyzer::history::Period;
+use crate::analyzer::history::UsageGrid;
+use crate::analyzer::history::dosage_over_time;
+use crate::cli::MessageFormat;
+use crate::cli::formatter::Formatter;
+use crate::core::CommandHandler;
+use crate::database::entities::ingestion;
+use crate::database::entities::ingestion::Entity as Ingestion;
+use crate::substance::route_of_administration::dosage::Dosage;
+use crate::utils::AppContext;
+use crate::utils::parse_date_string;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDate;
+use clap::Parser;
+use miette::IntoDiagnostic;
+use miette::miette;
+use owo_colors::OwoColorize;
+use sea_orm::ColumnTrait;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use serde::Serialize;
+use std::borrow::Cow;
+use tabled::Tabled;
+use textplots::Chart;
+use textplots::LabelBuilder;
+use textplots::LabelFormat;
+use textplots::Plot;
+use textplots::Shape;
+
+/// Draw history of a substance usage in the terminal.
+///
+/// Chart shows total dosage of the substance ingested in every day, week or
+/// month, followed by a grid of ingestions by day of the week and hour of the
+/// day, which together make creeping consumption habits easy to notice.
+#[derive(Parser, Debug)]
+#[command(version, about = "Draw chart of substance usage over time", long_about, aliases = vec!["plot"])]
+pub struct ViewChart
+{
+    /// Name of the substance to draw chart for
+    #[arg(index = 1, value_name = "SUBSTANCE_NAME")]
+    pub substance_name: String,
+
+    /// Period of time used to sum up dosages
+    #[arg(short = 'b', long = "by", value_enum, default_value_t = Period::default())]
+    pub period: Period,
+
+    /// Include only ingestions that happened at or after given date
+    #[arg(long, value_name = "DATE", value_parser = parse_date_string)]
+    pub from: Option<DateTime<Local>>,
+
+    /// Include only ingestions that happened at or before given date
+    #[arg(long, value_name = "DATE", value_parser = parse_date_string)]
+    pub to: Option<DateTime<Local>>,
+}
+
+#[async_trait]
+impl CommandHandler for ViewChart
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        let substance_name = self.substance_name.trim().to_lowercase();
+
+        let mut query =
+            Ingestion::find().filter(ingestion::Column::SubstanceName.eq(substance_name.clone()));
+
+        if let Some(from) = self.from
+        {
+            query = query.filter(ingestion::Column::IngestedAt.gte(from.to_utc().naive_utc()));
+        }
+
+        if let Some(to) = self.to
+        {
+            query = query.filter(ingestion::Column::IngestedAt.lte(to.to_utc().naive_utc()));
+        }
+
+        let ingestions: Vec<crate::ingestion::Ingestion> = query
+            .order_by_asc(ingestion::Column::IngestedAt)
+            .all(ctx.database_connection)
+            .await
+            .into_diagnostic()?
+            .into_iter()
+            .map(crate::ingestion::Ingestion::from)
+            .collect();
+
+        if ingestions.is_empty()
+        {
+            return Err(miette!(
+                help = "Check substance name with `neuronek ingestion list`",
+                "No ingestions of {} found",
+                substance_name
+            ));
+        }
+
+        let view_model = ChartViewModel {
+            substance_name,
+            period: self.period,
+            series: dosage_over_time(&ingestions, self.period)
+                .into_iter()
+                .map(|(period_start, total)| ChartPoint {
+                    period_start,
+                    total_dosage: total.display_in(ctx.stdout_format.mass_unit()),
+                    total_dosage_mg: (total.as_base_units() * 1_000_000_000.0).round() / 1000.0,
+                })
+                .collect(),
+            usage_grid: UsageGrid::from_ingestions(&ingestions),
+        };
+
+        println!("{}", view_model.format(ctx.stdout_format));
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChartPoint
+{
+    pub period_start: NaiveDate,
+    pub total_dosage: String,
+    pub total_dosage_mg: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChartViewModel
+{
+    pub substance_name: String,
+    pub period: Period,
+    pub series: Vec<ChartPoint>,
+    /// Amount of ingestions by day of week (starting from Monday) and hour of
+    /// day.
+    pub usage_grid: UsageGrid,
+}
+
+impl Tabled for ChartViewModel
+{
+    const LENGTH: usize = 2;
+
+    fn fields(&self) -> Vec<Cow<'_, str>>
+    {
+        vec![
+            Cow::Borrowed(self.substance_name.as_str()),
+            Cow::Owned(self.series.len().to_string()),
+        ]
+    }
+
+    fn headers() -> Vec<Cow<'static, str>>
+    {
+        vec![Cow::Borrowed("Substance"), Cow::Borrowed("Periods")]
+    }
+}
+
+impl ChartViewModel
+{
+    fn render_dosage_chart(&self) -> String
+    {
+        // Every bar spans from its own point to the next one, so the series is
+        // closed with an empty point to give the last period its width.
+        let points: Vec<(f32, f32)> = self
+            .series
+            .iter()
+            .enumerate()
+            .map(|(index, point)| (index as f32, point.total_dosage_mg as f32))
+            .chain(std::iter::once((self.series.len() as f32, 0.0)))
+            .collect();
+
+        let terminal_width = crossterm::terminal::size()
+            .map(|(columns, _)| columns as u32)
+            .unwrap_or(80);
+        let width = (terminal_width.saturating_sub(16) * 2).clamp(32, 240);
+        let x_max = self.series.len() as f32;
+
+        let labels: Vec<String> = self
+            .series
+            .iter()
+            .map(|point| match self.period
+            {
+                | Period::Month => point.period_start.format("%Y-%m").to_string(),
+                | _ => point.period_start.format("%Y-%m-%d").to_string(),
+            })
+            .collect();
+
+        let unit = MessageFormat::Pretty.mass_unit();
+        let shape = Shape::Bars(&points);
+        let mut chart = Chart::new_with_y_range(width, 40, 0.0, x_max, 0.0, max_y(&points));
+        let chart = chart
+            .lineplot(&shape)
+            .x_label_format(LabelFormat::Custom(Box::new(move |x| {
+                let index = (x.round() as usize).min(labels.len().saturating_sub(1));
+                labels.get(index).cloned().unwrap_or_default()
+            })))
+            .y_label_format(LabelFormat::Custom(Box::new(move |y| match y
+            {
+                | y if y <= 0.0 => "0 mg".to_string(),
+                | y => Dosage::from_miligrams(y as f64).display_in(unit),
+            })));
+
+        chart.axis();
+        chart.figures();
+        chart.to_string()
+    }
+
+    fn render_usage_grid(&self) -> String
+    {
+        const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+        let max = self.usage_grid.max();
+        let mut output = String::from("    ");
+
+        for hour in (0..24).step_by(3)
+        {
+            output.push_str(&format!("{:<9}", format!("{:02}", hour)));
+        }
+        output.push('\n');
+
+        for (weekday, hours) in WEEKDAYS.iter().zip(self.usage_grid.0.iter())
+        {
+            output.push_str(&format!("{} ", weekday));
+
+            for count in hours
+            {
+                output.push_str(&heat_cell(*count as f64, max as f64, 2));
+                output.push(' ');
+            }
+
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+/// Single cell of a heatmap, empty cells are dimmed and the rest is split
+/// into four levels relative to the highest value in the heatmap.
+pub(super) fn heat_cell(value: f64, max: f64, width: usize) -> String
+{
+    const SHADES: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+
+    let level = match value
+    {
+        | value if value <= 0.0 || max <= 0.0 => 0,
+        | value => ((value * 4.0 / max).ceil() as usize).clamp(1, 4),
+    };
+    let cell = SHADES[level].repeat(width);
+
+    match level
+    {
+        | 0 => cell.dimmed().to_string(),
+        | 1 => cell.green().to_string(),
+        | 2 => cell.yellow().to_string(),
+        | 3 => cell.bright_red().to_string(),
+        | _ => cell.red().to_string(),
+    }
+}
+
+fn max_y(points: &[(f32, f32)]) -> f32
+{
+    points
+        .iter()
+        .map(|(_, y)| *y)
+        .fold(0.0, f32::max)
+        .max(1.0)
+}
+
+impl Formatter for ChartViewModel
+{
+    fn format(&self, format: MessageFormat) -> String
+    {
+        match format
+        {
+            | MessageFormat::Pretty => self.pretty(),
+            | MessageFormat::Json => self.json(),
+            | MessageFormat::Ndjson => self.ndjson(),
+        }
+    }
+
+    fn pretty(&self) -> String
+    {
+        let period = match self.period
+        {
+            | Period::Day => "day",
+            | Period::Week => "week",
+            | Period::Month => "month",
+        };
+
+        format!(
+            "{}\n\n{}\n{}\n\n{}",
+            format!("Total dosage of {} per {}", self.substance_name, period).bold(),
+            self.render_dosage_chart(),
+            "Ingestions by day of week and hour of day".bold(),
+            self.render_usage_grid()
+        )
+    }
+}
@@ -0,0 +1,131 @@
+use crate::cli::MessageFormat;
+use crate::cli::formatter::Formatter;
+use crate::cli::formatter::FormatterVector;
+use crate::core::CommandHandler;
+use crate::core::config::Config;
+use crate::utils::AppContext;
+use async_trait::async_trait;
+use clap::Parser;
+use clap::Subcommand;
+use miette::miette;
+use serde::Serialize;
+use std::env;
+use tabled::Tabled;
+
+/// Print value of a configuration key
+#[derive(Debug, Parser)]
+pub struct GetConfig
+{
+    /// Key of the setting, e.g. "default_route" or "units.mass"
+    #[arg(value_name = "KEY")]
+    key: String,
+}
+
+/// Change value of a configuration key in the configuration file
+#[derive(Debug, Parser)]
+pub struct SetConfig
+{
+    /// Key of the setting, e.g. "default_route" or "units.mass"
+    #[arg(value_name = "KEY")]
+    key: String,
+    /// New value, an empty value restores the default one
+    #[arg(value_name = "VALUE")]
+    value: String,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommands
+{
+    Get(GetConfig),
+    Set(SetConfig),
+    /// List all settings together with environment variables overriding them
+    List,
+    /// Print location of the configuration file
+    Path,
+}
+
+#[derive(Debug, Parser)]
+#[command(about = "Manage configuration")]
+pub struct ConfigCommand
+{
+    #[command(subcommand)]
+    commands: ConfigCommands,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct ConfigEntryViewModel
+{
+    pub key: String,
+    #[tabled(display_with = "display_value")]
+    pub value: Option<String>,
+    /// Environment variable which overrides the value.
+    pub environment_variable: String,
+    /// Whether the environment variable is currently set.
+    pub overridden: bool,
+}
+
+fn display_value(value: &Option<String>) -> String { value.clone().unwrap_or_default() }
+
+impl Formatter for ConfigEntryViewModel {}
+
+#[async_trait]
+impl CommandHandler for ConfigCommand
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        match &self.commands
+        {
+            | ConfigCommands::Get(command) =>
+            {
+                let value = Config::current().get(&command.key)?;
+
+                match ctx.stdout_format
+                {
+                    | MessageFormat::Pretty => println!("{}", value.unwrap_or_default()),
+                    | _ => println!("{}", serde_json::to_string(&value).unwrap()),
+                }
+            }
+            | ConfigCommands::Set(command) =>
+            {
+                // Only the configuration file is changed, values coming from
+                // environment variables or flags are not persisted.
+                let mut config = Config::load_file()?;
+                config.set(&command.key, &command.value)?;
+                config.save()?;
+
+                let variable = Config::environment_variable(&command.key);
+
+                if env::var_os(&variable).is_some()
+                {
+                    eprintln!("Warning: {} is set and overrides {}", variable, command.key);
+                }
+            }
+            | ConfigCommands::List =>
+            {
+                let config = Config::current();
+                let entries = Config::KEYS
+                    .iter()
+                    .map(|key| {
+                        let environment_variable = Config::environment_variable(key);
+
+                        Ok(ConfigEntryViewModel {
+                            key: key.to_string(),
+                            value: config.get(key)?,
+                            overridden: env::var_os(&environment_variable).is_some(),
+                            environment_variable,
+                        })
+                    })
+                    .collect::<miette::Result<Vec<_>>>()?;
+
+                println!("{}", FormatterVector::new(entries).format(ctx.stdout_format));
+            }
+            | ConfigCommands::Path => match Config::path().to_str()
+            {
+                | Some(path) => println!("{}", path),
+                | None => return Err(miette!("Configuration path is not valid UTF-8")),
+            },
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+use crate::cli::formatter::FormatterVector;
+use crate::cli::formatter::Formatter;
+use crate::core::CommandHandler;
+use crate::core::config::Config;
+use crate::database::Migrator;
+use crate::database::maintenance;
+use crate::database::maintenance::CheckResult;
+use crate::database::migrator::SQL_MIGRATIONS;
+use crate::database::migrator::SqlMigration;
+use crate::database::migrator::find_migration;
+use crate::utils::AppContext;
+use crate::utils::check_schema_version;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Local;
+use clap::Parser;
+use clap::Subcommand;
+use miette::IntoDiagnostic;
+use miette::miette;
+use sea_orm::DatabaseConnection;
+use sea_orm_migration::MigrationName;
+use sea_orm_migration::MigrationStatus;
+use sea_orm_migration::MigratorTrait;
+use serde::Serialize;
+use std::path::PathBuf;
+use tabled::Tabled;
+
+/// Create a consistent copy of the journal, even while it's in use
+#[derive(Debug, Parser)]
+pub struct BackupDatabase
+{
+    /// Backup file, by default it's created in "backups" directory next to
+    /// the journal
+    #[arg(value_name = "PATH")]
+    path: Option<PathBuf>,
+}
+
+/// Replace the journal with a backup, current journal is backed up first
+#[derive(Debug, Parser)]
+pub struct RestoreDatabase
+{
+    #[arg(value_name = "PATH")]
+    path: PathBuf,
+}
+
+/// Apply pending migrations
+#[derive(Debug, Parser)]
+pub struct MigrateUp
+{
+    /// Last migration to apply, all pending migrations are applied by default
+    #[arg(long, value_name = "NAME")]
+    to: Option<String>,
+}
+
+/// Revert applied migrations, journal is backed up first
+#[derive(Debug, Parser)]
+pub struct MigrateDown
+{
+    /// Migration which stays applied, only the last migration is reverted by
+    /// default
+    #[arg(long, value_name = "NAME")]
+    to: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum MigrateCommands
+{
+    /// List migrations and whether they're applied
+    Status,
+    Up(MigrateUp),
+    Down(MigrateDown),
+}
+
+#[derive(Debug, Parser)]
+pub struct MigrateDatabase
+{
+    #[command(subcommand)]
+    commands: MigrateCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum DatabaseCommands
+{
+    Backup(BackupDatabase),
+    Restore(RestoreDatabase),
+    /// Check integrity of the journal and consistency of its data
+    Check,
+    /// Show, apply and revert schema migrations
+    Migrate(MigrateDatabase),
+}
+
+#[derive(Debug, Parser)]
+#[command(about = "Back up, restore, check and migrate the journal database")]
+pub struct DatabaseCommand
+{
+    #[command(subcommand)]
+    commands: DatabaseCommands,
+}
+
+impl DatabaseCommand
+{
+    pub fn manages_migrations(&self) -> bool
+    {
+        matches!(self.commands, DatabaseCommands::Migrate(_))
+    }
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct MigrationViewModel
+{
+    pub name: String,
+    pub applied: bool,
+    #[tabled(display_with = "display_applied_at")]
+    pub applied_at: Option<DateTime<Local>>,
+    /// Whether the migration has a down migration.
+    pub reversible: bool,
+}
+
+fn display_applied_at(applied_at: &Option<DateTime<Local>>) -> String
+{
+    applied_at
+        .map(|applied_at| applied_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+impl Formatter for CheckResult {}
+impl Formatter for MigrationViewModel {}
+
+/// Names of known migrations which are applied, in order of application.
+async fn applied_migrations(database_connection: &DatabaseConnection) -> miette::Result<Vec<String>>
+{
+    Ok(Migrator::get_applied_migrations(database_connection)
+        .await
+        .into_diagnostic()?
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect())
+}
+
+fn unknown_migration(name: &str) -> miette::Report
+{
+    miette!(
+        help = "See `neuronek db migrate status` for names of migrations",
+        "Unknown migration \"{}\"",
+        name
+    )
+}
+
+impl MigrateDatabase
+{
+    async fn handle(&self, ctx: AppContext<'_>) -> miette::Result<()>
+    {
+        let database_connection = ctx.database_connection;
+
+        match &self.commands
+        {
+            | MigrateCommands::Status =>
+            {
+                let applied_at = Migrator::get_migration_models(database_connection)
+                    .await
+                    .into_diagnostic()?;
+
+                let mut migrations: Vec<MigrationViewModel> = SQL_MIGRATIONS
+                    .iter()
+                    .map(|migration| {
+                        let applied_at = applied_at
+                            .iter()
+                            .find(|model| model.version == migration.name())
+                            .and_then(|model| DateTime::from_timestamp(model.applied_at, 0))
+                            .map(|applied_at| applied_at.with_timezone(&Local));
+
+                        MigrationViewModel {
+                            name: migration.name().to_string(),
+                            applied: applied_at.is_some(),
+                            applied_at,
+                            reversible: migration.is_reversible(),
+                        }
+                    })
+                    .collect();
+
+                // Migrations applied by a newer version of neuronek.
+                migrations.extend(
+                    applied_at
+                        .iter()
+                        .filter(|model| find_migration(&model.version).is_none())
+                        .map(|model| MigrationViewModel {
+                            name: model.version.clone(),
+                            applied: true,
+                            applied_at: DateTime::from_timestamp(model.applied_at, 0)
+                                .map(|applied_at| applied_at.with_timezone(&Local)),
+                            reversible: false,
+                        }),
+                );
+
+                println!("{}", FormatterVector::new(migrations).format(ctx.stdout_format));
+            }
+            | MigrateCommands::Up(command) =>
+            {
+                check_schema_version(database_connection).await?;
+
+                let pending: Vec<String> = Migrator::get_pending_migrations(database_connection)
+                    .await
+                    .into_diagnostic()?
+                    .iter()
+                    .map(|migration| migration.name().to_string())
+                    .collect();
+
+                let steps = match &command.to
+                {
+                    | None => pending.len(),
+                    | Some(name) => match pending.iter().position(|pending| pending == name)
+                    {
+                        | Some(index) => index + 1,
+                        | None if find_migration(name).is_some() =>
+                        {
+                            return Err(miette!("Migration {} is already applied", name));
+                        }
+                        | None => return Err(unknown_migration(name)),
+                    },
+                };
+
+                if steps == 0
+                {
+                    println!("No pending migrations");
+                    return Ok(());
+                }
+
+                if !applied_migrations(database_connection).await?.is_empty()
+                {
+                    maintenance::migration_backup(
+                        database_connection,
+                        &Config::current().sqlite_path,
+                    )
+                    .await?;
+                }
+
+                Migrator::up(database_connection, Some(steps as u32))
+                    .await
+                    .into_diagnostic()?;
+
+                let applied = applied_migrations(database_connection).await?;
+
+                println!("Applied {} migrations, schema version is {}", steps, applied.len());
+            }
+            | MigrateCommands::Down(command) =>
+            {
+                check_schema_version(database_connection).await?;
+
+                let applied = applied_migrations(database_connection).await?;
+
+                let steps = match &command.to
+                {
+                    | None => applied.len().min(1),
+                    | Some(name) => match applied.iter().position(|applied| applied == name)
+                    {
+                        | Some(index) => applied.len() - index - 1,
+                        | None if find_migration(name).is_some() =>
+                        {
+                            return Err(miette!("Migration {} is not applied", name));
+                        }
+                        | None => return Err(unknown_migration(name)),
+                    },
+                };
+
+                if steps == 0
+                {
+                    println!("No migrations to revert");
+                    return Ok(());
+                }
+
+                // Check everything up front, so the journal isn't left
+                // half-way reverted.
+                if let Some(irreversible) = applied
+                    .iter()
+                    .rev()
+                    .take(steps)
+                    .find(|name| !find_migration(name).is_some_and(SqlMigration::is_reversible))
+                {
+                    return Err(miette!(
+                        help = "Migrations marked as not reversible in `neuronek db migrate \
+                                status` can't be reverted",
+                        "Migration {} can't be reverted",
+                        irreversible
+                    ));
+                }
+
+                let backup = maintenance::backup_path(
+                    &Config::current().sqlite_path,
+                    "pre-migration-down",
+                );
+                maintenance::backup(database_connection, &backup).await?;
+
+                Migrator::down(database_connection, Some(steps as u32))
+                    .await
+                    .into_diagnostic()?;
+
+                let version = applied.len() - steps;
+
+                println!(
+                    "Reverted {} migrations, schema version is {}, previous journal was saved to {}",
+                    steps,
+                    version,
+                    backup.display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CommandHandler for DatabaseCommand
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        let database_path = Config::current().sqlite_path;
+
+        match &self.commands
+        {
+            | DatabaseCommands::Backup(command) =>
+            {
+                let path = command
+                    .path
+                    .clone()
+                    .unwrap_or_else(|| maintenance::backup_path(&database_path, "backup"));
+
+                maintenance::backup(ctx.database_connection, &path).await?;
+                println!("{}", path.display());
+            }
+            | DatabaseCommands::Restore(command) =>
+            {
+                let safety_backup =
+                    maintenance::restore(ctx.database_connection, &database_path, &command.path)
+                        .await?;
+
+                println!(
+                    "Restored {}, previous journal was saved to {}",
+                    command.path.display(),
+                    safety_backup.display()
+                );
+            }
+            | DatabaseCommands::Check =>
+            {
+                let results = maintenance::check(ctx.database_connection).await?;
+                let failed = results.iter().filter(|result| !result.passed).count();
+
+                println!("{}", FormatterVector::new(results).format(ctx.stdout_format));
+
+                if failed > 0
+                {
+                    return Err(miette!("{} database checks failed", failed));
+                }
+            }
+            | DatabaseCommands::Migrate(command) => command.handle(ctx).await?,
+        }
+
+        Ok(())
+    }
+}
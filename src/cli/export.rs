@@ -0,0 +1,124 @@
+use crate::core::CommandHandler;
+use crate::database::export;
+use crate::utils::AppContext;
+use crate::utils::parse_date_string;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Local;
+use clap::Parser;
+use clap::ValueEnum;
+use miette::IntoDiagnostic;
+use miette::miette;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat
+{
+    /// Single JSON document with ingestions and their phases
+    #[default]
+    Json,
+    /// Table with one row per ingestion phase
+    Csv,
+    /// SQL script which can be loaded with `sqlite3`
+    Sql,
+    /// iCalendar file with an event for every ingestion
+    Ical,
+}
+
+/// Export the whole journal (or its part) for archiving or analysis in
+/// external tools.
+///
+/// Exported ingestions include their phases and classifications, the format
+/// of every export is versioned and described in `docs/export.md`. iCalendar
+/// export (`neuronek export ical`) puts the timeline into calendar apps.
+#[derive(Parser, Debug)]
+#[command(version, about = "Export ingestion journal", long_about)]
+pub struct ExportJournal
+{
+    /// Format of the export, JSON by default
+    #[arg(value_enum, value_name = "FORMAT")]
+    pub format: Option<ExportFormat>,
+
+    /// Add every ingestion phase as a separate event (iCalendar only)
+    #[arg(long)]
+    pub phases: bool,
+
+    /// Export only ingestions that happened at or after given date
+    #[arg(long, value_name = "DATE", value_parser = parse_date_string)]
+    pub from: Option<DateTime<Local>>,
+
+    /// Export only ingestions that happened at or before given date
+    #[arg(long, value_name = "DATE", value_parser = parse_date_string)]
+    pub to: Option<DateTime<Local>>,
+
+    /// File to write the export to instead of standard output
+    #[arg(short = 'o', long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[async_trait]
+impl CommandHandler for ExportJournal
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        if let (Some(from), Some(to)) = (self.from, self.to)
+        {
+            if from > to
+            {
+                return Err(miette!(
+                    help = "Swap values of --from and --to",
+                    "Start date ({}) is after end date ({})",
+                    from.format("%Y-%m-%d %H:%M"),
+                    to.format("%Y-%m-%d %H:%M")
+                ));
+            }
+        }
+
+        let journal = export::export(ctx.database_connection, self.from, self.to).await?;
+
+        let format = self.format.unwrap_or_default();
+
+        let content = match format
+        {
+            | ExportFormat::Json => journal.to_json()?,
+            | ExportFormat::Csv => journal.to_csv(),
+            | ExportFormat::Sql => journal.to_sql(ctx.database_connection).await?,
+            | ExportFormat::Ical => journal.to_ical(self.phases),
+        };
+
+        // iCalendar already ends its lines with CRLF.
+        let content = match content.ends_with('\n')
+        {
+            | true => content,
+            | false => content + "\n",
+        };
+
+        match &self.output
+        {
+            | Some(path) =>
+            {
+                std::fs::write(path, &content).into_diagnostic()?;
+                eprintln!("Exported {} ingestions to {}", journal.ingestions.len(), path.display());
+            }
+            | None =>
+            {
+                let mut stdout = std::io::stdout().lock();
+
+                // Export is often piped, e.g. to `head`, which may close it early.
+                match write!(stdout, "{}", content).and_then(|_| stdout.flush())
+                {
+                    | Err(error) if error.kind() != ErrorKind::BrokenPipe =>
+                    {
+                        return Err(error).into_diagnostic();
+                    }
+                    | _ =>
+                    {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
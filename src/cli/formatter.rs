@@ -1,5 +1,10 @@
 use crate::cli::MessageFormat;
+use futures::Stream;
+use futures::StreamExt;
+use miette::IntoDiagnostic;
 use serde::Serialize;
+use std::io::ErrorKind;
+use std::io::Write;
 use tabled::Table;
 use tabled::Tabled;
 
@@ -11,6 +16,7 @@ pub trait Formatter: Serialize + Tabled + Sized
         {
             | MessageFormat::Pretty => self.pretty(),
             | MessageFormat::Json => self.json(),
+            | MessageFormat::Ndjson => self.ndjson(),
         }
     }
 
@@ -20,6 +26,11 @@ pub trait Formatter: Serialize + Tabled + Sized
             .unwrap_or_else(|_| "Error serializing to JSON".to_string())
     }
 
+    fn ndjson(&self) -> String
+    {
+        serde_json::to_string(self).unwrap_or_else(|_| "Error serializing to JSON".to_string())
+    }
+
     fn pretty(&self) -> String
     {
         Table::new(std::iter::once(self))
@@ -44,6 +55,44 @@ impl<T: Formatter> FormatterVector<T>
                 .to_string(),
             | MessageFormat::Json => serde_json::to_string_pretty(&self.0)
                 .unwrap_or_else(|_| "Error serializing to JSON".to_string()),
+            | MessageFormat::Ndjson => self
+                .0
+                .iter()
+                .map(Formatter::ndjson)
+                .collect::<Vec<_>>()
+                .join("\n"),
         }
     }
 }
+
+/// Write every item produced by the stream to `stdout` as a single line of
+/// compact JSON, flushing after each line so consumers (e.g. `jq`) can start
+/// processing before the stream is exhausted.
+///
+/// Stream stops silently when the reading side of the pipe is closed (e.g.
+/// `neuronek ... | head`), as it's not an error from the user's perspective.
+pub async fn stream_ndjson<T, E, S>(stream: S) -> miette::Result<()>
+where
+    T: Formatter,
+    E: std::error::Error + Send + Sync + 'static,
+    S: Stream<Item = Result<T, E>>,
+{
+    let mut stream = std::pin::pin!(stream);
+    let stdout = std::io::stdout();
+
+    while let Some(item) = stream.next().await
+    {
+        let line = item.into_diagnostic()?.ndjson();
+        let mut handle = stdout.lock();
+
+        match writeln!(handle, "{}", line).and_then(|_| handle.flush())
+        {
+            | Ok(_) =>
+            {}
+            | Err(error) if error.kind() == ErrorKind::BrokenPipe => return Ok(()),
+            | Err(error) => return Err(error).into_diagnostic(),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use crate::cli::formatter::Formatter;
+use crate::cli::formatter::FormatterVector;
+use crate::core::CommandHandler;
+use crate::database::entities::solution;
+use crate::database::import;
+use crate::database::import::ImportedIngestion;
+use crate::database::import::ImportedSolution;
+use crate::database::psychonautwiki;
+use crate::ingestion::LogIngestion;
+use crate::ingestion::service::IngestionService;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+use crate::substance::route_of_administration::dosage::MassUnit;
+use crate::utils::AppContext;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Local;
+use clap::Parser;
+use clap::ValueEnum;
+use miette::IntoDiagnostic;
+use miette::WrapErr;
+use miette::miette;
+use sea_orm::ActiveModelTrait;
+use sea_orm::ActiveValue;
+use sea_orm::ColumnTrait;
+use sea_orm::ConnectionTrait;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::TransactionTrait;
+use serde::Serialize;
+use std::path::PathBuf;
+use tabled::Tabled;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat
+{
+    /// JSON export of neuronek
+    Json,
+    /// CSV export of neuronek
+    Csv,
+    /// JSON export of the PsychonautWiki Journal app
+    Psychonautwiki,
+}
+
+/// Import ingestions from a file created by `neuronek export` or by the
+/// PsychonautWiki Journal app.
+///
+/// Ingestions already present in the journal (same substance, dosage and
+/// time of ingestion) are skipped. Every imported ingestion is analyzed
+/// again, so its phases and dosage classification are computed from current
+/// substance data.
+#[derive(Parser, Debug)]
+#[command(version, about = "Import ingestions from an export", long_about)]
+pub struct ImportJournal
+{
+    /// JSON or CSV file created by `neuronek export`, or JSON file exported
+    /// from the PsychonautWiki Journal app
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Format of the file, by default it's chosen by the file extension and
+    /// content
+    #[arg(long = "as", value_name = "FORMAT", value_enum)]
+    pub format: Option<ImportFormat>,
+
+    /// Only print what would be imported without changing the journal
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct ImportViewModel
+{
+    pub substance_name: String,
+    pub dosage: String,
+    pub route: String,
+    pub ingested_at: DateTime<Local>,
+    /// Whether the ingestion is already in the journal.
+    pub duplicate: bool,
+}
+
+impl Formatter for ImportViewModel {}
+
+impl ImportViewModel
+{
+    fn new(ingestion: &ImportedIngestion, unit: MassUnit) -> Self
+    {
+        Self {
+            substance_name: ingestion.substance_name.clone(),
+            dosage: ingestion
+                .amount
+                .as_ref()
+                .map_or(ingestion.dosage.display_in(unit), |amount| {
+                    amount.display_with_mass(&ingestion.dosage, unit)
+                }),
+            route: ingestion.route.to_string(),
+            ingested_at: ingestion.ingested_at,
+            duplicate: false,
+        }
+    }
+}
+
+impl ImportJournal
+{
+    fn format(&self, content: &str) -> miette::Result<ImportFormat>
+    {
+        if let Some(format) = self.format
+        {
+            return Ok(format);
+        }
+
+        match self.file.extension().and_then(|extension| extension.to_str())
+        {
+            | Some("json") if psychonautwiki::is_journal_app_export(content) =>
+            {
+                Ok(ImportFormat::Psychonautwiki)
+            }
+            | Some("json") => Ok(ImportFormat::Json),
+            | Some("csv") => Ok(ImportFormat::Csv),
+            | _ => Err(miette!(
+                help = "Choose the format with --as",
+                "Can't tell format of {}",
+                self.file.display()
+            )),
+        }
+    }
+}
+
+/// Id of the solution with the same name in the journal, or of a new one
+/// created with the imported concentration when there is none.
+async fn link_solution(
+    database_connection: &impl ConnectionTrait,
+    imported: &ImportedSolution,
+) -> miette::Result<i32>
+{
+    let existing = solution::Entity::find()
+        .filter(solution::Column::Name.eq(imported.name.trim()))
+        .one(database_connection)
+        .await
+        .into_diagnostic()?;
+
+    if let Some(existing) = existing
+    {
+        return Ok(existing.id);
+    }
+
+    let created = solution::ActiveModel {
+        id: ActiveValue::NotSet,
+        name: ActiveValue::Set(imported.name.trim().to_string()),
+        substance_name: ActiveValue::Set(imported.substance_name.to_lowercase()),
+        dissolved_dosage: ActiveValue::Set(imported.dissolved.as_base_units()),
+        solvent_volume: ActiveValue::Set(imported.volume),
+        created_at: ActiveValue::Set(Local::now().to_utc().naive_local()),
+        updated_at: ActiveValue::Set(Local::now().to_utc().naive_local()),
+    }
+    .insert(database_connection)
+    .await
+    .into_diagnostic()?;
+
+    Ok(created.id)
+}
+
+#[async_trait]
+impl CommandHandler for ImportJournal
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        let content = std::fs::read_to_string(&self.file)
+            .into_diagnostic()
+            .wrap_err(format!("Can't read {}", self.file.display()))?;
+
+        // Whole file is read and validated before anything is imported.
+        let ingestions = match self.format(&content)?
+        {
+            | ImportFormat::Json => import::from_json(&content)?,
+            | ImportFormat::Csv => import::from_csv(&content)?,
+            | ImportFormat::Psychonautwiki =>
+            {
+                let import = psychonautwiki::from_journal_app(&content)?
+                    .convert_amounts(ctx.database_connection)
+                    .await?;
+
+                for skipped in &import.skipped
+                {
+                    eprintln!("Skipped {}: {}", skipped.entry, skipped.reason);
+                }
+
+                if !import.skipped.is_empty()
+                {
+                    eprintln!("{} entries can't be imported", import.skipped.len());
+                }
+
+                if import.dropped_notes > 0
+                {
+                    eprintln!(
+                        "Notes of {} ingestions are not imported, neuronek doesn't store notes of \
+                         ingestions",
+                        import.dropped_notes
+                    );
+                }
+
+                import.ingestions
+            }
+        };
+
+        let mut known = import::existing_keys(ctx.database_connection).await?;
+        let mut entries = vec![];
+        let mut analyzed = vec![];
+
+        for ingestion in &ingestions
+        {
+            // Duplicates within the file itself are skipped as well.
+            let duplicate = !known.insert(ingestion.duplicate_key());
+
+            if !duplicate && !self.dry_run
+            {
+                // Substances are read through the shared connection, which the
+                // transaction below holds until it's committed.
+                let analysis = IngestionService::analyze(
+                    ctx.database_connection,
+                    &ingestion.substance_name,
+                    &ingestion.dosage,
+                    ingestion.route,
+                    ingestion.ingested_at,
+                )
+                .await?;
+
+                analyzed.push((ingestion, analysis));
+            }
+
+            entries.push(ImportViewModel {
+                duplicate,
+                ..ImportViewModel::new(ingestion, ctx.stdout_format.mass_unit())
+            });
+        }
+
+        // Import either succeeds as a whole or leaves the journal unchanged,
+        // otherwise retrying it would skip already imported ingestions as
+        // duplicates.
+        let transaction = ctx.database_connection.begin().await.into_diagnostic()?;
+
+        for (ingestion, analysis) in analyzed
+        {
+            let command = LogIngestion {
+                substance_name: ingestion.substance_name.clone(),
+                dosage: ingestion
+                    .amount
+                    .clone()
+                    .unwrap_or(ingestion.dosage.map(|dosage| Amount::Mass(*dosage))),
+                ingestion_date: ingestion.ingested_at,
+                route_of_administration: ingestion.route,
+                solution: ingestion.solution.as_ref().map(|solution| solution.name.clone()),
+            };
+
+            let solution_id = match &ingestion.solution
+            {
+                | Some(solution) => Some(link_solution(&transaction, solution).await?),
+                | None => None,
+            };
+
+            IngestionService::record(
+                &transaction,
+                &command,
+                &ingestion.substance_name,
+                ingestion.dosage.clone(),
+                solution_id,
+                analysis,
+            )
+            .await?;
+        }
+
+        transaction.commit().await.into_diagnostic()?;
+
+        let duplicates = entries.iter().filter(|entry| entry.duplicate).count();
+
+        if self.dry_run
+        {
+            println!("{}", FormatterVector::new(entries).format(ctx.stdout_format));
+            eprintln!(
+                "Would import {} ingestions, {} duplicates would be skipped",
+                ingestions.len() - duplicates,
+                duplicates
+            );
+        }
+        else
+        {
+            eprintln!(
+                "Imported {} ingestions, skipped {} duplicates",
+                ingestions.len() - duplicates,
+                duplicates
+            );
+        }
+
+        Ok(())
+    }
+}
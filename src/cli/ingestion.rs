@@ -1,6 +1,7 @@
 use crate::cli::MessageFormat;
 use crate::cli::formatter::Formatter;
 use crate::cli::formatter::FormatterVector;
+use crate::cli::formatter::stream_ndjson;
 use crate::core::CommandHandler;
 use crate::core::QueryHandler;
 use crate::database::entities::ingestion;
@@ -8,12 +9,25 @@ use crate::database::entities::ingestion::Entity as Ingestion;
 use crate::database::entities::ingestion::Model;
 use crate::database::entities::ingestion_phase;
 use crate::database::entities::ingestion_phase::Entity as IngestionPhase;
+use crate::ingestion::body_weight::body_weight_at;
+use crate::ingestion::body_weight::display_per_kg;
+use crate::ingestion::body_weight::display_weight;
 use crate::ingestion::command::LogIngestion;
+use crate::ingestion::model::display_classification;
 use crate::ingestion::query::AnalyzeIngestion;
 use crate::ingestion::service::IngestionService;
+use crate::ingestion::service::stored_route;
 use crate::substance::repository::get_substance;
 use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::bioavailability::display_bioavailability;
+use crate::substance::conversion;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::DisplayMass;
 use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::DosageClassification;
+use crate::substance::route_of_administration::dosage::DosageEstimate;
+use crate::substance::route_of_administration::dosage::Estimate;
+use crate::substance::route_of_administration::dosage::MassUnit;
 use crate::utils::AppContext;
 use crate::utils::DATABASE_CONNECTION;
 use crate::utils::parse_date_string;
@@ -30,6 +44,7 @@ use chrono_humanize::Humanize;
 use chrono_humanize::Tense;
 use clap::Parser;
 use clap::Subcommand;
+use futures::StreamExt;
 use miette::IntoDiagnostic;
 use miette::miette;
 use owo_colors::OwoColorize;
@@ -37,10 +52,17 @@ use owo_colors::style;
 use sea_orm::ActiveModelTrait;
 use sea_orm::ActiveValue;
 use sea_orm::ColumnTrait;
+use sea_orm::Condition;
 use sea_orm::EntityTrait;
 use sea_orm::QueryFilter;
 use sea_orm::QueryOrder;
 use sea_orm::QuerySelect;
+use sea_orm::Select;
+use sea_orm::TransactionTrait;
+use sea_orm::sea_query::Expr;
+use sea_orm::sea_query::Func;
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::Order;
 use sea_orm_migration::IntoSchemaManagerConnection;
 use serde::Deserialize;
 use serde::Serialize;
@@ -72,9 +94,15 @@ pub struct UpdateIngestion
     #[arg(short = 'n', long = "name", value_name = "SUBSTANCE_NAME")]
     pub substance_name: Option<String>,
 
-    /// New dosage (optional, e.g., 20 mg)
-    #[arg(short = 'd', long = "dosage", value_name = "DOSAGE", value_parser=Dosage::from_str)]
-    pub dosage: Option<Dosage>,
+    /// New dosage (optional, e.g., 20 mg, 5 ml, 2 tablets, ~20 mg, 10-20 mg or
+    /// unknown)
+    #[arg(
+        short = 'd',
+        long = "dosage",
+        value_name = "DOSAGE",
+        value_parser = Estimate::<Amount>::from_str
+    )]
+    pub dosage: Option<Estimate<Amount>>,
 
     /// New ingestion date (optional, e.g., "today 10:00")
     #[arg(short = 't', long = "date", value_name = "INGESTION_DATE", value_parser=parse_date_string
@@ -91,17 +119,46 @@ impl CommandHandler for UpdateIngestion
 {
     async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
     {
-        if Ingestion::find_by_id(self.ingestion_identifier)
+        let ingestion = Ingestion::find_by_id(self.ingestion_identifier)
             .one(ctx.database_connection)
             .await
             .into_diagnostic()?
-            .is_none()
+            .ok_or_else(|| miette!("Ingestion with ID {} not found", self.ingestion_identifier))?;
+
+        let dosage = match &self.dosage
         {
-            return Err(miette!(
-                "Ingestion with ID {} not found",
-                self.ingestion_identifier
-            ));
-        }
+            // Ingestions of a solution keep concentration they were logged with
+            | Some(amount @ Estimate::Exact(Amount::Volume(_)))
+            | Some(amount @ Estimate::Approximate(Amount::Volume(_)))
+            | Some(amount @ Estimate::Range(Amount::Volume(_), _))
+                if ingestion.solution_id.is_some()
+                    && ingestion.dosage.is_some()
+                    && ingestion.amount.is_some_and(|amount| amount > 0.0) =>
+            {
+                let concentration = ingestion.dosage.unwrap() as f64 / ingestion.amount.unwrap();
+                let dosage = amount
+                    .map(|volume| Dosage::from_base_units(concentration * volume.value()));
+
+                Some((dosage, amount.to_stored()))
+            }
+            | Some(amount) =>
+            {
+                let substance_name =
+                    self.substance_name.as_ref().unwrap_or(&ingestion.substance_name);
+                let dosage = conversion::to_dosage_estimate(
+                    ctx.database_connection,
+                    &[substance_name],
+                    amount,
+                )
+                .await?;
+
+                Some((dosage, amount.to_stored()))
+            }
+            | None => None,
+        };
+
+        let stored_dosage = dosage.as_ref().map(|(dosage, _)| dosage.to_stored());
+        let stored_amount = dosage.as_ref().map(|(_, amount)| amount.clone());
 
         let updated_model = ingestion::ActiveModel {
             id: ActiveValue::Set(self.ingestion_identifier),
@@ -110,15 +167,30 @@ impl CommandHandler for UpdateIngestion
                 .as_ref()
                 .map(|name| ActiveValue::Set(name.clone()))
                 .unwrap_or(ActiveValue::NotSet),
-            dosage: self
-                .dosage
+            dosage: stored_dosage
+                .map(|(dosage, _, _)| ActiveValue::Set(dosage))
+                .unwrap_or(ActiveValue::NotSet),
+            dosage_max: stored_dosage
+                .map(|(_, dosage_max, _)| ActiveValue::Set(dosage_max))
+                .unwrap_or(ActiveValue::NotSet),
+            dosage_approximate: stored_dosage
+                .map(|(_, _, approximate)| ActiveValue::Set(approximate))
+                .unwrap_or(ActiveValue::NotSet),
+            amount: stored_amount
                 .as_ref()
-                .map(|dosage| ActiveValue::Set(dosage.as_base_units() as f32))
+                .map(|(amount, _, _)| ActiveValue::Set(*amount))
+                .unwrap_or(ActiveValue::NotSet),
+            amount_max: stored_amount
+                .as_ref()
+                .map(|(_, amount_max, _)| ActiveValue::Set(*amount_max))
+                .unwrap_or(ActiveValue::NotSet),
+            amount_unit: stored_amount
+                .map(|(_, _, unit)| ActiveValue::Set(unit))
                 .unwrap_or(ActiveValue::NotSet),
             route_of_administration: self
                 .route_of_administration
                 .as_ref()
-                .map(|roa| ActiveValue::Set(serde_json::to_string(roa).unwrap()))
+                .map(|roa| ActiveValue::Set(stored_route(*roa)))
                 .unwrap_or(ActiveValue::NotSet),
             ingested_at: self
                 .ingestion_date
@@ -129,10 +201,58 @@ impl CommandHandler for UpdateIngestion
             ..Default::default()
         };
 
-        let updated_record = updated_model
-            .update(ctx.database_connection)
+        // Analysis depends on everything but the amount the dosage was logged
+        // in, so it's repeated when any of it changes.
+        let reanalyze = self.substance_name.is_some()
+            || self.dosage.is_some()
+            || self.ingestion_date.is_some()
+            || self.route_of_administration.is_some();
+
+        // Substances are read through the shared connection, so the analysis
+        // is done before the transaction holds it.
+        let analysis = match reanalyze
+        {
+            | true => Some(
+                IngestionService::analyze(
+                    ctx.database_connection,
+                    self.substance_name.as_ref().unwrap_or(&ingestion.substance_name),
+                    &dosage
+                        .as_ref()
+                        .map(|(dosage, _)| dosage.clone())
+                        .unwrap_or_else(|| dosage_estimate(&ingestion)),
+                    self.route_of_administration.unwrap_or(
+                        ingestion
+                            .route_of_administration
+                            .parse()
+                            .unwrap_or(RouteOfAdministrationClassification::Oral),
+                    ),
+                    self.ingestion_date
+                        .unwrap_or(Local.from_utc_datetime(&ingestion.ingested_at)),
+                )
+                .await?,
+            ),
+            | false => None,
+        };
+
+        let transaction = ctx.database_connection.begin().await.into_diagnostic()?;
+        updated_model.update(&transaction).await.into_diagnostic()?;
+
+        if let Some(analysis) = &analysis
+        {
+            IngestionService::store_analysis(
+                &transaction,
+                self.ingestion_identifier,
+                analysis.as_ref(),
+            )
+            .await?;
+        }
+
+        let updated_record = Ingestion::find_by_id(self.ingestion_identifier)
+            .one(&transaction)
             .await
-            .into_diagnostic()?;
+            .into_diagnostic()?
+            .ok_or_else(|| miette!("Ingestion with ID {} not found", self.ingestion_identifier))?;
+        transaction.commit().await.into_diagnostic()?;
 
         info!(
             "Successfully updated ingestion with ID {}.",
@@ -141,13 +261,77 @@ impl CommandHandler for UpdateIngestion
 
         println!(
             "{}",
-            IngestionViewModel::from(updated_record).format(ctx.stdout_format)
+            IngestionViewModel::from_model(updated_record, ctx.stdout_format.mass_unit())
+                .format(ctx.stdout_format)
         );
 
         Ok(())
     }
 }
 
+/// Column by which listed ingestions are ordered.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IngestionSortColumn
+{
+    Id,
+    Substance,
+    Route,
+    Dosage,
+    Classification,
+    /// Date of ingestion
+    #[default]
+    Date,
+    /// Date when ingestion was logged
+    CreatedAt,
+    /// Date when ingestion was last modified
+    UpdatedAt,
+}
+
+impl From<IngestionSortColumn> for ingestion::Column
+{
+    fn from(column: IngestionSortColumn) -> Self
+    {
+        match column
+        {
+            | IngestionSortColumn::Id => ingestion::Column::Id,
+            | IngestionSortColumn::Substance => ingestion::Column::SubstanceName,
+            | IngestionSortColumn::Route => ingestion::Column::RouteOfAdministration,
+            | IngestionSortColumn::Dosage => ingestion::Column::Dosage,
+            | IngestionSortColumn::Classification => ingestion::Column::DosageClassification,
+            | IngestionSortColumn::Date => ingestion::Column::IngestedAt,
+            | IngestionSortColumn::CreatedAt => ingestion::Column::CreatedAt,
+            | IngestionSortColumn::UpdatedAt => ingestion::Column::UpdatedAt,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortDirection
+{
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl From<SortDirection> for Order
+{
+    fn from(direction: SortDirection) -> Self
+    {
+        match direction
+        {
+            | SortDirection::Asc => Order::Asc,
+            | SortDirection::Desc => Order::Desc,
+        }
+    }
+}
+
+/// List ingestions matching the provided filters.
+///
+/// Every filter is optional and filters are combined with each other, so
+/// `--substance caffeine --min 50mg --from "last monday"` lists only caffeine
+/// ingestions of at least 50mg logged since last Monday. Filters which accept
+/// multiple values (substance, route, classification) match any of the
+/// provided values.
 #[derive(Parser, Debug)]
 #[command(version, about = "List all ingestions", long_about, aliases = vec!["ls", "get"])]
 pub struct ListIngestion
@@ -155,6 +339,197 @@ pub struct ListIngestion
     /// Defines the amount of ingestion to display
     #[arg(short = 'l', long, default_value_t = 10)]
     pub limit: u64,
+
+    /// Amount of matching ingestions to skip, used for pagination
+    #[arg(short = 'o', long, default_value_t = 0)]
+    pub offset: u64,
+
+    /// Show only ingestions of given substance (can be repeated)
+    #[arg(short = 's', long = "substance", value_name = "SUBSTANCE_NAME")]
+    pub substances: Vec<String>,
+
+    /// Show only ingestions with given route of administration (can be
+    /// repeated)
+    #[arg(short = 'r', long = "roa", value_enum)]
+    pub routes: Vec<RouteOfAdministrationClassification>,
+
+    /// Show only ingestions with given dosage classification (can be repeated)
+    #[arg(short = 'c', long = "classification", value_enum)]
+    pub classifications: Vec<DosageClassification>,
+
+    /// Show only ingestions that happened at or after given date (e.g.
+    /// "last monday", "2025-01-01")
+    #[arg(long, value_name = "DATE", value_parser = parse_date_string)]
+    pub from: Option<DateTime<Local>>,
+
+    /// Show only ingestions that happened at or before given date (e.g.
+    /// "yesterday 23:59")
+    #[arg(long, value_name = "DATE", value_parser = parse_date_string)]
+    pub to: Option<DateTime<Local>>,
+
+    /// Show only ingestions with dosage equal to or above given one (e.g.
+    /// 50mg). Dosage ranges match when their lower bound does, unknown
+    /// dosages never match.
+    #[arg(long = "min", value_name = "DOSAGE", value_parser = Dosage::from_str)]
+    pub min_dosage: Option<Dosage>,
+
+    /// Show only ingestions with dosage equal to or below given one (e.g.
+    /// 200mg). Dosage ranges match when their upper bound does, unknown
+    /// dosages never match.
+    #[arg(long = "max", value_name = "DOSAGE", value_parser = Dosage::from_str)]
+    pub max_dosage: Option<Dosage>,
+
+    /// Show only ingestions where substance, route or classification contains
+    /// given text
+    #[arg(long, value_name = "TEXT")]
+    pub search: Option<String>,
+
+    /// Column used to order ingestions
+    #[arg(long = "sort", value_enum, default_value_t = IngestionSortColumn::default())]
+    pub sort_by: IngestionSortColumn,
+
+    /// Direction in which ingestions are ordered
+    #[arg(long = "order", value_enum, default_value_t = SortDirection::default())]
+    pub sort_direction: SortDirection,
+}
+
+impl ListIngestion
+{
+    /// Translate provided filters into a condition over `ingestion` table.
+    pub fn condition(&self) -> Condition
+    {
+        let mut condition = Condition::all();
+
+        if !self.substances.is_empty()
+        {
+            condition = condition.add(
+                ingestion::Column::SubstanceName
+                    .is_in(self.substances.iter().map(|name| name.trim().to_lowercase())),
+            );
+        }
+
+        if !self.routes.is_empty()
+        {
+            condition = condition.add(
+                ingestion::Column::RouteOfAdministration.is_in(
+                    self.routes
+                        .iter()
+                        .map(|route| serde_json::to_value(route).unwrap().as_str().unwrap().to_string()),
+                ),
+            );
+        }
+
+        if !self.classifications.is_empty()
+        {
+            condition = condition.add(
+                ingestion::Column::DosageClassification
+                    .is_in(self.classifications.iter().map(|class| class.to_string())),
+            );
+        }
+
+        if let Some(from) = self.from
+        {
+            condition = condition.add(ingestion::Column::IngestedAt.gte(from.to_utc().naive_utc()));
+        }
+
+        if let Some(to) = self.to
+        {
+            condition = condition.add(ingestion::Column::IngestedAt.lte(to.to_utc().naive_utc()));
+        }
+
+        // Unknown dosages are stored as NULL and so they fail both of the
+        // comparisons below.
+        if let Some(min_dosage) = self.min_dosage
+        {
+            condition =
+                condition.add(ingestion::Column::Dosage.gte(min_dosage.as_base_units() as f32));
+        }
+
+        if let Some(max_dosage) = self.max_dosage
+        {
+            condition = condition
+                .add(Expr::expr(dosage_upper_bound()).lte(max_dosage.as_base_units() as f32));
+        }
+
+        if let Some(search) = self.search.as_deref().map(str::trim).filter(|s| !s.is_empty())
+        {
+            // Substances and routes are stored lowercase, classifications
+            // are capitalized and so they're lowercased before matching.
+            let search = search.to_lowercase();
+            condition = condition.add(
+                Condition::any()
+                    .add(ingestion::Column::SubstanceName.contains(&search))
+                    .add(ingestion::Column::RouteOfAdministration.contains(&search))
+                    .add(
+                        Expr::expr(Func::lower(Expr::col(ingestion::Column::DosageClassification)))
+                            .like(format!("%{}%", search)),
+                    ),
+            );
+        }
+
+        condition
+    }
+
+    /// Build a query selecting ingestions which match the provided filters,
+    /// ordered and paginated as requested.
+    pub fn select(&self) -> miette::Result<Select<Ingestion>>
+    {
+        if let (Some(min), Some(max)) = (self.min_dosage, self.max_dosage)
+        {
+            if min > max
+            {
+                return Err(miette!(
+                    help = "Swap values of --min and --max",
+                    "Minimum dosage ({}) is greater than maximum dosage ({})",
+                    min,
+                    max
+                ));
+            }
+        }
+
+        if let (Some(from), Some(to)) = (self.from, self.to)
+        {
+            if from > to
+            {
+                return Err(miette!(
+                    help = "Swap values of --from and --to",
+                    "Start date ({}) is after end date ({})",
+                    from.format("%Y-%m-%d %H:%M"),
+                    to.format("%Y-%m-%d %H:%M")
+                ));
+            }
+        }
+
+        let query = Ingestion::find().filter(self.condition());
+        let query = match self.sort_by
+        {
+            // Ranges are ordered by their middle, unknown dosages come last in
+            // both directions.
+            | IngestionSortColumn::Dosage => query
+                .order_by(Expr::col(ingestion::Column::Dosage).is_null(), Order::Asc)
+                .order_by(
+                    Expr::col(ingestion::Column::Dosage).add(dosage_upper_bound()),
+                    self.sort_direction.into(),
+                ),
+            | column => query.order_by(ingestion::Column::from(column), self.sort_direction.into()),
+        };
+
+        Ok(query
+            .order_by(ingestion::Column::Id, self.sort_direction.into())
+            .offset(Some(self.offset))
+            .limit(Some(self.limit)))
+    }
+}
+
+/// Upper bound of stored dosage, which is the dosage itself unless it's a
+/// range.
+fn dosage_upper_bound() -> SimpleExpr
+{
+    Func::coalesce([
+        Expr::col(ingestion::Column::DosageMax).into(),
+        Expr::col(ingestion::Column::Dosage).into(),
+    ])
+    .into()
 }
 
 #[async_trait]
@@ -162,15 +537,29 @@ impl CommandHandler for ListIngestion
 {
     async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
     {
-        let ingestions = Ingestion::find()
-            .order_by_desc(ingestion::Column::IngestedAt)
-            .limit(Some(self.limit))
+        let query = self.select()?;
+
+        // Stream rows directly from the database cursor, so large histories
+        // are neither buffered in memory nor delayed until the query completes.
+        if let MessageFormat::Ndjson = ctx.stdout_format
+        {
+            let rows = query
+                .stream(ctx.database_connection)
+                .await
+                .into_diagnostic()?
+                .map(|row| {
+                    row.map(|model| IngestionViewModel::from_model(model, MassUnit::Auto))
+                });
+
+            return stream_ndjson(rows).await;
+        }
+
+        let ingestions = query
             .all(ctx.database_connection)
             .await
-            .map_err(|e| e.to_string())
-            .unwrap()
-            .iter()
-            .map(|i| IngestionViewModel::from(i.clone()))
+            .into_diagnostic()?
+            .into_iter()
+            .map(|model| IngestionViewModel::from_model(model, ctx.stdout_format.mass_unit()))
             .collect();
 
         println!(
@@ -241,10 +630,13 @@ impl CommandHandler for GetIngestion
             .into_diagnostic()?
             .ok_or_else(|| miette::miette!("Ingestion with ID {} not found", self.ingestion_id))?;
 
+        let ingested_at = Local.from_utc_datetime(&ingestion.ingested_at);
+        let body_weight = body_weight_at(ctx.database_connection, ingested_at).await?;
+
         let analysis_query = AnalyzeIngestion::builder()
             .substance(ingestion.substance_name.clone())
-            .date(Local.from_utc_datetime(&ingestion.ingested_at))
-            .dosage(Dosage::from_base_units(ingestion.dosage as f64))
+            .date(ingested_at)
+            .dosage(dosage_estimate(&ingestion))
             .roa(
                 ingestion
                     .route_of_administration
@@ -252,16 +644,20 @@ impl CommandHandler for GetIngestion
                     .unwrap_or(RouteOfAdministrationClassification::Oral),
             )
             .ingestion_id(ingestion.id)
+            .maybe_body_weight(body_weight.map(|body_weight| body_weight.weight))
             .build();
 
         match analysis_query.query().await
         {
             | Ok(analysis) =>
             {
-                println!(
-                    "{}",
-                    IngestionViewModel::from(analysis).format(ctx.stdout_format)
-                );
+                let unit = ctx.stdout_format.mass_unit();
+                let view_model = IngestionViewModel {
+                    dosage: display_dosage(&ingestion, unit),
+                    ..IngestionViewModel::from_ingestion(analysis, unit)
+                };
+
+                println!("{}", view_model.format(ctx.stdout_format));
             }
             | Err(e) =>
             {
@@ -273,7 +669,8 @@ impl CommandHandler for GetIngestion
                 );
                 println!(
                     "{}",
-                    IngestionViewModel::from(ingestion).format(ctx.stdout_format)
+                    IngestionViewModel::from_model(ingestion, ctx.stdout_format.mass_unit())
+                        .format(ctx.stdout_format)
                 );
             }
         }
@@ -316,7 +713,9 @@ impl CommandHandler for IngestionCommand
                 let ingestion = IngestionService::log(log_ingestion)
                     .await
                     .map_err(|e| miette!(e))?;
-                let message = IngestionViewModel::from(ingestion).format(ctx.stdout_format);
+                let message =
+                    IngestionViewModel::from_ingestion(ingestion, ctx.stdout_format.mass_unit())
+                        .format(ctx.stdout_format);
                 println!("{}", message);
                 Ok(())
             }
@@ -330,6 +729,46 @@ impl CommandHandler for IngestionCommand
 
 fn display_date(date: &DateTime<Local>) -> String { HumanTime::from(*date).to_string() }
 
+/// Dosage of stored ingestion, as precisely as it's known.
+pub fn dosage_estimate(model: &Model) -> DosageEstimate
+{
+    DosageEstimate::from_stored(model.dosage, model.dosage_max, model.dosage_approximate)
+}
+
+/// Dosage of stored ingestion together with the amount it was logged in,
+/// masses are displayed in given unit.
+pub fn display_dosage(model: &Model, unit: MassUnit) -> String
+{
+    let dosage = dosage_estimate(model);
+
+    match Estimate::<Amount>::from_stored(
+        model.amount,
+        model.amount_max,
+        model.amount_unit.as_deref(),
+        model.dosage_approximate,
+    )
+    {
+        | Some(amount) => amount.display_with_mass(&dosage, unit),
+        | None => dosage.display_in(unit),
+    }
+}
+
+/// Classification of stored ingestion, or range of classifications of its
+/// dosage range.
+pub fn display_stored_classification(model: &Model) -> Option<String>
+{
+    let parse = |classification: &Option<String>| {
+        classification
+            .as_ref()
+            .and_then(|c| DosageClassification::from_str(&c.to_lowercase()).ok())
+    };
+
+    display_classification(
+        parse(&model.dosage_classification),
+        parse(&model.dosage_classification_max),
+    )
+}
+
 #[derive(Debug, Serialize, Tabled, bon::Builder)]
 pub struct IngestionViewModel
 {
@@ -346,6 +785,22 @@ pub struct IngestionViewModel
     pub ingested_at: DateTime<Local>,
     #[tabled(rename = "Dosage Classification")]
     pub dosage_classification: String,
+    /// Dosage per kilogram of body weight, e.g. "1.43 mg/kg".
+    #[tabled(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dosage_per_kg: Option<String>,
+    /// Body weight the dosage was normalised with.
+    #[tabled(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_weight: Option<String>,
+    /// Dosage which reaches systemic circulation, e.g. "17.0 mg".
+    #[tabled(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_dosage: Option<String>,
+    /// Bioavailability the effective dosage was computed with, e.g. "17%".
+    #[tabled(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bioavailability: Option<String>,
     #[tabled(skip)]
     pub phases: Vec<IngestionPhaseViewModel>,
     #[tabled(skip)]
@@ -406,6 +861,23 @@ impl Formatter for IngestionViewModel
             }
         ));
 
+        if let (Some(dosage_per_kg), Some(body_weight)) = (&self.dosage_per_kg, &self.body_weight)
+        {
+            md.push_str(&format!(
+                "**Normalised Dosage**: {} _(at {})_\n",
+                dosage_per_kg, body_weight
+            ));
+        }
+
+        if let (Some(effective_dosage), Some(bioavailability)) =
+            (&self.effective_dosage, &self.bioavailability)
+        {
+            md.push_str(&format!(
+                "**Effective Dosage**: {} _({} bioavailable)_\n",
+                effective_dosage, bioavailability
+            ));
+        }
+
         let time_since = HumanTime::from(self.ingested_at);
 
         md.push_str(&format!(
@@ -481,11 +953,13 @@ impl Formatter for IngestionViewModel
     }
 }
 
-impl From<Model> for IngestionViewModel
+impl IngestionViewModel
 {
-    fn from(model: Model) -> Self
+    /// View of stored ingestion with masses displayed in given unit.
+    pub fn from_model(model: Model, unit: MassUnit) -> Self
     {
-        let dosage = Dosage::from_base_units(model.dosage.into());
+        let dosage = display_dosage(&model, unit);
+        let dosage_classification = display_stored_classification(&model);
         let route_enum: RouteOfAdministrationClassification =
             model.route_of_administration.parse().unwrap_or_default();
         let local_ingestion_date = Local::from_utc_datetime(&Local, &model.ingested_at);
@@ -494,23 +968,22 @@ impl From<Model> for IngestionViewModel
             .id(model.id)
             .substance_name(model.substance_name)
             .route(RouteOfAdministrationClassification::to_string(&route_enum))
-            .dosage(dosage.to_string())
+            .dosage(dosage)
             .ingested_at(local_ingestion_date)
-            .dosage_classification(
-                model
-                    .dosage_classification
-                    .map_or("n/a".to_string(), |c| c.to_string()),
-            )
+            .dosage_classification(dosage_classification.unwrap_or("n/a".to_string()))
             .phases(vec![])
             .build()
     }
-}
 
-impl From<crate::ingestion::model::Ingestion> for IngestionViewModel
-{
-    fn from(model: crate::ingestion::model::Ingestion) -> Self
+    /// View of analyzed ingestion with masses displayed in given unit.
+    pub fn from_ingestion(model: crate::ingestion::model::Ingestion, unit: MassUnit) -> Self
     {
-        let dosage = model.dosage;
+        let dosage = model.display_dosage(unit);
+        let dosage_classification = model.display_classification();
+        let dosage_per_kg = model
+            .dosage_per_kg()
+            .map(|dosage| display_per_kg(dosage.display_in(unit)));
+        let effective_dosage = model.effective_dosage().map(|dosage| dosage.display_in(unit));
         let route_enum = model.route;
 
         let phases = model
@@ -546,13 +1019,13 @@ impl From<crate::ingestion::model::Ingestion> for IngestionViewModel
             .id(model.id.unwrap_or(0))
             .substance_name(model.substance_name)
             .route(RouteOfAdministrationClassification::to_string(&route_enum))
-            .dosage(dosage.to_string())
+            .dosage(dosage)
             .ingested_at(model.ingestion_date)
-            .dosage_classification(
-                model
-                    .dosage_classification
-                    .map_or("n/a".to_string(), |c| c.to_string()),
-            )
+            .dosage_classification(dosage_classification.unwrap_or("n/a".to_string()))
+            .maybe_dosage_per_kg(dosage_per_kg)
+            .maybe_body_weight(model.body_weight.map(display_weight))
+            .maybe_effective_dosage(effective_dosage)
+            .maybe_bioavailability(model.bioavailability.map(display_bioavailability))
             .phases(phases)
             .maybe_active_phase(active_phase)
             .build()
@@ -1,12 +1,15 @@
 use crate::cli::MessageFormat;
 use crate::cli::formatter::Formatter;
+use crate::cli::ingestion::display_dosage;
+use crate::cli::ingestion::display_stored_classification;
+use crate::cli::ingestion::dosage_estimate;
 use crate::core::CommandHandler;
 use crate::core::QueryHandler;
 use crate::database::entities::ingestion::Entity as Ingestion;
 use crate::database::entities::ingestion::Model as IngestionModel;
 use crate::ingestion::query::AnalyzeIngestion;
+use crate::ingestion::query::ingested_between;
 use crate::substance::route_of_administration::RouteOfAdministrationClassification;
-use crate::substance::route_of_administration::dosage::Dosage;
 use crate::utils::AppContext;
 use async_trait::async_trait;
 use chrono::DateTime;
@@ -86,7 +89,7 @@ impl JournalViewModel
             let analysis_query = AnalyzeIngestion::builder()
                 .substance(ingestion.substance_name.clone())
                 .date(Local.from_utc_datetime(&ingestion.ingested_at))
-                .dosage(Dosage::from_base_units(ingestion.dosage as f64))
+                .dosage(dosage_estimate(&ingestion))
                 .roa(
                     ingestion
                         .route_of_administration
@@ -145,6 +148,7 @@ impl Formatter for JournalViewModel
         {
             | MessageFormat::Pretty => self.pretty(),
             | MessageFormat::Json => serde_json::to_string_pretty(self).unwrap(),
+            | MessageFormat::Ndjson => self.ndjson(),
         }
     }
 
@@ -172,7 +176,8 @@ impl Formatter for JournalViewModel
 
                 for ingestion in ingestions
                 {
-                    let dosage = Dosage::from_base_units(ingestion.model.dosage as f64);
+                    let dosage =
+                        display_dosage(&ingestion.model, MessageFormat::Pretty.mass_unit());
                     let route_enum: RouteOfAdministrationClassification = ingestion
                         .model
                         .route_of_administration
@@ -214,10 +219,7 @@ impl Formatter for JournalViewModel
                         String::new()
                     };
 
-                    let dosage_class = ingestion
-                        .model
-                        .dosage_classification
-                        .as_ref()
+                    let dosage_class = display_stored_classification(&ingestion.model)
                         .map(|c| format!(" [{}]", c))
                         .unwrap_or_default();
 
@@ -245,12 +247,17 @@ impl CommandHandler for ViewJournal
 {
     async fn handle<'a>(&self, ctx: AppContext<'a>) -> Result<()>
     {
-        let today_start = Local::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
-        let today_end = Local::now().date_naive().and_hms_opt(23, 59, 59).unwrap();
-
-        let mut query = Ingestion::find()
-            .filter(crate::database::entities::ingestion::Column::IngestedAt.gte(today_start))
-            .filter(crate::database::entities::ingestion::Column::IngestedAt.lte(today_end));
+        let today = Local::now().date_naive();
+        let today_start = Local
+            .from_local_datetime(&today.and_hms_opt(0, 0, 0).unwrap())
+            .earliest()
+            .unwrap_or_else(Local::now);
+        let today_end = Local
+            .from_local_datetime(&today.and_hms_opt(23, 59, 59).unwrap())
+            .latest()
+            .unwrap_or_else(Local::now);
+
+        let mut query = ingested_between(today_start, today_end);
 
         if !self.all
         {
@@ -260,7 +267,6 @@ impl CommandHandler for ViewJournal
         }
 
         let ingestions = query
-            .order_by_asc(crate::database::entities::ingestion::Column::IngestedAt)
             .all(ctx.database_connection)
             .await
             .into_diagnostic()?;
@@ -1,10 +1,13 @@
 use crate::cli::ingestion::IngestionCommands;
 use crate::core::CommandHandler;
+use crate::core::config::Config;
+use crate::core::profile::Profile;
 use crate::database::entities::ingestion::Column as IngestionColumn;
 use crate::database::entities::ingestion::Entity as IngestionEntity;
 use crate::database::entities::ingestion_phase::Column as IngestionPhaseColumn;
 use crate::database::entities::ingestion_phase::Entity as IngestionPhaseEntity;
 use crate::ingestion::LogIngestion;
+use crate::substance::route_of_administration::dosage::MassUnit;
 use crate::utils::AppContext;
 use atty::Stream;
 use chrono::Duration;
@@ -14,8 +17,18 @@ use clap::ColorChoice;
 use clap::CommandFactory;
 use clap::Parser;
 use clap::Subcommand;
+use calendar::ViewCalendar;
+use config::ConfigCommand;
+use database::DatabaseCommand;
+use export::ExportJournal;
+use import::ImportJournal;
+use profile::ProfileCommand;
+use solution::SolutionCommand;
+use weight::WeightCommand;
 use ingestion::IngestionCommand;
+use chart::ViewChart;
 use journal::ViewJournal;
+use stats::ViewStatistics;
 use miette::IntoDiagnostic;
 use sea_orm::ColumnTrait;
 use sea_orm::EntityTrait;
@@ -23,35 +36,49 @@ use sea_orm::QueryFilter;
 use sea_orm::QueryOrder;
 use sea_orm::prelude::*;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use substance::SubstanceCommand;
-use textplots::Chart;
-use textplots::Plot;
-use textplots::Shape;
 use tracing::log::Log;
+mod calendar;
+mod chart;
+mod config;
+mod database;
+mod export;
+mod import;
 pub mod formatter;
 mod ingestion;
 mod journal;
 mod parser;
+mod profile;
+mod solution;
+mod stats;
 pub mod substance;
+mod weight;
 
 fn is_interactive() -> bool { atty::is(Stream::Stdout) }
 
 // TODO: Markdown?
 // TODO: TUI?
 // TODO: CSV?
-#[derive(clap::ValueEnum, Clone, Debug)]
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 /// The output format specifies how application data is presented:
 ///
 /// - `Pretty`: Used in interactive shells to display data in a visually
 ///   appealing table format.
 /// - `Json`: Used in non-interactive shells (e.g., scripts or when data is
 ///   piped) to provide raw JSON for automated parsing.
+/// - `Ndjson`: Used for large outputs consumed by line-oriented tools (e.g.
+///   `jq`), every record is written as a single line of compact JSON as soon
+///   as it is available.
 pub enum MessageFormat
 {
     /// Pretty printed tables
     Pretty,
     /// JSON formatted output
     Json,
+    /// Newline-delimited JSON, one compact object per line
+    Ndjson,
     // TODO: Application may support custom templates like liquidless or smth
 }
 
@@ -59,6 +86,11 @@ impl Default for MessageFormat
 {
     fn default() -> Self
     {
+        if let Some(format) = Config::current().output_format
+        {
+            return format;
+        }
+
         if is_interactive()
         {
             MessageFormat::Pretty
@@ -70,6 +102,20 @@ impl Default for MessageFormat
     }
 }
 
+impl MessageFormat
+{
+    /// Unit in which masses are displayed, preferred unit applies only to
+    /// output read by people so machine-readable output stays stable.
+    pub fn mass_unit(&self) -> MassUnit
+    {
+        match self
+        {
+            | MessageFormat::Pretty => Config::current().units.mass,
+            | MessageFormat::Json | MessageFormat::Ndjson => MassUnit::Auto,
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl CommandHandler for ApplicationCommands
 {
@@ -79,12 +125,36 @@ impl CommandHandler for ApplicationCommands
         {
             | ApplicationCommands::Ingestion(cmd) => cmd.handle(ctx).await,
             | ApplicationCommands::Substance(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Solution(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Weight(cmd) => cmd.handle(ctx).await,
             | ApplicationCommands::Journal(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Stats(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Chart(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Calendar(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Export(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Import(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Config(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Profile(cmd) => cmd.handle(ctx).await,
+            | ApplicationCommands::Db(cmd) => cmd.handle(ctx).await,
         }
     }
 }
 
 
+impl ApplicationCommands
+{
+    /// Whether pending migrations are applied before the command runs,
+    /// migration commands manage them on their own.
+    pub fn migrates_database(&self) -> bool
+    {
+        match self
+        {
+            | ApplicationCommands::Db(cmd) => !cmd.manages_migrations(),
+            | _ => true,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub(crate) enum ApplicationCommands
 {
@@ -92,8 +162,28 @@ pub(crate) enum ApplicationCommands
     Ingestion(IngestionCommand),
     #[command(hide = true)]
     Substance(SubstanceCommand),
+    /// Manage solutions for volumetric dosing
+    Solution(SolutionCommand),
+    /// Manage history of body weight
+    Weight(WeightCommand),
     /// View today's ingestion journal
     Journal(ViewJournal),
+    /// Show statistics of ingestion history
+    Stats(ViewStatistics),
+    /// Draw chart of substance usage over time
+    Chart(ViewChart),
+    /// Show calendar of ingestion days
+    Calendar(ViewCalendar),
+    /// Export ingestion journal to JSON, CSV, SQL or iCalendar
+    Export(ExportJournal),
+    /// Import ingestions exported to JSON or CSV
+    Import(ImportJournal),
+    /// Manage configuration
+    Config(ConfigCommand),
+    /// Manage profiles with separate journals and settings
+    Profile(ProfileCommand),
+    /// Back up, restore, check and migrate the journal database
+    Db(DatabaseCommand),
 }
 
 #[derive(Parser)]
@@ -112,6 +202,107 @@ pub struct CommandLineInterface
     #[arg(short, long = "format", value_enum, default_value_t = MessageFormat::default())]
     pub format: MessageFormat,
 
+    /// Configuration file used instead of the default one (can also be set
+    /// with NEURONEK_CONFIG)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Journal database file used instead of the configured one
+    #[arg(long, global = true, value_name = "FILE")]
+    pub database: Option<PathBuf>,
+
+    /// Profile used instead of the active one (can also be set with
+    /// NEURONEK_PROFILE)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
     #[command(flatten)]
     verbose: clap_verbosity_flag::Verbosity,
 }
+
+/// Apply `--profile`, `--config` and `--database` options and load the
+/// configuration, they have to be read before the command line is parsed as
+/// parsing already depends on configuration (e.g. for default values). The
+/// options are read with the same parser, errors are left to the full parse.
+pub fn apply_configuration_options() -> miette::Result<()>
+{
+    let options = configuration_options(std::env::args_os());
+
+    if let Some(name) = &options.profile
+    {
+        Profile::use_name(name)?;
+    }
+
+    if let Some(path) = options.config
+    {
+        Config::use_path(path);
+    }
+
+    Config::initialize()?;
+
+    if let Some(path) = options.database
+    {
+        Config::use_database(path);
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ConfigurationOptions
+{
+    profile: Option<String>,
+    config: Option<PathBuf>,
+    database: Option<PathBuf>,
+}
+
+/// Configuration options of given command line, errors of the command line
+/// are ignored and reported once it's parsed after loading configuration.
+fn configuration_options<I, T>(args: I) -> ConfigurationOptions
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let Ok(matches) = CommandLineInterface::command()
+        .ignore_errors(true)
+        .try_get_matches_from(args)
+    else
+    {
+        return ConfigurationOptions::default();
+    };
+
+    ConfigurationOptions {
+        profile: matches.try_get_one::<String>("profile").ok().flatten().cloned(),
+        config: matches.try_get_one::<PathBuf>("config").ok().flatten().cloned(),
+        database: matches.try_get_one::<PathBuf>("database").ok().flatten().cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn verify_command_line_interface() { CommandLineInterface::command().debug_assert(); }
+
+    #[test]
+    fn test_configuration_options()
+    {
+        let options = configuration_options([
+            "neuronek",
+            "--config",
+            "a.toml",
+            "journal",
+            "--database=b.db",
+        ]);
+
+        assert_eq!(options.config, Some(PathBuf::from("a.toml")));
+        assert_eq!(options.database, Some(PathBuf::from("b.db")));
+        assert_eq!(configuration_options(["neuronek", "--config", "a.toml"]).database, None);
+        assert_eq!(
+            configuration_options(["neuronek", "journal", "--profile", "work"]).profile,
+            Some("work".to_string())
+        );
+    }
+}
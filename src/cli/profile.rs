@@ -0,0 +1,128 @@
+use crate::cli::formatter::Formatter;
+use crate::cli::formatter::FormatterVector;
+use crate::core::CommandHandler;
+use crate::core::config::Config;
+use crate::core::profile::Profile;
+use crate::utils::AppContext;
+use async_trait::async_trait;
+use clap::Parser;
+use clap::Subcommand;
+use miette::miette;
+use serde::Serialize;
+use std::path::PathBuf;
+use tabled::Tabled;
+
+/// Create a new profile with its own journal and settings
+#[derive(Debug, Parser)]
+pub struct CreateProfile
+{
+    /// Name of the profile, e.g. "experiment"
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// Make the profile active right away
+    #[arg(long)]
+    switch: bool,
+}
+
+/// Make profile active for following commands
+#[derive(Debug, Parser)]
+pub struct SwitchProfile
+{
+    #[arg(value_name = "NAME")]
+    name: String,
+}
+
+#[derive(Debug, Subcommand)]
+enum ProfileCommands
+{
+    Create(CreateProfile),
+    /// List profiles
+    List,
+    Switch(SwitchProfile),
+}
+
+#[derive(Debug, Parser)]
+#[command(about = "Manage profiles")]
+pub struct ProfileCommand
+{
+    #[command(subcommand)]
+    commands: ProfileCommands,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct ProfileViewModel
+{
+    pub name: String,
+    pub active: bool,
+    pub database: String,
+    pub config: String,
+}
+
+impl Formatter for ProfileViewModel {}
+
+#[async_trait]
+impl CommandHandler for ProfileCommand
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        match &self.commands
+        {
+            | ProfileCommands::Create(command) =>
+            {
+                let profile = Profile::new(&command.name)?;
+
+                if profile.exists()
+                {
+                    return Err(miette!("Profile \"{}\" already exists", profile.name));
+                }
+
+                let config = Config {
+                    sqlite_path: profile.database_path(),
+                    ..Config::default()
+                };
+                config.save_to(&profile.config_path())?;
+
+                if command.switch
+                {
+                    profile.switch()?;
+                }
+
+                println!("Created profile {}", profile.name);
+            }
+            | ProfileCommands::List =>
+            {
+                let active = Profile::active();
+                let profiles = Profile::list()?
+                    .into_iter()
+                    .map(|profile| {
+                        // Database might be changed in profile's settings.
+                        let database = std::fs::read_to_string(profile.config_path())
+                            .ok()
+                            .and_then(|content| content.parse::<toml::Table>().ok())
+                            .and_then(|table| table.get("database")?.as_str().map(PathBuf::from))
+                            .unwrap_or_else(|| profile.database_path());
+
+                        ProfileViewModel {
+                            active: profile == active,
+                            database: database.display().to_string(),
+                            config: profile.config_path().display().to_string(),
+                            name: profile.name,
+                        }
+                    })
+                    .collect();
+
+                println!("{}", FormatterVector::new(profiles).format(ctx.stdout_format));
+            }
+            | ProfileCommands::Switch(command) =>
+            {
+                let profile = Profile::new(&command.name)?;
+                profile.switch()?;
+
+                println!("Switched to profile {}", profile.name);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use crate::cli::formatter::Formatter;
+use crate::cli::formatter::FormatterVector;
+use crate::core::CommandHandler;
+use crate::database::entities::ingestion;
+use crate::database::entities::solution;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::solution::Solution;
+use crate::substance::solution::find_solution;
+use crate::utils::AppContext;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+use crate::substance::route_of_administration::dosage::MassUnit;
+use async_trait::async_trait;
+use chrono::Local;
+use clap::Parser;
+use clap::Subcommand;
+use miette::IntoDiagnostic;
+use miette::miette;
+use sea_orm::ActiveModelTrait;
+use sea_orm::ActiveValue;
+use sea_orm::ColumnTrait;
+use sea_orm::EntityTrait;
+use sea_orm::PaginatorTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use serde::Serialize;
+use std::str::FromStr;
+use tabled::Tabled;
+
+/// Parse volume of solution such as "10ml", in milliliters.
+fn parse_volume(s: &str) -> Result<f64, String>
+{
+    match Amount::from_str(s)?
+    {
+        | Amount::Volume(volume) if volume > 0.0 => Ok(volume),
+        | amount => Err(format!(
+            "Invalid volume \"{}\", expected positive volume (e.g. 10ml)",
+            amount
+        )),
+    }
+}
+
+/// Create a solution of substance dissolved in a solvent
+#[derive(Debug, Parser)]
+pub struct CreateSolution
+{
+    /// Name of the solution, e.g. "lsd-10ml"
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// Dissolved substance, e.g. "LSD"
+    #[arg(short = 's', long = "substance")]
+    substance_name: String,
+
+    /// Mass of dissolved substance, e.g. 100ug
+    #[arg(long, value_parser = Dosage::from_str)]
+    dissolved: Dosage,
+
+    /// Volume of solvent, e.g. 10ml
+    #[arg(long, value_parser = parse_volume)]
+    volume: f64,
+}
+
+/// Change substance amount or volume of a solution, ingestions which were
+/// already logged keep their dosage
+#[derive(Debug, Parser)]
+pub struct UpdateSolution
+{
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// New mass of dissolved substance
+    #[arg(long, value_parser = Dosage::from_str)]
+    dissolved: Option<Dosage>,
+
+    /// New volume of solvent
+    #[arg(long, value_parser = parse_volume)]
+    volume: Option<f64>,
+}
+
+/// Delete a solution, its ingestions keep their volume and dosage
+#[derive(Debug, Parser)]
+pub struct DeleteSolution
+{
+    #[arg(value_name = "NAME")]
+    name: String,
+}
+
+#[derive(Debug, Subcommand)]
+enum SolutionCommands
+{
+    Create(CreateSolution),
+    /// List solutions
+    List,
+    Update(UpdateSolution),
+    Delete(DeleteSolution),
+}
+
+/// Solutions are used for volumetric dosing, ingestion of a solution is
+/// logged with its volume (`neuronek ingestion log --solution NAME --volume
+/// 0.5ml`) and its dosage is computed from concentration of the solution.
+#[derive(Debug, Parser)]
+#[command(about = "Manage solutions for volumetric dosing")]
+pub struct SolutionCommand
+{
+    #[command(subcommand)]
+    commands: SolutionCommands,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct SolutionViewModel
+{
+    #[tabled(rename = "Name")]
+    pub name: String,
+    #[tabled(rename = "Substance")]
+    pub substance_name: String,
+    #[tabled(rename = "Dissolved")]
+    pub dissolved: String,
+    #[tabled(rename = "Volume")]
+    pub volume: String,
+    #[tabled(rename = "Concentration")]
+    pub concentration: String,
+}
+
+impl Formatter for SolutionViewModel {}
+
+impl SolutionViewModel
+{
+    fn new(solution: Solution, unit: MassUnit) -> Self
+    {
+        Self {
+            concentration: format!("{}/ml", solution.concentration().display_in(unit)),
+            dissolved: solution.dissolved.display_in(unit),
+            volume: Amount::Volume(solution.volume).to_string(),
+            name: solution.name,
+            substance_name: solution.substance_name,
+        }
+    }
+}
+
+#[async_trait]
+impl CommandHandler for SolutionCommand
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        match &self.commands
+        {
+            | SolutionCommands::Create(command) =>
+            {
+                let exists = solution::Entity::find()
+                    .filter(solution::Column::Name.eq(command.name.trim()))
+                    .one(ctx.database_connection)
+                    .await
+                    .into_diagnostic()?
+                    .is_some();
+
+                if exists
+                {
+                    return Err(miette!("Solution \"{}\" already exists", command.name));
+                }
+
+                let solution = solution::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    name: ActiveValue::Set(command.name.trim().to_string()),
+                    substance_name: ActiveValue::Set(command.substance_name.to_lowercase()),
+                    dissolved_dosage: ActiveValue::Set(command.dissolved.as_base_units()),
+                    solvent_volume: ActiveValue::Set(command.volume),
+                    created_at: ActiveValue::Set(Local::now().to_utc().naive_local()),
+                    updated_at: ActiveValue::Set(Local::now().to_utc().naive_local()),
+                }
+                .insert(ctx.database_connection)
+                .await
+                .into_diagnostic()?;
+
+                println!(
+                    "{}",
+                    SolutionViewModel::new(Solution::from(solution), ctx.stdout_format.mass_unit())
+                        .format(ctx.stdout_format)
+                );
+            }
+            | SolutionCommands::List =>
+            {
+                let solutions = solution::Entity::find()
+                    .order_by_asc(solution::Column::Name)
+                    .all(ctx.database_connection)
+                    .await
+                    .into_diagnostic()?
+                    .into_iter()
+                    .map(Solution::from)
+                    .map(|solution| SolutionViewModel::new(solution, ctx.stdout_format.mass_unit()))
+                    .collect();
+
+                println!("{}", FormatterVector::new(solutions).format(ctx.stdout_format));
+            }
+            | SolutionCommands::Update(command) =>
+            {
+                let previous = find_solution(ctx.database_connection, &command.name).await?;
+
+                if command.dissolved.is_none() && command.volume.is_none()
+                {
+                    return Err(miette!("Nothing to update, use --dissolved or --volume"));
+                }
+
+                let solution = solution::ActiveModel {
+                    id: ActiveValue::Unchanged(previous.id),
+                    dissolved_dosage: command
+                        .dissolved
+                        .map(|dosage| ActiveValue::Set(dosage.as_base_units()))
+                        .unwrap_or(ActiveValue::NotSet),
+                    solvent_volume: command
+                        .volume
+                        .map(ActiveValue::Set)
+                        .unwrap_or(ActiveValue::NotSet),
+                    updated_at: ActiveValue::Set(Local::now().to_utc().naive_local()),
+                    ..Default::default()
+                }
+                .update(ctx.database_connection)
+                .await
+                .into_diagnostic()?;
+
+                let ingestions = ingestion::Entity::find()
+                    .filter(ingestion::Column::SolutionId.eq(previous.id))
+                    .count(ctx.database_connection)
+                    .await
+                    .into_diagnostic()?;
+
+                if ingestions > 0
+                {
+                    eprintln!(
+                        "{} ingestions of {} keep dosage computed with previous concentration {}/ml",
+                        ingestions,
+                        previous.name,
+                        previous.concentration()
+                    );
+                }
+
+                println!(
+                    "{}",
+                    SolutionViewModel::new(Solution::from(solution), ctx.stdout_format.mass_unit())
+                        .format(ctx.stdout_format)
+                );
+            }
+            | SolutionCommands::Delete(command) =>
+            {
+                let solution = find_solution(ctx.database_connection, &command.name).await?;
+
+                solution::Entity::delete_by_id(solution.id)
+                    .exec(ctx.database_connection)
+                    .await
+                    .into_diagnostic()?;
+
+                println!("Deleted solution {}", solution.name);
+            }
+        }
+
+        Ok(())
+    }
+}
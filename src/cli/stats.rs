@@ -0,0 +1,213 @@
+use crate::analyzer::statistics::DosageDistribution;
+use crate::analyzer::statistics::SubstanceStatistics;
+use crate::cli::formatter::Formatter;
+use crate::cli::formatter::FormatterVector;
+use crate::core::CommandHandler;
+use crate::database::entities::ingestion;
+use crate::database::entities::ingestion::Entity as Ingestion;
+use crate::substance::bioavailability::find_bioavailabilities;
+use crate::utils::AppContext;
+use crate::utils::parse_date_string;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+use crate::substance::route_of_administration::dosage::MassUnit;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Local;
+use clap::Parser;
+use miette::IntoDiagnostic;
+use miette::miette;
+use sea_orm::ColumnTrait;
+use sea_orm::Condition;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use serde::Serialize;
+use tabled::Tabled;
+
+/// Show aggregated statistics of ingestion history.
+///
+/// Statistics are computed separately for every substance found in the
+/// journal, optionally limited to a single substance and a period of time.
+#[derive(Parser, Debug)]
+#[command(version, about = "Show statistics of ingestion history", long_about, aliases = vec!["statistics"])]
+pub struct ViewStatistics
+{
+    /// Compute statistics only for given substance
+    #[arg(short = 's', long = "substance", value_name = "SUBSTANCE_NAME")]
+    pub substance_name: Option<String>,
+
+    /// Include only ingestions that happened at or after given date (e.g.
+    /// "last month", "2025-01-01")
+    #[arg(long, value_name = "DATE", value_parser = parse_date_string)]
+    pub from: Option<DateTime<Local>>,
+
+    /// Include only ingestions that happened at or before given date
+    #[arg(long, value_name = "DATE", value_parser = parse_date_string)]
+    pub to: Option<DateTime<Local>>,
+}
+
+impl ViewStatistics
+{
+    fn condition(&self) -> Condition
+    {
+        let mut condition = Condition::all();
+
+        if let Some(substance_name) = &self.substance_name
+        {
+            condition = condition
+                .add(ingestion::Column::SubstanceName.eq(substance_name.trim().to_lowercase()));
+        }
+
+        if let Some(from) = self.from
+        {
+            condition = condition.add(ingestion::Column::IngestedAt.gte(from.to_utc().naive_utc()));
+        }
+
+        if let Some(to) = self.to
+        {
+            condition = condition.add(ingestion::Column::IngestedAt.lte(to.to_utc().naive_utc()));
+        }
+
+        condition
+    }
+}
+
+#[async_trait]
+impl CommandHandler for ViewStatistics
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        if let (Some(from), Some(to)) = (self.from, self.to)
+        {
+            if from > to
+            {
+                return Err(miette!(
+                    help = "Swap values of --from and --to",
+                    "Start date ({}) is after end date ({})",
+                    from.format("%Y-%m-%d %H:%M"),
+                    to.format("%Y-%m-%d %H:%M")
+                ));
+            }
+        }
+
+        let bioavailabilities = find_bioavailabilities(ctx.database_connection).await?;
+
+        // Effective dosages make ingestions through different routes comparable.
+        let ingestions: Vec<crate::ingestion::Ingestion> = Ingestion::find()
+            .filter(self.condition())
+            .order_by_asc(ingestion::Column::IngestedAt)
+            .all(ctx.database_connection)
+            .await
+            .into_diagnostic()?
+            .into_iter()
+            .map(|model| {
+                let mut ingestion = crate::ingestion::Ingestion::from(model);
+                ingestion.bioavailability =
+                    bioavailabilities.get(&ingestion.substance_name, ingestion.route);
+                ingestion
+            })
+            .collect();
+
+        let statistics = SubstanceStatistics::by_substance(&ingestions)
+            .into_iter()
+            .map(|statistics| {
+                SubstanceStatisticsViewModel::new(statistics, ctx.stdout_format.mass_unit())
+            })
+            .collect();
+
+        println!(
+            "{}",
+            FormatterVector::new(statistics).format(ctx.stdout_format)
+        );
+
+        Ok(())
+    }
+}
+
+fn display_distribution(distribution: &DosageDistribution) -> String
+{
+    distribution
+        .entries()
+        .iter()
+        .map(|(classification, count)| format!("{}: {}", classification, count))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn display_optional(value: &Option<String>) -> String
+{
+    value.clone().unwrap_or_else(|| "n/a".to_string())
+}
+
+fn display_streak(days: &usize) -> String
+{
+    match days
+    {
+        | 1 => "1 day".to_string(),
+        | days => format!("{} days", days),
+    }
+}
+
+fn format_interval(interval: Duration) -> String
+{
+    let rounded = Duration::minutes(interval.num_minutes());
+
+    match rounded.to_std()
+    {
+        | Ok(duration) if !duration.is_zero() => humantime::format_duration(duration).to_string(),
+        | _ => "less than a minute".to_string(),
+    }
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct SubstanceStatisticsViewModel
+{
+    #[tabled(rename = "Substance")]
+    pub substance_name: String,
+    #[tabled(rename = "Ingestions")]
+    pub ingestion_count: usize,
+    #[tabled(rename = "Total Dosage")]
+    pub total_dosage: String,
+    #[tabled(rename = "Mean Dosage")]
+    pub mean_dosage: String,
+    #[tabled(rename = "Effective Dosage")]
+    #[tabled(display_with = "display_optional")]
+    pub total_effective_dosage: Option<String>,
+    #[tabled(rename = "Mean Effective Dosage")]
+    #[tabled(display_with = "display_optional")]
+    pub mean_effective_dosage: Option<String>,
+    #[tabled(rename = "Dosage Distribution")]
+    #[tabled(display_with = "display_distribution")]
+    pub dosage_distribution: DosageDistribution,
+    #[tabled(rename = "Most Common Route")]
+    #[tabled(display_with = "display_optional")]
+    pub most_common_route: Option<String>,
+    #[tabled(rename = "Average Interval")]
+    #[tabled(display_with = "display_optional")]
+    pub average_interval: Option<String>,
+    #[tabled(rename = "Longest Streak")]
+    #[tabled(display_with = "display_streak")]
+    pub longest_streak_days: usize,
+}
+
+impl Formatter for SubstanceStatisticsViewModel {}
+
+impl SubstanceStatisticsViewModel
+{
+    fn new(statistics: SubstanceStatistics, unit: MassUnit) -> Self
+    {
+        Self {
+            substance_name: statistics.substance_name,
+            ingestion_count: statistics.ingestion_count,
+            total_dosage: statistics.total_dosage.display_in(unit),
+            mean_dosage: statistics.mean_dosage.display_in(unit),
+            total_effective_dosage: statistics.total_effective_dosage.map(|d| d.display_in(unit)),
+            mean_effective_dosage: statistics.mean_effective_dosage.map(|d| d.display_in(unit)),
+            dosage_distribution: statistics.dosage_distribution,
+            most_common_route: statistics.most_common_route.map(|r| r.to_string()),
+            average_interval: statistics.average_interval.map(format_interval),
+            longest_streak_days: statistics.longest_streak,
+        }
+    }
+}
@@ -1,13 +1,28 @@
+use crate::cli::formatter::Formatter;
+use crate::cli::formatter::FormatterVector;
 use crate::core::CommandHandler;
+use crate::substance::bioavailability;
+use crate::substance::bioavailability::Bioavailability;
+use crate::substance::bioavailability::display_bioavailability;
+use crate::substance::bioavailability::parse_bioavailability;
+use crate::substance::conversion;
+use crate::substance::conversion::UnitConversion;
 use crate::substance::error::SubstanceError;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::Dosage;
 use crate::utils::AppContext;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+use crate::substance::route_of_administration::dosage::MassUnit;
 use async_trait::async_trait;
 use bon::builder;
 use clap::Args;
 use clap::Parser;
 use clap::Subcommand;
+use miette::miette;
 use serde::Deserialize;
 use serde::Serialize;
+use std::str::FromStr;
 use tabled::Tabled;
 
 
@@ -31,6 +46,7 @@ struct SubstanceRouteOfAdministrationPhase
 struct SubstanceRouteOfAdministration
 {
     pub name: String,
+    pub bioavailability: Option<String>,
     pub dosages: Vec<SubstanceRouteOfAdministrationDosage>,
     pub phases: Vec<SubstanceRouteOfAdministrationPhase>,
 }
@@ -55,23 +71,26 @@ impl From<crate::substance::Substance> for Substance
                 .iter()
                 .map(|route| SubstanceRouteOfAdministration {
                     name: route.0.to_string(),
+                    bioavailability: route.1.bioavailability.map(display_bioavailability),
                     dosages: route
                         .1
                         .dosages
                         .iter()
-                        .map(|dosage| SubstanceRouteOfAdministrationDosage {
+                        .map(|dosage| (dosage, ""))
+                        .chain(route.1.dosages_per_kg.iter().map(|dosage| (dosage, "/kg")))
+                        .map(|(dosage, per)| SubstanceRouteOfAdministrationDosage {
                             classification: dosage.0.to_string(),
                             dosage_min: dosage
                                 .1
                                 .clone()
                                 .start
-                                .map(|d| d.to_string())
+                                .map(|d| format!("{}{}", d, per))
                                 .unwrap_or("N/A".parse().unwrap()),
                             dosage_max: dosage
                                 .1
                                 .clone()
                                 .end
-                                .map(|d| d.to_string())
+                                .map(|d| format!("{}{}", d, per))
                                 .unwrap_or("N/A".parse().unwrap()),
                         })
                         .collect(),
@@ -115,10 +134,291 @@ impl CommandHandler<Substance> for GetSubstance
     }
 }
 
+/// Set mass of an amount of substance in other units, e.g. `"vitamin d3"
+/// 40IU 1µg` or `caffeine "1 cup" 95mg`
+#[derive(Debug, Args)]
+pub struct SetUnitConversion
+{
+    #[arg(value_name = "SUBSTANCE")]
+    pub substance_name: String,
+    /// Amount in volume (ml), international units (IU) or countable units
+    /// such as drops, puffs or tablets
+    #[arg(value_name = "AMOUNT", value_parser = Amount::from_str)]
+    pub amount: Amount,
+    /// Mass of the amount, e.g. 95mg
+    #[arg(value_name = "DOSAGE", value_parser = Dosage::from_str)]
+    pub dosage: Dosage,
+}
+
+/// Remove conversion of a unit of substance
+#[derive(Debug, Args)]
+pub struct RemoveUnitConversion
+{
+    #[arg(value_name = "SUBSTANCE")]
+    pub substance_name: String,
+    /// Unit of the conversion, e.g. ml, IU or cup
+    #[arg(value_name = "UNIT")]
+    pub unit: String,
+}
+
+/// List conversions of all substances or of a single one
+#[derive(Debug, Args)]
+pub struct ListUnitConversions
+{
+    #[arg(value_name = "SUBSTANCE")]
+    pub substance_name: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum UnitConversionCommands
+{
+    List(ListUnitConversions),
+    Set(SetUnitConversion),
+    Remove(RemoveUnitConversion),
+}
+
+/// Ingestions logged in units other than mass (ml, IU, drops, cups...) are
+/// converted to mass with conversions of their substance, so they can be
+/// classified like any other ingestion.
+#[derive(Debug, Parser)]
+pub struct UnitConversionCommand
+{
+    #[command(subcommand)]
+    commands: UnitConversionCommands,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct UnitConversionViewModel
+{
+    #[tabled(rename = "Substance")]
+    pub substance_name: String,
+    #[tabled(rename = "Unit")]
+    pub unit: String,
+    #[tabled(rename = "Dosage")]
+    pub dosage: String,
+}
+
+impl Formatter for UnitConversionViewModel {}
+
+impl UnitConversionViewModel
+{
+    fn new(conversion: UnitConversion, unit: MassUnit) -> Self
+    {
+        Self {
+            substance_name: conversion.substance_name,
+            unit: conversion.unit,
+            dosage: conversion.dosage.display_in(unit),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandHandler for UnitConversionCommand
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        match &self.commands
+        {
+            | UnitConversionCommands::List(command) =>
+            {
+                let conversions = conversion::list_conversions(
+                    ctx.database_connection,
+                    command.substance_name.as_deref(),
+                )
+                .await?
+                .into_iter()
+                .map(|conversion| {
+                    UnitConversionViewModel::new(conversion, ctx.stdout_format.mass_unit())
+                })
+                .collect();
+
+                println!("{}", FormatterVector::new(conversions).format(ctx.stdout_format));
+            }
+            | UnitConversionCommands::Set(command) =>
+            {
+                let conversion = conversion::set_conversion(
+                    ctx.database_connection,
+                    &command.substance_name,
+                    &command.amount,
+                    command.dosage,
+                )
+                .await?;
+
+                println!(
+                    "{}",
+                    UnitConversionViewModel::new(conversion, ctx.stdout_format.mass_unit())
+                        .format(ctx.stdout_format)
+                );
+            }
+            | UnitConversionCommands::Remove(command) =>
+            {
+                let amount = Amount::from_str(&format!("1 {}", command.unit))
+                    .map_err(|error| miette!("{}", error))?;
+
+                if !conversion::remove_conversion(
+                    ctx.database_connection,
+                    &command.substance_name,
+                    &amount,
+                )
+                .await?
+                {
+                    return Err(miette!(
+                        "Substance {} has no conversion of {}",
+                        command.substance_name,
+                        command.unit
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Set bioavailability of substance through a route, e.g. `ketamine
+/// insufflated 45%`
+#[derive(Debug, Args)]
+pub struct SetBioavailability
+{
+    #[arg(value_name = "SUBSTANCE")]
+    pub substance_name: String,
+    #[arg(value_name = "ROUTE", value_enum)]
+    pub route: RouteOfAdministrationClassification,
+    /// Bioavailability as percentage (45%) or fraction (0.45)
+    #[arg(value_name = "BIOAVAILABILITY", value_parser = parse_bioavailability)]
+    pub bioavailability: f64,
+}
+
+/// Remove bioavailability of substance through a route
+#[derive(Debug, Args)]
+pub struct RemoveBioavailability
+{
+    #[arg(value_name = "SUBSTANCE")]
+    pub substance_name: String,
+    #[arg(value_name = "ROUTE", value_enum)]
+    pub route: RouteOfAdministrationClassification,
+}
+
+/// List bioavailability of all substances or of a single one
+#[derive(Debug, Args)]
+pub struct ListBioavailability
+{
+    #[arg(value_name = "SUBSTANCE")]
+    pub substance_name: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum BioavailabilityCommands
+{
+    List(ListBioavailability),
+    Set(SetBioavailability),
+    Remove(RemoveBioavailability),
+}
+
+/// Effective dosage of an ingestion is its dosage multiplied by
+/// bioavailability of the substance through the route of ingestion, so
+/// ingestions through different routes can be compared.
+#[derive(Debug, Parser)]
+pub struct BioavailabilityCommand
+{
+    #[command(subcommand)]
+    commands: BioavailabilityCommands,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct BioavailabilityViewModel
+{
+    #[tabled(rename = "Substance")]
+    pub substance_name: String,
+    #[tabled(rename = "Route")]
+    pub route: String,
+    #[tabled(rename = "Bioavailability")]
+    pub bioavailability: String,
+}
+
+impl Formatter for BioavailabilityViewModel {}
+
+impl From<Bioavailability> for BioavailabilityViewModel
+{
+    fn from(bioavailability: Bioavailability) -> Self
+    {
+        Self {
+            substance_name: bioavailability.substance_name,
+            route: bioavailability.route.to_string(),
+            bioavailability: display_bioavailability(bioavailability.bioavailability),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandHandler for BioavailabilityCommand
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        match &self.commands
+        {
+            | BioavailabilityCommands::List(command) =>
+            {
+                let bioavailability = bioavailability::list_bioavailability(
+                    ctx.database_connection,
+                    command.substance_name.as_deref(),
+                )
+                .await?
+                .into_iter()
+                .map(BioavailabilityViewModel::from)
+                .collect();
+
+                println!(
+                    "{}",
+                    FormatterVector::new(bioavailability).format(ctx.stdout_format)
+                );
+            }
+            | BioavailabilityCommands::Set(command) =>
+            {
+                let bioavailability = bioavailability::set_bioavailability(
+                    ctx.database_connection,
+                    &command.substance_name,
+                    command.route,
+                    command.bioavailability,
+                )
+                .await?;
+
+                println!(
+                    "{}",
+                    BioavailabilityViewModel::from(bioavailability).format(ctx.stdout_format)
+                );
+            }
+            | BioavailabilityCommands::Remove(command) =>
+            {
+                if !bioavailability::remove_bioavailability(
+                    ctx.database_connection,
+                    &command.substance_name,
+                    command.route,
+                )
+                .await?
+                {
+                    return Err(miette!(
+                        "Substance {} has no bioavailability of {} route",
+                        command.substance_name,
+                        command.route.to_string().to_lowercase()
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Subcommand)]
 enum SubstanceCommands
 {
     Get(GetSubstance),
+    /// Manage conversions of volumes, international units and countable units
+    /// to mass
+    Unit(UnitConversionCommand),
+    /// Manage bioavailability of substances by route of administration
+    Bioavailability(BioavailabilityCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -136,6 +436,8 @@ impl CommandHandler for SubstanceCommand
         match &self.commands
         {
             | SubstanceCommands::Get(command) => command.handle(ctx).await.map(|_| ()),
+            | SubstanceCommands::Unit(command) => command.handle(ctx).await,
+            | SubstanceCommands::Bioavailability(command) => command.handle(ctx).await,
         }
     }
 }
@@ -0,0 +1,192 @@
+use crate::cli::formatter::Formatter;
+use crate::cli::formatter::FormatterVector;
+use crate::core::CommandHandler;
+use crate::database::entities::body_weight;
+use crate::ingestion::body_weight::BodyWeight;
+use crate::ingestion::body_weight::display_weight;
+use crate::ingestion::body_weight::normalised_period;
+use crate::ingestion::service::IngestionService;
+use crate::substance::route_of_administration::dosage::Dosage;
+use crate::utils::AppContext;
+use crate::utils::parse_date_string;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::TimeZone;
+use clap::Parser;
+use clap::Subcommand;
+use miette::IntoDiagnostic;
+use miette::miette;
+use sea_orm::ActiveModelTrait;
+use sea_orm::ActiveValue;
+use sea_orm::DatabaseConnection;
+use sea_orm::EntityTrait;
+use sea_orm::ModelTrait;
+use sea_orm::QueryOrder;
+use serde::Serialize;
+use std::str::FromStr;
+use tabled::Tabled;
+
+/// Parse body weight such as "72.5kg" or "72.5" (kilograms).
+fn parse_weight(s: &str) -> Result<f64, String>
+{
+    let weight = match s.trim().parse::<f64>()
+    {
+        | Ok(kilograms) => kilograms,
+        | Err(_) => Dosage::from_str(s)?.as_base_units(),
+    };
+
+    if weight > 0.0 && weight.is_finite()
+    {
+        Ok(weight)
+    }
+    else
+    {
+        Err(format!("Invalid body weight \"{}\", expected e.g. 72.5kg", s))
+    }
+}
+
+/// Log body weight, weight logged last before an ingestion is used to
+/// normalise its dosage
+#[derive(Debug, Parser)]
+pub struct LogBodyWeight
+{
+    /// Body weight, e.g. 72.5kg
+    #[arg(value_name = "WEIGHT", value_parser = parse_weight)]
+    weight: f64,
+
+    /// Date of measurement, e.g. "yesterday 8:00"
+    #[arg(short = 't', long = "date", default_value = "now", value_parser = parse_date_string)]
+    date: DateTime<Local>,
+}
+
+/// Delete logged body weight
+#[derive(Debug, Parser)]
+pub struct DeleteBodyWeight
+{
+    #[arg(value_name = "ID")]
+    id: i32,
+}
+
+#[derive(Debug, Subcommand)]
+enum WeightCommands
+{
+    Log(LogBodyWeight),
+    /// List history of body weight
+    List,
+    Delete(DeleteBodyWeight),
+}
+
+/// Body weight is used to classify dosages of substances described in mg/kg
+/// and to show dosages per kilogram of body weight.
+#[derive(Debug, Parser)]
+#[command(about = "Manage history of body weight")]
+pub struct WeightCommand
+{
+    #[command(subcommand)]
+    commands: WeightCommands,
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct BodyWeightViewModel
+{
+    #[tabled(rename = "ID")]
+    pub id: i32,
+    #[tabled(rename = "Weight")]
+    pub weight: String,
+    #[tabled(rename = "Measured At")]
+    pub measured_at: String,
+}
+
+impl Formatter for BodyWeightViewModel {}
+
+impl From<BodyWeight> for BodyWeightViewModel
+{
+    fn from(body_weight: BodyWeight) -> Self
+    {
+        Self {
+            id: body_weight.id,
+            weight: display_weight(body_weight.weight),
+            measured_at: body_weight.measured_at.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+}
+
+/// Dosages per kilogram depend on the weight measured last before an
+/// ingestion, so ingestions normalised with a weight which was logged or
+/// deleted are classified again.
+async fn reclassify_ingestions(
+    database_connection: &DatabaseConnection,
+    measured_at: DateTime<Local>,
+) -> miette::Result<()>
+{
+    let (from, to) = normalised_period(database_connection, measured_at).await?;
+    let reclassified = IngestionService::reclassify_between(database_connection, from, to).await?;
+
+    if reclassified > 0
+    {
+        eprintln!("Classified {} ingestions again", reclassified);
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl CommandHandler for WeightCommand
+{
+    async fn handle<'a>(&self, ctx: AppContext<'a>) -> miette::Result<()>
+    {
+        match &self.commands
+        {
+            | WeightCommands::Log(command) =>
+            {
+                let body_weight = body_weight::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    weight: ActiveValue::Set(command.weight),
+                    measured_at: ActiveValue::Set(command.date.to_utc().naive_utc()),
+                    created_at: ActiveValue::Set(Local::now().to_utc().naive_utc()),
+                }
+                .insert(ctx.database_connection)
+                .await
+                .into_diagnostic()?;
+
+                reclassify_ingestions(ctx.database_connection, command.date).await?;
+
+                println!(
+                    "{}",
+                    BodyWeightViewModel::from(BodyWeight::from(body_weight))
+                        .format(ctx.stdout_format)
+                );
+            }
+            | WeightCommands::List =>
+            {
+                let weights = body_weight::Entity::find()
+                    .order_by_desc(body_weight::Column::MeasuredAt)
+                    .all(ctx.database_connection)
+                    .await
+                    .into_diagnostic()?
+                    .into_iter()
+                    .map(|model| BodyWeightViewModel::from(BodyWeight::from(model)))
+                    .collect();
+
+                println!("{}", FormatterVector::new(weights).format(ctx.stdout_format));
+            }
+            | WeightCommands::Delete(command) =>
+            {
+                let body_weight = body_weight::Entity::find_by_id(command.id)
+                    .one(ctx.database_connection)
+                    .await
+                    .into_diagnostic()?
+                    .ok_or_else(|| miette!("Body weight with ID {} not found", command.id))?;
+                let measured_at = Local.from_utc_datetime(&body_weight.measured_at);
+
+                body_weight.delete(ctx.database_connection).await.into_diagnostic()?;
+                reclassify_ingestions(ctx.database_connection, measured_at).await?;
+
+                println!("Deleted body weight {}", command.id);
+            }
+        }
+
+        Ok(())
+    }
+}
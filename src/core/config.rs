@@ -1,13 +1,28 @@
 use crate::cli::CommandLineInterface;
+use crate::cli::MessageFormat;
+use crate::core::profile::Profile;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::route_of_administration::dosage::MassUnit;
+use chrono::NaiveTime;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
+use miette::IntoDiagnostic;
+use miette::WrapErr;
+use miette::miette;
 use serde::Deserialize;
 use serde::Serialize;
 use std::env;
 use std::env::temp_dir;
+use std::path::Path;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::sync::RwLock;
 
 pub const NAME: &str = env!("CARGO_PKG_NAME");
+
+/// Version of the configuration file format, files of newer versions are
+/// refused instead of being misread.
+pub const CONFIG_VERSION: u32 = 1;
 // pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 lazy_static! {
@@ -31,31 +46,357 @@ lazy_static! {
     };
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Preferences of units used to display values.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnitPreferences
+{
+    pub mass: MassUnit,
+}
+
+/// Application configuration persisted as TOML file in [`CONFIG_DIR`], fields
+/// missing in the file take their default values and unknown fields (e.g.
+/// misspelled ones) are an error.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config
 {
+    /// Journal database file.
+    #[serde(rename = "database", alias = "sqlite_path", default = "default_database")]
     pub sqlite_path: PathBuf,
-    pub version: Option<u32>,
+    /// Format version of the configuration file, see [`CONFIG_VERSION`].
+    pub version: u32,
+    /// Route of administration used when logging ingestion without one.
+    pub default_route: RouteOfAdministrationClassification,
+    /// Output format used when `--format` is not provided, by default it's
+    /// chosen depending on whether the terminal is interactive.
+    pub output_format: Option<MessageFormat>,
+    /// Time at which user usually goes to sleep, used to warn about
+    /// ingestions which effects last past it.
+    pub bedtime: Option<NaiveTime>,
+    pub units: UnitPreferences,
+    /// Disables requests to external services (e.g. PubChem).
+    pub offline: bool,
 }
 
 impl Default for Config
 {
     fn default() -> Self
     {
-        let mut journal_path = DATA_DIR.join("journal.db").clone();
+        Config {
+            sqlite_path: default_database(),
+            version: CONFIG_VERSION,
+            default_route: RouteOfAdministrationClassification::default(),
+            output_format: None,
+            bedtime: None,
+            units: UnitPreferences::default(),
+            offline: false,
+        }
+    }
+}
+
+/// Journal of the active profile.
+fn default_database() -> PathBuf
+{
+    match cfg!(test)
+    {
+        | true => temp_dir().join("neuronek.sqlite"),
+        | false => Profile::active().database_path(),
+    }
+}
+
+/// Configuration file chosen with `--config` flag.
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+impl Config
+{
+    /// Keys of settings which can be read and changed, nested settings are
+    /// separated with a dot.
+    pub const KEYS: [&str; 6] = [
+        "database",
+        "default_route",
+        "output_format",
+        "bedtime",
+        "units.mass",
+        "offline",
+    ];
+
+    /// Location of the configuration file, chosen with `--config` flag or
+    /// `NEURONEK_CONFIG` environment variable, the active profile's
+    /// configuration file otherwise.
+    pub fn path() -> PathBuf
+    {
+        if let Some(path) = CONFIG_PATH.get()
+        {
+            return path.clone();
+        }
 
-        if cfg!(test) || cfg!(debug_assertions)
+        match env::var_os("NEURONEK_CONFIG")
         {
-            journal_path = temp_dir().join("neuronek.sqlite");
+            | Some(path) if !path.is_empty() => PathBuf::from(path),
+            | _ => Profile::active().config_path(),
         }
+    }
 
-        Config {
-            sqlite_path: journal_path,
-            version: Some(1),
+    /// Use another configuration file, has effect only before configuration
+    /// is accessed for the first time.
+    pub fn use_path(path: PathBuf) { let _ = CONFIG_PATH.set(path); }
+
+    /// Configuration from the configuration file with values overridden by
+    /// `NEURONEK_*` environment variables.
+    pub fn load() -> miette::Result<Self>
+    {
+        let mut config = Self::load_file()?;
+
+        for key in Self::KEYS
+        {
+            let variable = Self::environment_variable(key);
+
+            if let Ok(value) = env::var(&variable)
+            {
+                config
+                    .set(key, &value)
+                    .wrap_err(format!("Invalid value of {} environment variable", variable))?;
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Read configuration file, default configuration is used when the file
+    /// doesn't exist yet.
+    pub fn load_file() -> miette::Result<Self>
+    {
+        let path = Self::path();
+
+        if !path.exists()
+        {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path).into_diagnostic()?;
+
+        let config: Self = toml::from_str(&content)
+            .into_diagnostic()
+            .wrap_err(format!("Invalid configuration file {}", path.display()))?;
+
+        if config.version > CONFIG_VERSION
+        {
+            return Err(miette!(
+                help = "Update neuronek to use it",
+                "Configuration file {} has version {}, newer than supported version {}",
+                path.display(),
+                config.version,
+                CONFIG_VERSION
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// Name of environment variable which overrides given key, e.g.
+    /// `NEURONEK_UNITS_MASS` for `units.mass`.
+    pub fn environment_variable(key: &str) -> String
+    {
+        format!("NEURONEK_{}", key.replace('.', "_").to_uppercase())
+    }
+
+    fn check_key(key: &str) -> miette::Result<()>
+    {
+        match Self::KEYS.contains(&key)
+        {
+            | true => Ok(()),
+            | false => Err(miette!(
+                help = format!("Available keys: {}", Self::KEYS.join(", ")),
+                "Unknown configuration key \"{}\"",
+                key
+            )),
+        }
+    }
+
+    fn to_table(&self) -> miette::Result<toml::Table>
+    {
+        toml::Table::try_from(self).into_diagnostic()
+    }
+
+    /// Value of setting with given key, `None` when it's not set.
+    pub fn get(&self, key: &str) -> miette::Result<Option<String>>
+    {
+        Self::check_key(key)?;
+
+        let table = self.to_table()?;
+
+        let (parent, name) = split_key(key);
+        let value = parent
+            .iter()
+            .try_fold(&table, |table, part| table.get(*part)?.as_table())
+            .and_then(|table| table.get(name));
+
+        Ok(value.map(|value| match value
+        {
+            | toml::Value::String(value) => value.clone(),
+            | value => value.to_string(),
+        }))
+    }
+
+    /// Change setting with given key, value is interpreted as TOML value
+    /// (e.g. `true`) or as a string if it's not one. Boolean settings accept
+    /// `yes`/`no`, `on`/`off` and `1`/`0` as well. Empty value restores the
+    /// default.
+    pub fn set(&mut self, key: &str, value: &str) -> miette::Result<()>
+    {
+        Self::check_key(key)?;
+
+        let mut table = self.to_table()?;
+        let (parent, name) = split_key(key);
+
+        let mut target = &mut table;
+
+        for part in parent
+        {
+            target = target
+                .entry(part)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| miette!("Configuration key {} is not a table", part))?;
+        }
+
+        match value.trim()
+        {
+            | "" =>
+            {
+                target.remove(name);
+            }
+            | value if matches!(target.get(name), Some(toml::Value::Boolean(_))) =>
+            {
+                let parsed = match value.to_lowercase().as_str()
+                {
+                    | "true" | "yes" | "on" | "1" => true,
+                    | "false" | "no" | "off" | "0" => false,
+                    | _ =>
+                    {
+                        return Err(miette!(
+                            help = "Use true, false, yes, no, on, off, 1 or 0",
+                            "Invalid value \"{}\" of {}",
+                            value,
+                            key
+                        ));
+                    }
+                };
+                target.insert(name.to_string(), toml::Value::Boolean(parsed));
+            }
+            | value =>
+            {
+                let parsed = toml::from_str::<toml::Table>(&format!("value = {}", value))
+                    .ok()
+                    .and_then(|mut table| table.remove("value"))
+                    .unwrap_or_else(|| toml::Value::String(value.to_string()));
+                target.insert(name.to_string(), parsed);
+            }
         }
+
+        // Removed keys take their default values.
+        let config: Config = table
+            .try_into()
+            .into_diagnostic()
+            .wrap_err(format!("Invalid value \"{}\" of {}", value, key))?;
+
+        config.validate()?;
+        *self = config;
+
+        Ok(())
+    }
+
+    pub fn save(&self) -> miette::Result<()> { self.save_to(&Self::path()) }
+
+    pub fn save_to(&self, path: &Path) -> miette::Result<()>
+    {
+        if let Some(parent) = path.parent()
+        {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+
+        let content = toml::to_string_pretty(self).into_diagnostic()?;
+
+        // Other running instances may read the file in the meantime, so it's
+        // replaced at once instead of being rewritten in place.
+        let temporary_path = path.with_extension(format!("toml.{}", std::process::id()));
+        std::fs::write(&temporary_path, content).into_diagnostic()?;
+        std::fs::rename(&temporary_path, path).into_diagnostic()
+    }
+
+    pub fn validate(&self) -> miette::Result<()>
+    {
+        if self.sqlite_path.as_os_str().is_empty()
+        {
+            return Err(miette!("Database path cannot be empty"));
+        }
+
+        if self.sqlite_path.is_dir()
+        {
+            return Err(miette!(
+                help = format!(
+                    "Provide path to a file, e.g. {}",
+                    self.sqlite_path.join("journal.db").display()
+                ),
+                "Database path {} is a directory",
+                self.sqlite_path.display()
+            ));
+        }
+
+        if self.sqlite_path.to_str().is_none()
+        {
+            return Err(miette!("Database path must be valid UTF-8"));
+        }
+
+        Ok(())
+    }
+
+    /// Load configuration file and environment overrides as the current
+    /// configuration. Invalid configuration is an error, falling back to
+    /// defaults would quietly switch to the default journal.
+    pub fn initialize() -> miette::Result<()>
+    {
+        let config = Self::load()?;
+        *CONFIG.write().expect("configuration lock poisoned") = config;
+
+        Ok(())
+    }
+
+    /// Use another journal database for the rest of the process.
+    pub fn use_database(path: PathBuf)
+    {
+        CONFIG.write().expect("configuration lock poisoned").sqlite_path = path;
+    }
+
+    /// Currently used configuration.
+    pub fn current() -> Self { CONFIG.read().expect("configuration lock poisoned").clone() }
+
+    /// Validate and persist configuration, then make it the current one.
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub fn apply(self) -> miette::Result<()>
+    {
+        self.validate()?;
+        self.save()?;
+
+        *CONFIG.write().expect("configuration lock poisoned") = self;
+
+        Ok(())
+    }
+}
+
+/// Split key into names of tables containing the setting and its own name.
+fn split_key(key: &str) -> (Vec<&str>, &str)
+{
+    match key.rsplit_once('.')
+    {
+        | Some((parent, name)) => (parent.split('.').collect(), name),
+        | None => (vec![], key),
     }
 }
 
 lazy_static! {
-    pub static ref CONFIG: Config = Config::default();
+    /// Configuration of the process, it's loaded by [`Config::initialize`]
+    /// when the application starts.
+    pub static ref CONFIG: RwLock<Config> = RwLock::new(Config::default());
 }
@@ -3,6 +3,7 @@ use crate::utils::AppContext;
 pub mod config;
 pub(crate) mod error_handling;
 pub(crate) mod logging;
+pub mod profile;
 
 #[async_trait::async_trait]
 pub trait CommandHandler<O = ()>
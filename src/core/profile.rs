@@ -0,0 +1,172 @@
+use crate::core::config::CONFIG_DIR;
+use crate::core::config::DATA_DIR;
+use miette::IntoDiagnostic;
+use miette::miette;
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Profile chosen with `--profile` flag.
+static PROFILE_NAME: OnceLock<String> = OnceLock::new();
+
+/// Named journal with its own database and settings, the default profile
+/// uses the main configuration file and journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile
+{
+    pub name: String,
+}
+
+impl Profile
+{
+    pub fn new(name: &str) -> miette::Result<Self>
+    {
+        let is_valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        match is_valid
+        {
+            | true => Ok(Self {
+                name: name.to_string(),
+            }),
+            | false => Err(miette!(
+                help = "Use letters, digits, \"-\" and \"_\" only",
+                "Invalid profile name \"{}\"",
+                name
+            )),
+        }
+    }
+
+    pub fn is_default(&self) -> bool { self.name == DEFAULT_PROFILE }
+
+    /// File in which name of the active profile is stored.
+    fn active_profile_path() -> PathBuf { CONFIG_DIR.join("profile") }
+
+    fn profiles_dir() -> PathBuf { CONFIG_DIR.join("profiles") }
+
+    pub fn config_path(&self) -> PathBuf
+    {
+        match self.is_default()
+        {
+            | true => CONFIG_DIR.join("config.toml"),
+            | false => Self::profiles_dir().join(format!("{}.toml", self.name)),
+        }
+    }
+
+    /// Database used by the profile unless its configuration says otherwise.
+    pub fn database_path(&self) -> PathBuf
+    {
+        match self.is_default()
+        {
+            | true => DATA_DIR.join("journal.db"),
+            | false => DATA_DIR.join("profiles").join(format!("{}.db", self.name)),
+        }
+    }
+
+    /// The default profile always exists, other ones exist once they're
+    /// created.
+    pub fn exists(&self) -> bool { self.is_default() || self.config_path().exists() }
+
+    /// Use another profile for the rest of the process, has effect only
+    /// before configuration is accessed for the first time.
+    pub fn use_name(name: &str) -> miette::Result<()>
+    {
+        let profile = Self::new(name)?;
+
+        if !profile.exists()
+        {
+            return Err(miette!(
+                help = format!("Create it with `neuronek profile create {}`", name),
+                "Profile \"{}\" doesn't exist",
+                name
+            ));
+        }
+
+        let _ = PROFILE_NAME.set(profile.name);
+        Ok(())
+    }
+
+    /// Profile chosen with `--profile` flag, `NEURONEK_PROFILE` environment
+    /// variable or `profile switch` command, in this order.
+    pub fn active() -> Self
+    {
+        let name = PROFILE_NAME
+            .get()
+            .cloned()
+            .or_else(|| env::var("NEURONEK_PROFILE").ok())
+            .or_else(|| std::fs::read_to_string(Self::active_profile_path()).ok())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+
+        name.and_then(|name| Self::new(&name).ok())
+            .filter(Self::exists)
+            .unwrap_or_else(|| Self {
+                name: DEFAULT_PROFILE.to_string(),
+            })
+    }
+
+    /// All existing profiles sorted by name, starting with the default one.
+    pub fn list() -> miette::Result<Vec<Self>>
+    {
+        let mut profiles = vec![];
+
+        if Self::profiles_dir().exists()
+        {
+            for entry in std::fs::read_dir(Self::profiles_dir()).into_diagnostic()?
+            {
+                let path = entry.into_diagnostic()?.path();
+
+                if path.extension().is_some_and(|extension| extension == "toml")
+                {
+                    if let Some(profile) = path
+                        .file_stem()
+                        .and_then(|name| name.to_str())
+                        .and_then(|name| Self::new(name).ok())
+                    {
+                        profiles.push(profile);
+                    }
+                }
+            }
+        }
+
+        profiles.retain(|profile| !profile.is_default());
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        profiles.insert(0, Self::new(DEFAULT_PROFILE)?);
+
+        Ok(profiles)
+    }
+
+    /// Make the profile active for following runs of the application.
+    pub fn switch(&self) -> miette::Result<()>
+    {
+        if !self.exists()
+        {
+            return Err(miette!("Profile \"{}\" doesn't exist", self.name));
+        }
+
+        std::fs::create_dir_all(&*CONFIG_DIR).into_diagnostic()?;
+        std::fs::write(Self::active_profile_path(), &self.name).into_diagnostic()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_profile_paths()
+    {
+        let profile = Profile::new("experiment").unwrap();
+
+        assert!(profile.config_path().ends_with("profiles/experiment.toml"));
+        assert!(profile.database_path().ends_with("profiles/experiment.db"));
+        assert!(Profile::new(DEFAULT_PROFILE).unwrap().is_default());
+        assert!(Profile::new("../escape").is_err());
+        assert!(Profile::new("").is_err());
+    }
+}
@@ -18,8 +18,15 @@ pub struct Model
     pub id: i32,
     pub substance_name: String,
     pub route_of_administration: String,
-    pub dosage: f32,
+    pub dosage: Option<f32>,
+    pub dosage_max: Option<f32>,
+    pub dosage_approximate: bool,
     pub dosage_classification: Option<String>,
+    pub dosage_classification_max: Option<String>,
+    pub amount: Option<f64>,
+    pub amount_max: Option<f64>,
+    pub amount_unit: Option<String>,
+    pub solution_id: Option<i32>,
     pub ingested_at: DateTime,
     pub updated_at: DateTime,
     pub created_at: DateTime,
@@ -32,7 +39,14 @@ pub enum Column
     SubstanceName,
     RouteOfAdministration,
     Dosage,
+    DosageMax,
+    DosageApproximate,
     DosageClassification,
+    DosageClassificationMax,
+    Amount,
+    AmountMax,
+    AmountUnit,
+    SolutionId,
     IngestedAt,
     UpdatedAt,
     CreatedAt,
@@ -54,6 +68,7 @@ impl PrimaryKeyTrait for PrimaryKey
 pub enum Relation
 {
     IngestionPhase,
+    Solution,
 }
 
 impl ColumnTrait for Column
@@ -66,8 +81,15 @@ impl ColumnTrait for Column
             | Self::Id => ColumnType::Integer.def(),
             | Self::SubstanceName => ColumnType::String(StringLen::None).def(),
             | Self::RouteOfAdministration => ColumnType::String(StringLen::None).def(),
-            | Self::Dosage => ColumnType::Float.def(),
+            | Self::Dosage => ColumnType::Float.def().null(),
+            | Self::DosageMax => ColumnType::Float.def().null(),
+            | Self::DosageApproximate => ColumnType::Boolean.def(),
             | Self::DosageClassification => ColumnType::Text.def().null(),
+            | Self::DosageClassificationMax => ColumnType::Text.def().null(),
+            | Self::Amount => ColumnType::Double.def().null(),
+            | Self::AmountMax => ColumnType::Double.def().null(),
+            | Self::AmountUnit => ColumnType::Text.def().null(),
+            | Self::SolutionId => ColumnType::Integer.def().null(),
             | Self::IngestedAt => ColumnType::DateTime.def(),
             | Self::UpdatedAt => ColumnType::DateTime.def(),
             | Self::CreatedAt => ColumnType::DateTime.def(),
@@ -82,6 +104,10 @@ impl RelationTrait for Relation
         match self
         {
             | Self::IngestionPhase => Entity::has_many(super::ingestion_phase::Entity).into(),
+            | Self::Solution => Entity::belongs_to(super::solution::Entity)
+                .from(Column::SolutionId)
+                .to(super::solution::Column::Id)
+                .into(),
         }
     }
 }
@@ -91,4 +117,9 @@ impl Related<super::ingestion_phase::Entity> for Entity
     fn to() -> RelationDef { Relation::IngestionPhase.def() }
 }
 
+impl Related<super::solution::Entity> for Entity
+{
+    fn to() -> RelationDef { Relation::Solution.def() }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -2,9 +2,13 @@
 
 pub mod prelude;
 
+pub mod body_weight;
 pub mod ingestion;
 pub mod ingestion_phase;
+pub mod solution;
 pub mod substance;
+pub mod substance_bioavailability;
 pub mod substance_route_of_administration;
 pub mod substance_route_of_administration_dosage;
 pub mod substance_route_of_administration_phase;
+pub mod substance_unit_conversion;
@@ -1,8 +1,12 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.4
 
+pub use super::body_weight::Entity as BodyWeight;
 pub use super::ingestion::Entity as Ingestion;
 pub use super::ingestion_phase::Entity as IngestionPhase;
+pub use super::solution::Entity as Solution;
 pub use super::substance::Entity as Substance;
+pub use super::substance_bioavailability::Entity as SubstanceBioavailability;
 pub use super::substance_route_of_administration::Entity as SubstanceRouteOfAdministration;
 pub use super::substance_route_of_administration_dosage::Entity as SubstanceRouteOfAdministrationDosage;
 pub use super::substance_route_of_administration_phase::Entity as SubstanceRouteOfAdministrationPhase;
+pub use super::substance_unit_conversion::Entity as SubstanceUnitConversion;
@@ -0,0 +1,679 @@
+use crate::database::entities::ingestion;
+use crate::database::entities::ingestion_phase;
+use crate::database::entities::solution;
+use crate::database::migrator::SCHEMA_VERSION;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::DosageEstimate;
+use crate::substance::route_of_administration::dosage::Estimate;
+use crate::substance::route_of_administration::dosage::MassUnit;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDateTime;
+use chrono::Utc;
+use miette::IntoDiagnostic;
+use sea_orm::ColumnTrait;
+use sea_orm::ConnectionTrait;
+use sea_orm::DatabaseConnection;
+use sea_orm::DbBackend;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use sea_orm::Statement;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Identifies files produced by `neuronek export`.
+pub const EXPORT_FORMAT: &str = "neuronek-journal";
+
+/// Version of the export schema described in `docs/export.md`, it changes
+/// whenever a field is renamed, removed or changes its meaning.
+pub const EXPORT_VERSION: u32 = 3;
+
+/// Ingestion journal in a form which doesn't depend on the database schema.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalExport
+{
+    pub format: String,
+    pub version: u32,
+    /// Database schema version of the exported journal.
+    pub schema_version: u32,
+    pub exported_at: DateTime<Local>,
+    pub ingestions: Vec<ExportedIngestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedIngestion
+{
+    pub id: i32,
+    pub substance_name: String,
+    pub route_of_administration: String,
+    /// Dosage in milligrams, lower bound of a dosage range and `None` when
+    /// dosage is unknown.
+    pub dosage_mg: Option<f64>,
+    /// Upper bound of a dosage range in milligrams.
+    #[serde(default)]
+    pub dosage_max_mg: Option<f64>,
+    #[serde(default)]
+    pub dosage_approximate: bool,
+    pub dosage_classification: Option<String>,
+    /// Classification of the upper bound of a dosage range.
+    #[serde(default)]
+    pub dosage_classification_max: Option<String>,
+    /// Amount in the unit it was logged in (e.g. `ml` or `tablets`), both are
+    /// `None` for ingestions logged as a mass.
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub amount_max: Option<f64>,
+    #[serde(default)]
+    pub amount_unit: Option<String>,
+    /// Solution the ingestion was dosed from, `amount` is then its volume.
+    #[serde(default)]
+    pub solution: Option<ExportedSolution>,
+    pub ingested_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub phases: Vec<ExportedPhase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSolution
+{
+    pub id: i32,
+    pub name: String,
+    pub substance_name: String,
+    /// Mass of substance dissolved in the solvent in milligrams.
+    pub dissolved_mg: f64,
+    /// Volume of solvent in milliliters.
+    pub solvent_volume_ml: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedPhase
+{
+    pub id: String,
+    pub classification: String,
+    pub start_date_min: DateTime<Utc>,
+    pub start_date_max: DateTime<Utc>,
+    pub end_date_min: DateTime<Utc>,
+    pub end_date_max: DateTime<Utc>,
+    /// Shortest duration of the phase in minutes.
+    pub duration_min: i32,
+    /// Longest duration of the phase in minutes.
+    pub duration_max: i32,
+    pub common_dosage_weight: i32,
+    pub notes: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Columns of CSV export, every row is a single phase of an ingestion
+/// and ingestions without phases take a single row with empty phase columns.
+const CSV_HEADER: [&str; 26] = [
+    "ingestion_id",
+    "substance_name",
+    "route_of_administration",
+    "dosage_mg",
+    "dosage_max_mg",
+    "dosage_approximate",
+    "dosage_classification",
+    "dosage_classification_max",
+    "amount",
+    "amount_max",
+    "amount_unit",
+    "solution_name",
+    "solution_dissolved_mg",
+    "solution_volume_ml",
+    "ingested_at",
+    "created_at",
+    "updated_at",
+    "phase_id",
+    "phase_classification",
+    "phase_start_date_min",
+    "phase_start_date_max",
+    "phase_end_date_min",
+    "phase_end_date_max",
+    "phase_duration_min",
+    "phase_duration_max",
+    "phase_notes",
+];
+
+fn utc(date: NaiveDateTime) -> DateTime<Utc> { date.and_utc() }
+
+/// Dosages are stored in kilograms, those of ingestions as single precision
+/// numbers, so anything below a microgram is noise.
+fn milligrams(dosage: impl Into<f64>) -> f64 { (dosage.into() * 1_000_000_000.0).round() / 1_000.0 }
+
+fn optional(value: Option<impl ToString>) -> String
+{
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+impl ExportedIngestion
+{
+    /// Dosage as precisely as it's known.
+    pub fn dosage_estimate(&self) -> DosageEstimate
+    {
+        Estimate::from_bounds(
+            self.dosage_mg.map(Dosage::from_miligrams),
+            self.dosage_max_mg.map(Dosage::from_miligrams),
+            self.dosage_approximate,
+        )
+    }
+
+    /// Amount in the unit it was logged in, when it wasn't a mass.
+    pub fn amount_estimate(&self) -> Option<Estimate<Amount>>
+    {
+        Estimate::<Amount>::from_stored(
+            self.amount,
+            self.amount_max,
+            self.amount_unit.as_deref(),
+            self.dosage_approximate,
+        )
+    }
+
+    /// Dosage with the amount it was logged in, e.g. "2 cups (190 mg)".
+    pub fn dosage(&self) -> String
+    {
+        let dosage = self.dosage_estimate();
+
+        match self.amount_estimate()
+        {
+            | Some(amount) => amount.display_with_mass(&dosage, MassUnit::Auto),
+            | None => dosage.to_string(),
+        }
+    }
+}
+
+impl From<solution::Model> for ExportedSolution
+{
+    fn from(solution: solution::Model) -> Self
+    {
+        Self {
+            id: solution.id,
+            name: solution.name,
+            substance_name: solution.substance_name,
+            dissolved_mg: milligrams(solution.dissolved_dosage),
+            solvent_volume_ml: solution.solvent_volume,
+            created_at: utc(solution.created_at),
+            updated_at: utc(solution.updated_at),
+        }
+    }
+}
+
+impl From<ingestion_phase::Model> for ExportedPhase
+{
+    fn from(phase: ingestion_phase::Model) -> Self
+    {
+        Self {
+            id: phase.id,
+            classification: phase.classification,
+            start_date_min: utc(phase.start_date_min),
+            start_date_max: utc(phase.start_date_max),
+            end_date_min: utc(phase.end_date_min),
+            end_date_max: utc(phase.end_date_max),
+            duration_min: phase.duration_min,
+            duration_max: phase.duration_max,
+            common_dosage_weight: phase.common_dosage_weight,
+            notes: phase.notes,
+            created_at: phase.created_at,
+            updated_at: phase.updated_at,
+        }
+    }
+}
+
+/// Read ingestions which happened within given dates together with their
+/// phases and solutions, ordered by date of ingestion.
+pub async fn export(
+    database_connection: &DatabaseConnection,
+    from: Option<DateTime<Local>>,
+    to: Option<DateTime<Local>>,
+) -> miette::Result<JournalExport>
+{
+    let mut query = ingestion::Entity::find();
+
+    if let Some(from) = from
+    {
+        query = query.filter(ingestion::Column::IngestedAt.gte(from.to_utc().naive_utc()));
+    }
+
+    if let Some(to) = to
+    {
+        query = query.filter(ingestion::Column::IngestedAt.lte(to.to_utc().naive_utc()));
+    }
+
+    let ingestions = query
+        .order_by_asc(ingestion::Column::IngestedAt)
+        .order_by_asc(ingestion::Column::Id)
+        .find_with_related(ingestion_phase::Entity)
+        .all(database_connection)
+        .await
+        .into_diagnostic()?;
+
+    let solutions: HashMap<i32, solution::Model> = solution::Entity::find()
+        .all(database_connection)
+        .await
+        .into_diagnostic()?
+        .into_iter()
+        .map(|solution| (solution.id, solution))
+        .collect();
+
+    Ok(JournalExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        schema_version: SCHEMA_VERSION,
+        exported_at: Local::now(),
+        ingestions: ingestions
+            .into_iter()
+            .map(|(ingestion, mut phases)| {
+                phases.sort_by_key(|phase| phase.start_date_min);
+
+                ExportedIngestion {
+                    id: ingestion.id,
+                    substance_name: ingestion.substance_name,
+                    route_of_administration: ingestion.route_of_administration,
+                    dosage_mg: ingestion.dosage.map(milligrams),
+                    dosage_max_mg: ingestion.dosage_max.map(milligrams),
+                    dosage_approximate: ingestion.dosage_approximate,
+                    dosage_classification: ingestion.dosage_classification,
+                    dosage_classification_max: ingestion.dosage_classification_max,
+                    amount: ingestion.amount,
+                    amount_max: ingestion.amount_max,
+                    amount_unit: ingestion.amount_unit,
+                    solution: ingestion
+                        .solution_id
+                        .and_then(|id| solutions.get(&id))
+                        .cloned()
+                        .map(ExportedSolution::from),
+                    ingested_at: utc(ingestion.ingested_at),
+                    created_at: utc(ingestion.created_at),
+                    updated_at: utc(ingestion.updated_at),
+                    phases: phases.into_iter().map(ExportedPhase::from).collect(),
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Quote CSV field when it contains a separator, a quote or a line break.
+fn csv_field(value: &str) -> String
+{
+    match value.contains([',', '"', '\n', '\r'])
+    {
+        | true => format!("\"{}\"", value.replace('"', "\"\"")),
+        | false => value.to_string(),
+    }
+}
+
+fn csv_row(fields: &[String]) -> String
+{
+    fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// SQL literal of given text, `NULL` when there is none.
+fn sql_text(value: Option<&str>) -> String
+{
+    match value
+    {
+        | Some(value) => format!("'{}'", value.replace('\'', "''")),
+        | None => "NULL".to_string(),
+    }
+}
+
+/// SQL literal of given number, `NULL` when there is none.
+fn sql_number(value: Option<f64>) -> String
+{
+    value.map_or("NULL".to_string(), |value| value.to_string())
+}
+
+/// Escape text value of iCalendar property.
+fn ical_text(value: &str) -> String
+{
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn ical_date(date: &DateTime<Utc>) -> String { date.format("%Y%m%dT%H%M%SZ").to_string() }
+
+/// Fold content line to lines of at most 75 octets as iCalendar requires,
+/// continuation lines start with a space.
+fn ical_line(line: &str) -> String
+{
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for c in line.chars()
+    {
+        if length + c.len_utf8() > 75
+        {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded + "\r\n"
+}
+
+/// Dates are stored in the same format as the database does.
+fn sql_date(date: &DateTime<Utc>) -> String { sql_text(Some(&date.naive_utc().to_string())) }
+
+impl JournalExport
+{
+    pub fn to_json(&self) -> miette::Result<String>
+    {
+        serde_json::to_string_pretty(self).into_diagnostic()
+    }
+
+    pub fn to_csv(&self) -> String
+    {
+        let mut rows = vec![csv_row(&CSV_HEADER.map(String::from))];
+
+        for ingestion in &self.ingestions
+        {
+            let columns = vec![
+                ingestion.id.to_string(),
+                ingestion.substance_name.clone(),
+                ingestion.route_of_administration.clone(),
+                optional(ingestion.dosage_mg),
+                optional(ingestion.dosage_max_mg),
+                ingestion.dosage_approximate.to_string(),
+                ingestion.dosage_classification.clone().unwrap_or_default(),
+                ingestion.dosage_classification_max.clone().unwrap_or_default(),
+                optional(ingestion.amount),
+                optional(ingestion.amount_max),
+                ingestion.amount_unit.clone().unwrap_or_default(),
+                optional(ingestion.solution.as_ref().map(|solution| &solution.name)),
+                optional(ingestion.solution.as_ref().map(|solution| solution.dissolved_mg)),
+                optional(ingestion.solution.as_ref().map(|solution| solution.solvent_volume_ml)),
+                ingestion.ingested_at.to_rfc3339(),
+                ingestion.created_at.to_rfc3339(),
+                ingestion.updated_at.to_rfc3339(),
+            ];
+
+            if ingestion.phases.is_empty()
+            {
+                let mut row = columns.clone();
+                row.resize(CSV_HEADER.len(), String::new());
+                rows.push(csv_row(&row));
+            }
+
+            for phase in &ingestion.phases
+            {
+                let mut row = columns.clone();
+                row.extend([
+                    phase.id.clone(),
+                    phase.classification.clone(),
+                    phase.start_date_min.to_rfc3339(),
+                    phase.start_date_max.to_rfc3339(),
+                    phase.end_date_min.to_rfc3339(),
+                    phase.end_date_max.to_rfc3339(),
+                    phase.duration_min.to_string(),
+                    phase.duration_max.to_string(),
+                    phase.notes.clone().unwrap_or_default(),
+                ]);
+                rows.push(csv_row(&row));
+            }
+        }
+
+        rows.join("\n")
+    }
+
+    /// iCalendar with an event for every ingestion lasting until end of its
+    /// last phase, phases become separate events when `include_phases` is set.
+    pub fn to_ical(&self, include_phases: bool) -> String
+    {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:-//neuronek//neuronek {}//EN", env!("CARGO_PKG_VERSION")),
+            "CALSCALE:GREGORIAN".to_string(),
+            "X-WR-CALNAME:neuronek".to_string(),
+        ];
+        let timestamp = ical_date(&self.exported_at.to_utc());
+
+        for ingestion in &self.ingestions
+        {
+            let dosage = ingestion.dosage();
+            let classification = match (
+                &ingestion.dosage_classification,
+                &ingestion.dosage_classification_max,
+            )
+            {
+                | (Some(lower), Some(upper)) => format!("{} - {}", lower, upper),
+                | (Some(classification), None) => classification.clone(),
+                | (None, _) => "Unknown".to_string(),
+            };
+            let description = format!(
+                "Dosage: {}\nRoute: {}\nClassification: {}",
+                dosage, ingestion.route_of_administration, classification
+            );
+            let end = ingestion
+                .phases
+                .iter()
+                .map(|phase| phase.end_date_max)
+                .max()
+                .unwrap_or(ingestion.ingested_at);
+
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:ingestion-{}@neuronek", ingestion.id),
+                format!("DTSTAMP:{}", timestamp),
+                format!("DTSTART:{}", ical_date(&ingestion.ingested_at)),
+                format!("DTEND:{}", ical_date(&end)),
+                format!(
+                    "SUMMARY:{}",
+                    ical_text(&format!(
+                        "{} {} ({})",
+                        ingestion.substance_name, dosage, ingestion.route_of_administration
+                    ))
+                ),
+                format!("DESCRIPTION:{}", ical_text(&description)),
+                "END:VEVENT".to_string(),
+            ]);
+
+            if !include_phases
+            {
+                continue;
+            }
+
+            for phase in &ingestion.phases
+            {
+                let mut description = format!(
+                    "{}\nDuration: {}-{} minutes",
+                    description, phase.duration_min, phase.duration_max
+                );
+
+                if let Some(notes) = phase.notes.as_deref().filter(|notes| !notes.is_empty())
+                {
+                    description = format!("{}\nNotes: {}", description, notes);
+                }
+
+                lines.extend([
+                    "BEGIN:VEVENT".to_string(),
+                    format!("UID:phase-{}@neuronek", phase.id),
+                    format!("DTSTAMP:{}", timestamp),
+                    format!("DTSTART:{}", ical_date(&phase.start_date_min)),
+                    format!("DTEND:{}", ical_date(&phase.end_date_max)),
+                    format!(
+                        "SUMMARY:{}",
+                        ical_text(&format!(
+                            "{}: {}",
+                            ingestion.substance_name, phase.classification
+                        ))
+                    ),
+                    format!("DESCRIPTION:{}", ical_text(&description)),
+                    "END:VEVENT".to_string(),
+                ]);
+            }
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+
+        lines.iter().map(|line| ical_line(line)).collect()
+    }
+
+    /// SQL script which recreates journal tables (unless they exist) and
+    /// inserts exported rows into them, for use with `sqlite3`. Solutions
+    /// come first, so ingestions can reference them.
+    pub async fn to_sql(&self, database_connection: &DatabaseConnection) -> miette::Result<String>
+    {
+        let schema = database_connection
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT sql FROM sqlite_master WHERE tbl_name IN ('solution', 'ingestion', \
+                 'ingestion_phase') AND sql IS NOT NULL ORDER BY tbl_name = 'solution' DESC, \
+                 tbl_name, type DESC",
+            ))
+            .await
+            .into_diagnostic()?;
+
+        let mut sql = String::new();
+        let _ = writeln!(
+            sql,
+            "-- {} export version {}, schema version {}, exported at {}",
+            EXPORT_FORMAT,
+            self.version,
+            self.schema_version,
+            self.exported_at.to_rfc3339()
+        );
+        sql.push_str("BEGIN TRANSACTION;\n");
+
+        for row in schema
+        {
+            let statement: String = row.try_get_by_index(0).into_diagnostic()?;
+            let statement = statement
+                .replacen("CREATE TABLE ", "CREATE TABLE IF NOT EXISTS ", 1)
+                .replacen("CREATE INDEX ", "CREATE INDEX IF NOT EXISTS ", 1)
+                .replacen("CREATE UNIQUE INDEX ", "CREATE UNIQUE INDEX IF NOT EXISTS ", 1);
+            let _ = writeln!(sql, "{};", statement);
+        }
+
+        let mut solutions = HashMap::new();
+
+        for solution in self.ingestions.iter().filter_map(|ingestion| ingestion.solution.as_ref())
+        {
+            solutions.insert(solution.id, solution);
+        }
+
+        let mut solutions: Vec<_> = solutions.into_values().collect();
+        solutions.sort_by_key(|solution| solution.id);
+
+        // Solution may already be in the journal the script is applied to.
+        for solution in solutions
+        {
+            let _ = writeln!(
+                sql,
+                "INSERT OR IGNORE INTO solution (id, name, substance_name, dissolved_dosage, \
+                 solvent_volume, created_at, updated_at) VALUES ({}, {}, {}, {}, {}, {}, {});",
+                solution.id,
+                sql_text(Some(&solution.name)),
+                sql_text(Some(&solution.substance_name)),
+                sql_number(Some(solution.dissolved_mg / 1_000_000.0)),
+                sql_number(Some(solution.solvent_volume_ml)),
+                sql_date(&solution.created_at),
+                sql_date(&solution.updated_at),
+            );
+        }
+
+        for ingestion in &self.ingestions
+        {
+            let _ = writeln!(
+                sql,
+                "INSERT INTO ingestion (id, substance_name, route_of_administration, dosage, \
+                 dosage_max, dosage_approximate, dosage_classification, dosage_classification_max, \
+                 amount, amount_max, amount_unit, solution_id, ingested_at, updated_at, created_at) \
+                 VALUES ({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {});",
+                ingestion.id,
+                sql_text(Some(&ingestion.substance_name)),
+                sql_text(Some(&ingestion.route_of_administration)),
+                sql_number(ingestion.dosage_mg.map(|dosage| dosage / 1_000_000.0)),
+                sql_number(ingestion.dosage_max_mg.map(|dosage| dosage / 1_000_000.0)),
+                ingestion.dosage_approximate,
+                sql_text(ingestion.dosage_classification.as_deref()),
+                sql_text(ingestion.dosage_classification_max.as_deref()),
+                sql_number(ingestion.amount),
+                sql_number(ingestion.amount_max),
+                sql_text(ingestion.amount_unit.as_deref()),
+                ingestion
+                    .solution
+                    .as_ref()
+                    .map_or("NULL".to_string(), |solution| solution.id.to_string()),
+                sql_date(&ingestion.ingested_at),
+                sql_date(&ingestion.updated_at),
+                sql_date(&ingestion.created_at),
+            );
+
+            for phase in &ingestion.phases
+            {
+                let _ = writeln!(
+                    sql,
+                    "INSERT INTO ingestion_phase (id, ingestion_id, classification, start_date_min, \
+                     start_date_max, end_date_min, end_date_max, common_dosage_weight, duration_min, \
+                     duration_max, notes, created_at, updated_at) VALUES ({}, {}, {}, {}, {}, {}, {}, \
+                     {}, {}, {}, {}, {}, {});",
+                    sql_text(Some(&phase.id)),
+                    ingestion.id,
+                    sql_text(Some(&phase.classification)),
+                    sql_date(&phase.start_date_min),
+                    sql_date(&phase.start_date_max),
+                    sql_date(&phase.end_date_min),
+                    sql_date(&phase.end_date_max),
+                    phase.common_dosage_weight,
+                    phase.duration_min,
+                    phase.duration_max,
+                    sql_text(phase.notes.as_deref()),
+                    sql_text(Some(&phase.created_at)),
+                    sql_text(Some(&phase.updated_at)),
+                );
+            }
+        }
+
+        sql.push_str("COMMIT;");
+
+        Ok(sql)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quoting()
+    {
+        assert_eq!(csv_field("caffeine"), "caffeine");
+        assert_eq!(csv_field("felt \"wired\", jittery"), "\"felt \"\"wired\"\", jittery\"");
+        assert_eq!(sql_text(Some("it's")), "'it''s'");
+        assert_eq!(sql_text(None), "NULL");
+        assert_eq!(sql_number(Some(0.5)), "0.5");
+        assert_eq!(sql_number(None), "NULL");
+    }
+
+    #[test]
+    fn test_ical_formatting()
+    {
+        assert_eq!(ical_text("a, b; c\nd"), "a\\, b\\; c\\nd");
+
+        let folded = ical_line(&format!("DESCRIPTION:{}", "x".repeat(100)));
+        let lines: Vec<&str> = folded.trim_end().split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 75);
+        assert!(lines[1].starts_with(' '));
+    }
+}
@@ -0,0 +1,346 @@
+use crate::database::entities::ingestion;
+use crate::database::export::EXPORT_FORMAT;
+use crate::database::export::EXPORT_VERSION;
+use crate::database::export::JournalExport;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::DosageEstimate;
+use crate::substance::route_of_administration::dosage::Estimate;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
+use miette::IntoDiagnostic;
+use miette::WrapErr;
+use miette::miette;
+use sea_orm::DatabaseConnection;
+use sea_orm::EntityTrait;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// Ingestion read from an export, phases and classification are not
+/// imported as they're computed again.
+#[derive(Debug, Clone)]
+pub struct ImportedIngestion
+{
+    pub substance_name: String,
+    pub route: RouteOfAdministrationClassification,
+    pub dosage: DosageEstimate,
+    /// Amount in the unit it was logged in, when it wasn't a mass.
+    pub amount: Option<Estimate<Amount>>,
+    /// Solution the ingestion was dosed from, it's linked to the solution of
+    /// the same name in the journal or created with its concentration.
+    pub solution: Option<ImportedSolution>,
+    pub ingested_at: DateTime<Local>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedSolution
+{
+    pub name: String,
+    pub substance_name: String,
+    /// Mass of substance dissolved in the solvent.
+    pub dissolved: Dosage,
+    /// Volume of solvent in milliliters.
+    pub volume: f64,
+}
+
+/// Ingestions are considered the same when they have the same substance,
+/// dosage (to a microgram, lower bound of a range) and time of ingestion (to
+/// a second).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DuplicateKey
+{
+    substance_name: String,
+    dosage_ug: Option<i64>,
+    ingested_at: i64,
+}
+
+impl DuplicateKey
+{
+    fn new(
+        substance_name: &str,
+        dosage_base_units: Option<f64>,
+        ingested_at: DateTime<Utc>,
+    ) -> Self
+    {
+        Self {
+            substance_name: substance_name.trim().to_lowercase(),
+            dosage_ug: dosage_base_units.map(|dosage| (dosage * 1_000_000_000.0).round() as i64),
+            ingested_at: ingested_at.timestamp(),
+        }
+    }
+}
+
+impl ImportedIngestion
+{
+    pub fn duplicate_key(&self) -> DuplicateKey
+    {
+        DuplicateKey::new(
+            &self.substance_name,
+            self.dosage.lower().map(Dosage::as_base_units),
+            self.ingested_at.to_utc(),
+        )
+    }
+}
+
+/// Keys of all ingestions which are already in the journal.
+pub async fn existing_keys(
+    database_connection: &DatabaseConnection,
+) -> miette::Result<HashSet<DuplicateKey>>
+{
+    Ok(ingestion::Entity::find()
+        .all(database_connection)
+        .await
+        .into_diagnostic()?
+        .iter()
+        .map(|ingestion| {
+            DuplicateKey::new(
+                &ingestion.substance_name,
+                ingestion.dosage.map(f64::from),
+                ingestion.ingested_at.and_utc(),
+            )
+        })
+        .collect())
+}
+
+fn parse_route(route: &str) -> miette::Result<RouteOfAdministrationClassification>
+{
+    route
+        .trim()
+        .to_lowercase()
+        .parse()
+        .map_err(|_| miette!("Unknown route of administration \"{}\"", route))
+}
+
+/// Read ingestions from JSON export.
+pub fn from_json(content: &str) -> miette::Result<Vec<ImportedIngestion>>
+{
+    let export: JournalExport = serde_json::from_str(content)
+        .into_diagnostic()
+        .wrap_err("File is not a valid JSON export")?;
+
+    if export.format != EXPORT_FORMAT
+    {
+        return Err(miette!("Unknown export format \"{}\"", export.format));
+    }
+
+    if export.version > EXPORT_VERSION
+    {
+        return Err(miette!(
+            help = "Update neuronek to import it",
+            "Export version {} is newer than supported version {}",
+            export.version,
+            EXPORT_VERSION
+        ));
+    }
+
+    export
+        .ingestions
+        .into_iter()
+        .map(|ingestion| {
+            Ok(ImportedIngestion {
+                route: parse_route(&ingestion.route_of_administration)
+                    .wrap_err(format!("Invalid ingestion {}", ingestion.id))?,
+                amount: ingestion.amount_estimate(),
+                dosage: ingestion.dosage_estimate(),
+                solution: ingestion.solution.map(|solution| ImportedSolution {
+                    name: solution.name,
+                    substance_name: solution.substance_name,
+                    dissolved: Dosage::from_miligrams(solution.dissolved_mg),
+                    volume: solution.solvent_volume_ml,
+                }),
+                substance_name: ingestion.substance_name,
+                ingested_at: ingestion.ingested_at.with_timezone(&Local),
+            })
+        })
+        .collect()
+}
+
+/// Split CSV into rows of fields, quoted fields may contain separators,
+/// doubled quotes and line breaks.
+fn parse_csv(content: &str) -> miette::Result<Vec<Vec<String>>>
+{
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next()
+    {
+        match (quoted, c)
+        {
+            | (true, '"') if chars.peek() == Some(&'"') =>
+            {
+                chars.next();
+                field.push('"');
+            }
+            | (true, '"') => quoted = false,
+            | (true, c) => field.push(c),
+            | (false, '"') => quoted = true,
+            | (false, ',') => row.push(std::mem::take(&mut field)),
+            | (false, '\r') =>
+            {}
+            | (false, '\n') =>
+            {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            | (false, c) => field.push(c),
+        }
+    }
+
+    if quoted
+    {
+        return Err(miette!("CSV ends inside of a quoted field"));
+    }
+
+    if !field.is_empty() || !row.is_empty()
+    {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.retain(|row| row.iter().any(|field| !field.is_empty()));
+
+    Ok(rows)
+}
+
+pub(crate) fn parse_amount(amount: f64, unit: &str) -> miette::Result<Amount>
+{
+    Amount::from_str(&format!("{} {}", amount, unit)).map_err(|error| miette!("{}", error))
+}
+
+/// Read ingestions from CSV export, rows of the same ingestion (one for
+/// every phase) are imported once.
+pub fn from_csv(content: &str) -> miette::Result<Vec<ImportedIngestion>>
+{
+    let mut rows = parse_csv(content)?.into_iter();
+    let header = rows.next().ok_or_else(|| miette!("CSV file is empty"))?;
+
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|column| column == name)
+            .ok_or_else(|| miette!("CSV file has no \"{}\" column", name))
+    };
+
+    let id = column("ingestion_id").ok();
+    let substance_name = column("substance_name")?;
+    let route = column("route_of_administration")?;
+    let dosage = column("dosage_mg")?;
+    let dosage_max = column("dosage_max_mg").ok();
+    let dosage_approximate = column("dosage_approximate").ok();
+    let ingested_at = column("ingested_at")?;
+    let amount = column("amount").ok();
+    let amount_max = column("amount_max").ok();
+    let amount_unit = column("amount_unit").ok();
+    let solution_name = column("solution_name").ok();
+    let solution_dissolved = column("solution_dissolved_mg").ok();
+    let solution_volume = column("solution_volume_ml").ok();
+
+    let mut seen_ids = HashSet::new();
+    let mut ingestions = vec![];
+
+    for (index, row) in rows.enumerate()
+    {
+        // Header is the first row.
+        let number = index + 2;
+        let field = |column: usize| row.get(column).map(String::as_str).unwrap_or_default();
+
+        if let Some(id) = id
+        {
+            if !field(id).is_empty() && !seen_ids.insert(field(id).to_string())
+            {
+                continue;
+            }
+        }
+
+        let ingestion = (|| -> miette::Result<ImportedIngestion> {
+            if field(substance_name).trim().is_empty()
+            {
+                return Err(miette!("Substance name is missing"));
+            }
+
+            // Empty dosage is unknown and optional columns may be missing.
+            let value = |column: Option<usize>, name: &str| -> miette::Result<Option<f64>> {
+                match column.map(field).map(str::trim).filter(|value| !value.is_empty())
+                {
+                    | Some(value) => value
+                        .parse()
+                        .map(Some)
+                        .map_err(|_| miette!("Invalid {} \"{}\"", name, value)),
+                    | None => Ok(None),
+                }
+            };
+            let approximate = dosage_approximate.is_some_and(|column| field(column) == "true");
+            let unit = amount_unit.map(field).filter(|unit| !unit.trim().is_empty());
+            let solution = match solution_name.map(field).map(str::trim)
+            {
+                | Some(name) if !name.is_empty() => Some(ImportedSolution {
+                    name: name.to_string(),
+                    substance_name: field(substance_name).to_string(),
+                    dissolved: value(solution_dissolved, "solution dissolved mass")?
+                        .map(Dosage::from_miligrams)
+                        .ok_or_else(|| miette!("Solution \"{}\" has no dissolved mass", name))?,
+                    volume: value(solution_volume, "solution volume")?
+                        .ok_or_else(|| miette!("Solution \"{}\" has no volume", name))?,
+                }),
+                | _ => None,
+            };
+
+            Ok(ImportedIngestion {
+                substance_name: field(substance_name).to_string(),
+                route: parse_route(field(route))?,
+                dosage: Estimate::from_bounds(
+                    value(Some(dosage), "dosage")?.map(Dosage::from_miligrams),
+                    value(dosage_max, "dosage")?.map(Dosage::from_miligrams),
+                    approximate,
+                ),
+                amount: match (value(amount, "amount")?, unit)
+                {
+                    | (Some(amount), Some(unit)) => Some(Estimate::from_bounds(
+                        Some(parse_amount(amount, unit)?),
+                        value(amount_max, "amount")?
+                            .map(|amount| parse_amount(amount, unit))
+                            .transpose()?,
+                        approximate,
+                    )),
+                    | _ => None,
+                },
+                solution,
+                ingested_at: DateTime::parse_from_rfc3339(field(ingested_at).trim())
+                    .map_err(|_| miette!("Invalid date \"{}\"", field(ingested_at)))?
+                    .with_timezone(&Local),
+            })
+        })()
+        .wrap_err(format!("Invalid row {} of CSV file", number))?;
+
+        ingestions.push(ingestion);
+    }
+
+    Ok(ingestions)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_csv_import()
+    {
+        let csv = "ingestion_id,substance_name,route_of_administration,dosage_mg,ingested_at,phase_notes\n\
+                   1,caffeine,oral,100,2025-01-01T08:00:00+00:00,\"felt \"\"wired\"\",\nshaky\"\n\
+                   1,caffeine,oral,100,2025-01-01T08:00:00+00:00,\n\
+                   2,Caffeine,oral,100.0,2025-01-01T08:00:00.5+00:00,\n";
+
+        let ingestions = from_csv(csv).unwrap();
+
+        assert_eq!(ingestions.len(), 2);
+        assert_eq!(ingestions[0].route, RouteOfAdministrationClassification::Oral);
+        assert_eq!(ingestions[0].duplicate_key(), ingestions[1].duplicate_key());
+        assert!(from_csv("substance_name\ncaffeine").is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::database::entities::ingestion;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::utils::migrate_database;
+use chrono::Local;
+use miette::IntoDiagnostic;
+use miette::WrapErr;
+use miette::miette;
+use sea_orm::ConnectionTrait;
+use sea_orm::Database;
+use sea_orm::DatabaseConnection;
+use sea_orm::DbBackend;
+use sea_orm::EntityTrait;
+use sea_orm::Statement;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+use tabled::Tabled;
+use tracing::info;
+
+/// Number of backups created automatically before migrations which are kept.
+pub const MIGRATION_BACKUPS_KEPT: usize = 5;
+
+const MIGRATION_BACKUP_LABEL: &str = "pre-migration";
+
+/// Directory with backups of given database, placed next to it.
+pub fn backups_dir(database_path: &Path) -> PathBuf
+{
+    database_path
+        .parent()
+        .map(|parent| parent.join("backups"))
+        .unwrap_or_else(|| PathBuf::from("backups"))
+}
+
+/// Location of a new backup, e.g. `backups/journal-pre-migration-20250101-120000000.db`.
+pub fn backup_path(database_path: &Path, label: &str) -> PathBuf
+{
+    let stem = database_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("journal");
+
+    backups_dir(database_path).join(format!(
+        "{}-{}-{}.db",
+        stem,
+        label,
+        Local::now().format("%Y%m%d-%H%M%S%3f")
+    ))
+}
+
+/// Write consistent copy of the database to given path, database can be used
+/// by other connections in the meantime.
+pub async fn backup(database_connection: &DatabaseConnection, path: &Path) -> miette::Result<()>
+{
+    if path.exists()
+    {
+        return Err(miette!("Backup file {} already exists", path.display()));
+    }
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).into_diagnostic()?;
+    }
+
+    let path = path
+        .to_str()
+        .ok_or_else(|| miette!("Backup path must be valid UTF-8"))?;
+
+    database_connection
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "VACUUM INTO ?",
+            [path.into()],
+        ))
+        .await
+        .into_diagnostic()?;
+
+    info!("Database backed up to {}", path);
+
+    Ok(())
+}
+
+/// Back up database before applying migrations, only the most recent
+/// [`MIGRATION_BACKUPS_KEPT`] of such backups are kept.
+pub async fn migration_backup(
+    database_connection: &DatabaseConnection,
+    database_path: &Path,
+) -> miette::Result<PathBuf>
+{
+    let path = backup_path(database_path, MIGRATION_BACKUP_LABEL);
+    backup(database_connection, &path).await?;
+
+    let stem = database_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("journal");
+    let prefix = format!("{}-{}-", stem, MIGRATION_BACKUP_LABEL);
+
+    // Timestamps in names make alphabetical order chronological.
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(backups_dir(database_path))
+        .into_diagnostic()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix))
+        })
+        .collect();
+    backups.sort();
+
+    for old in backups.iter().rev().skip(MIGRATION_BACKUPS_KEPT)
+    {
+        std::fs::remove_file(old).into_diagnostic()?;
+    }
+
+    Ok(path)
+}
+
+/// Replace database with a backup, current database is backed up first and
+/// the path of that backup is returned. Backup is verified before anything
+/// is changed and migrated after it's restored. Given connection is closed,
+/// it can't be used afterwards.
+pub async fn restore(
+    database_connection: &DatabaseConnection,
+    database_path: &Path,
+    source: &Path,
+) -> miette::Result<PathBuf>
+{
+    if !source.is_file()
+    {
+        return Err(miette!("Backup file {} not found", source.display()));
+    }
+
+    let source_url = format!(
+        "sqlite://{}?mode=ro",
+        source
+            .to_str()
+            .ok_or_else(|| miette!("Backup path must be valid UTF-8"))?
+    );
+    let source_connection = Database::connect(&source_url)
+        .await
+        .into_diagnostic()
+        .map_err(|error| miette!("{} is not a valid database: {}", source.display(), error))?;
+
+    let integrity = integrity_problems(&source_connection)
+        .await
+        .wrap_err(format!("{} is not a valid database", source.display()));
+    let has_journal = table_exists(&source_connection, "ingestion").await;
+    source_connection.close().await.into_diagnostic()?;
+
+    match (integrity, has_journal)
+    {
+        | (Err(error), _) => return Err(error),
+        | (Ok(problems), _) if !problems.is_empty() =>
+        {
+            return Err(miette!(
+                "Backup {} is corrupted: {}",
+                source.display(),
+                problems.join(", ")
+            ));
+        }
+        | (_, Ok(false)) =>
+        {
+            return Err(miette!(
+                "{} doesn't contain an ingestion journal",
+                source.display()
+            ));
+        }
+        | (_, Err(error)) => return Err(error),
+        | _ =>
+        {}
+    }
+
+    let safety_backup = backup_path(database_path, "pre-restore");
+    backup(database_connection, &safety_backup).await?;
+
+    // Pooled connections could keep pages of the replaced file cached or
+    // write them back into the restored one, so all of them are closed
+    // before the file is replaced, together with their write-ahead log and
+    // shared memory.
+    database_connection.get_sqlite_connection_pool().close().await;
+
+    for suffix in ["-wal", "-shm"]
+    {
+        let mut path = database_path.as_os_str().to_owned();
+        path.push(suffix);
+        let path = PathBuf::from(path);
+
+        if path.exists()
+        {
+            std::fs::remove_file(&path).into_diagnostic()?;
+        }
+    }
+
+    // Copy is moved over the database at once, so the journal is never
+    // left half-written.
+    let temporary_path = database_path.with_extension(format!("restore.{}", std::process::id()));
+    std::fs::copy(source, &temporary_path).into_diagnostic()?;
+    std::fs::rename(&temporary_path, database_path).into_diagnostic()?;
+
+    // Backup may have been created by an older version of neuronek.
+    let restored_connection = Database::connect(format!(
+        "sqlite://{}",
+        database_path
+            .to_str()
+            .ok_or_else(|| miette!("Database path must be valid UTF-8"))?
+    ))
+    .await
+    .into_diagnostic()?;
+    migrate_database(&restored_connection).await?;
+    restored_connection.close().await.into_diagnostic()?;
+
+    Ok(safety_backup)
+}
+
+async fn table_exists(database_connection: &DatabaseConnection, table: &str) -> miette::Result<bool>
+{
+    let row = database_connection
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT COUNT(*) AS count FROM sqlite_master WHERE type = 'table' AND name = ?",
+            [table.into()],
+        ))
+        .await
+        .into_diagnostic()?;
+
+    Ok(row
+        .map(|row| row.try_get::<i64>("", "count").unwrap_or(0) > 0)
+        .unwrap_or(false))
+}
+
+/// Problems reported by SQLite's integrity check, empty when there are none.
+async fn integrity_problems(database_connection: &DatabaseConnection) -> miette::Result<Vec<String>>
+{
+    let rows = database_connection
+        .query_all(Statement::from_string(DbBackend::Sqlite, "PRAGMA integrity_check"))
+        .await
+        .into_diagnostic()?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| row.try_get_by_index::<String>(0).ok())
+        .filter(|message| message != "ok")
+        .collect())
+}
+
+/// Number of rows returned by a query counting problems.
+async fn count(database_connection: &DatabaseConnection, sql: &str) -> miette::Result<usize>
+{
+    let row = database_connection
+        .query_one(Statement::from_string(DbBackend::Sqlite, sql))
+        .await
+        .into_diagnostic()?;
+
+    Ok(row
+        .and_then(|row| row.try_get_by_index::<i64>(0).ok())
+        .unwrap_or(0) as usize)
+}
+
+#[derive(Debug, Serialize, Tabled)]
+pub struct CheckResult
+{
+    pub check: String,
+    pub passed: bool,
+    /// Number of problems found.
+    pub problems: usize,
+    pub details: String,
+}
+
+impl CheckResult
+{
+    fn new(check: &str, problems: usize, details: impl Into<String>) -> Self
+    {
+        Self {
+            check: check.to_string(),
+            passed: problems == 0,
+            problems,
+            details: match problems
+            {
+                | 0 => String::new(),
+                | _ => details.into(),
+            },
+        }
+    }
+}
+
+/// Run SQLite integrity checks together with checks of data which database
+/// constraints don't cover.
+pub async fn check(database_connection: &DatabaseConnection) -> miette::Result<Vec<CheckResult>>
+{
+    let integrity = integrity_problems(database_connection).await?;
+    let foreign_keys = database_connection
+        .query_all(Statement::from_string(DbBackend::Sqlite, "PRAGMA foreign_key_check"))
+        .await
+        .into_diagnostic()?
+        .len();
+
+    let orphaned_phases = count(
+        database_connection,
+        "SELECT COUNT(*) FROM ingestion_phase WHERE ingestion_id NOT IN (SELECT id FROM \
+         ingestion)",
+    )
+    .await?;
+
+    // Unknown dosages are stored as NULL and they're valid.
+    let invalid_dosages = count(
+        database_connection,
+        "SELECT COUNT(*) FROM ingestion WHERE dosage <= 0 OR dosage_max < dosage",
+    )
+    .await?;
+
+    let invalid_routes = ingestion::Entity::find()
+        .all(database_connection)
+        .await
+        .into_diagnostic()?
+        .iter()
+        .filter(|ingestion| {
+            ingestion
+                .route_of_administration
+                .parse::<RouteOfAdministrationClassification>()
+                .is_err()
+        })
+        .count();
+
+    Ok(vec![
+        CheckResult::new("integrity", integrity.len(), integrity.join(", ")),
+        CheckResult::new("foreign keys", foreign_keys, "Rows reference missing rows"),
+        CheckResult::new(
+            "orphaned phases",
+            orphaned_phases,
+            "Ingestion phases belong to deleted ingestions",
+        ),
+        CheckResult::new("dosages", invalid_dosages, "Ingestions have invalid dosage"),
+        CheckResult::new(
+            "routes",
+            invalid_routes,
+            "Ingestions have unknown route of administration",
+        ),
+    ])
+}
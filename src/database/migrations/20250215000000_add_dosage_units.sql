@@ -0,0 +1,25 @@
+-- Add amount in the unit of ingestion to "ingestion" table, "dosage" stays its mass
+ALTER TABLE `ingestion` ADD COLUMN `amount` real NULL;
+ALTER TABLE `ingestion` ADD COLUMN `amount_unit` text NULL;
+-- Create "substance_unit_conversion" table, "dosage" is mass of a single unit in kilograms
+CREATE TABLE `substance_unit_conversion`
+(
+    `substance_name` text  NOT NULL,
+    `unit`           text  NOT NULL,
+    `dosage`         float NOT NULL,
+    PRIMARY KEY (`substance_name`, `unit`),
+    CHECK (`dosage` > 0)
+);
+-- Insert known conversions of international units and of liquid substances
+INSERT INTO `substance_unit_conversion` (`substance_name`, `unit`, `dosage`)
+VALUES ('vitamin d', 'iu', 2.5e-11),
+       ('vitamin d2', 'iu', 2.5e-11),
+       ('vitamin d3', 'iu', 2.5e-11),
+       ('ergocalciferol', 'iu', 2.5e-11),
+       ('cholecalciferol', 'iu', 2.5e-11),
+       ('vitamin a', 'iu', 3.0e-10),
+       ('retinol', 'iu', 3.0e-10),
+       ('gbl', 'ml', 1.129e-3),
+       ('gamma-butyrolactone', 'ml', 1.129e-3),
+       ('1,4-butanediol', 'ml', 1.017e-3),
+       ('2m2b', 'ml', 8.05e-4);
@@ -0,0 +1,17 @@
+-- Create "solution" table, "dissolved_dosage" is mass of the substance in kilograms dissolved in "solvent_volume"
+-- milliliters of solvent
+CREATE TABLE `solution`
+(
+    `id`               integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `name`             text          NOT NULL,
+    `substance_name`   text          NOT NULL,
+    `dissolved_dosage` float         NOT NULL,
+    `solvent_volume`   real          NOT NULL,
+    `created_at`       datetime_text NOT NULL,
+    `updated_at`       datetime_text NOT NULL,
+    CHECK (`dissolved_dosage` > 0 AND `solvent_volume` > 0)
+);
+-- Create index "solution_name_key" to table: "solution"
+CREATE UNIQUE INDEX `solution_name_key` ON `solution` (`name`);
+-- Add solution of ingestion to "ingestion" table, its volume is stored as the amount
+ALTER TABLE `ingestion` ADD COLUMN `solution_id` integer NULL REFERENCES `solution` (`id`) ON DELETE SET NULL;
@@ -0,0 +1,11 @@
+-- Create "body_weight" table, history of body weight in kilograms measured at "measured_at"
+CREATE TABLE `body_weight`
+(
+    `id`          integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `weight`      real          NOT NULL,
+    `measured_at` datetime_text NOT NULL,
+    `created_at`  datetime_text NOT NULL,
+    CHECK (`weight` > 0)
+);
+-- Create index "body_weight_measured_at_idx" to table: "body_weight"
+CREATE INDEX `body_weight_measured_at_idx` ON `body_weight` (`measured_at`);
@@ -0,0 +1,30 @@
+-- Create "substance_bioavailability" table, "bioavailability" is the fraction of dosage which reaches systemic
+-- circulation when ingested through "route_of_administration"
+CREATE TABLE `substance_bioavailability`
+(
+    `substance_name`          text  NOT NULL,
+    `route_of_administration` text  NOT NULL,
+    `bioavailability`         float NOT NULL,
+    PRIMARY KEY (`substance_name`, `route_of_administration`),
+    CHECK (`bioavailability` > 0 AND `bioavailability` <= 1)
+);
+-- Insert typical bioavailability of common substances, intravenous route is always complete
+INSERT INTO `substance_bioavailability` (`substance_name`, `route_of_administration`, `bioavailability`)
+VALUES ('alprazolam', 'oral', 0.9),
+       ('amphetamine', 'oral', 0.75),
+       ('buprenorphine', 'sublingual', 0.3),
+       ('caffeine', 'oral', 0.99),
+       ('cannabis', 'oral', 0.06),
+       ('cannabis', 'smoked', 0.25),
+       ('cocaine', 'insufflated', 0.6),
+       ('codeine', 'oral', 0.9),
+       ('diazepam', 'oral', 0.93),
+       ('ketamine', 'oral', 0.17),
+       ('ketamine', 'insufflated', 0.45),
+       ('ketamine', 'sublingual', 0.3),
+       ('ketamine', 'intramuscular', 0.93),
+       ('ketamine', 'rectal', 0.25),
+       ('methylphenidate', 'oral', 0.3),
+       ('morphine', 'oral', 0.3),
+       ('nicotine', 'oral', 0.2),
+       ('oxycodone', 'oral', 0.75);
@@ -0,0 +1,50 @@
+-- Disable the enforcement of foreign-keys constraints
+PRAGMA
+foreign_keys = off;
+-- Create "new_ingestion" table, "dosage" is NULL when it's unknown and it's the lower bound of a range up to
+-- "dosage_max", "amount_max" is the upper bound of the amount
+CREATE TABLE `new_ingestion`
+(
+    `id`                        integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `substance_name`            varchar       NOT NULL,
+    `route_of_administration`   varchar       NOT NULL,
+    `dosage`                    float NULL,
+    `dosage_max`                float NULL,
+    `dosage_approximate`        boolean       NOT NULL DEFAULT false,
+    `dosage_classification`     text NULL,
+    `dosage_classification_max` text NULL,
+    `amount`                    real NULL,
+    `amount_max`                real NULL,
+    `amount_unit`               text NULL,
+    `solution_id`               integer NULL REFERENCES `solution` (`id`) ON DELETE SET NULL,
+    `ingested_at`               datetime_text NOT NULL,
+    `updated_at`                datetime_text NOT NULL,
+    `created_at`                datetime_text NOT NULL,
+    CHECK (`dosage_classification` IN
+           ('Threshold', 'Light', 'Common', 'Strong', 'Heavy')),
+    CHECK (`dosage_classification_max` IN
+           ('Threshold', 'Light', 'Common', 'Strong', 'Heavy')),
+    CHECK (`dosage_max` IS NULL OR `dosage` <= `dosage_max`)
+);
+-- Copy rows from old table "ingestion" to new temporary table "new_ingestion"
+INSERT INTO `new_ingestion` (`id`, `substance_name`, `route_of_administration`, `dosage`, `dosage_classification`,
+                             `amount`, `amount_unit`, `solution_id`, `ingested_at`, `updated_at`, `created_at`)
+SELECT `id`,
+       `substance_name`,
+       `route_of_administration`,
+       `dosage`,
+       `dosage_classification`,
+       `amount`,
+       `amount_unit`,
+       `solution_id`,
+       `ingested_at`,
+       `updated_at`,
+       `created_at`
+FROM `ingestion`;
+-- Drop "ingestion" table after copying rows
+DROP TABLE `ingestion`;
+-- Rename temporary table "new_ingestion" to "ingestion"
+ALTER TABLE `new_ingestion` RENAME TO `ingestion`;
+-- Enable back the enforcement of foreign-keys constraints
+PRAGMA
+foreign_keys = on;
@@ -0,0 +1,39 @@
+-- Phases kept aside by reverting "20250210165025_ingestion_phase_add_bounds", the table is empty on fresh databases
+CREATE TABLE IF NOT EXISTS `ingestion_phase_backup`
+(
+    `id`                   text    NOT NULL,
+    `ingestion_id`         integer NOT NULL,
+    `classification`       text    NOT NULL,
+    `start_date_min`       text    NOT NULL,
+    `start_date_max`       text    NOT NULL,
+    `end_date_min`         text    NOT NULL,
+    `end_date_max`         text    NOT NULL,
+    `common_dosage_weight` integer NOT NULL,
+    `duration_min`         integer NOT NULL,
+    `duration_max`         integer NOT NULL,
+    `notes`                text NULL,
+    `created_at`           text    NOT NULL,
+    `updated_at`           text    NOT NULL,
+    PRIMARY KEY (`id`)
+);
+-- Copy phases of ingestions which still exist back into "ingestion_phase"
+INSERT OR IGNORE INTO `ingestion_phase` (`id`, `ingestion_id`, `classification`, `start_date_min`, `start_date_max`,
+                                         `end_date_min`, `end_date_max`, `common_dosage_weight`, `duration_min`,
+                                         `duration_max`, `notes`, `created_at`, `updated_at`)
+SELECT `id`,
+       `ingestion_id`,
+       `classification`,
+       `start_date_min`,
+       `start_date_max`,
+       `end_date_min`,
+       `end_date_max`,
+       `common_dosage_weight`,
+       `duration_min`,
+       `duration_max`,
+       `notes`,
+       `created_at`,
+       `updated_at`
+FROM `ingestion_phase_backup`
+WHERE `ingestion_id` IN (SELECT `id` FROM `ingestion`);
+-- Drop "ingestion_phase_backup" table after copying rows
+DROP TABLE `ingestion_phase_backup`;
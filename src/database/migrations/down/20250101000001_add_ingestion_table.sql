@@ -0,0 +1,2 @@
+-- Drop "ingestion" table
+DROP TABLE `ingestion`;
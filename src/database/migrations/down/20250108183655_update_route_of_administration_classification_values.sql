@@ -0,0 +1,2 @@
+-- Routes of administration stay normalized, previous schema accepts them as well
+SELECT 1;
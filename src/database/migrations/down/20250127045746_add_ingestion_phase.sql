@@ -0,0 +1,4 @@
+-- Drop "ingestion_phase" table together with its indexes
+DROP TABLE `ingestion_phase`;
+-- Drop index "route_of_administration_dosage_intensivity_routeOfAdministrationId_key" from table: "substance_route_of_administration_dosage"
+DROP INDEX `route_of_administration_dosage_intensivity_routeOfAdministrationId_key`;
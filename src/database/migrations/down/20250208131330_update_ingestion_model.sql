@@ -0,0 +1,26 @@
+-- Disable the enforcement of foreign-keys constraints
+PRAGMA
+foreign_keys = off;
+-- Create "new_ingestion" table without "dosage_classification" column
+CREATE TABLE `new_ingestion`
+(
+    `id`                      integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `substance_name`          varchar       NOT NULL,
+    `route_of_administration` varchar       NOT NULL,
+    `dosage`                  float         NOT NULL,
+    `ingested_at`             datetime_text NOT NULL,
+    `updated_at`              datetime_text NOT NULL,
+    `created_at`              datetime_text NOT NULL
+);
+-- Copy rows from old table "ingestion" to new temporary table "new_ingestion"
+INSERT INTO `new_ingestion` (`id`, `substance_name`, `route_of_administration`, `dosage`, `ingested_at`, `updated_at`,
+                             `created_at`)
+SELECT `id`, `substance_name`, `route_of_administration`, `dosage`, `ingested_at`, `updated_at`, `created_at`
+FROM `ingestion`;
+-- Drop "ingestion" table after copying rows
+DROP TABLE `ingestion`;
+-- Rename temporary table "new_ingestion" to "ingestion"
+ALTER TABLE `new_ingestion` RENAME TO `ingestion`;
+-- Enable back the enforcement of foreign-keys constraints
+PRAGMA
+foreign_keys = on;
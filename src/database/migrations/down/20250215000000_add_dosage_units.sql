@@ -0,0 +1,5 @@
+-- Drop "substance_unit_conversion" table
+DROP TABLE `substance_unit_conversion`;
+-- Drop amount columns from "ingestion" table, ingestions keep their mass
+ALTER TABLE `ingestion` DROP COLUMN `amount_unit`;
+ALTER TABLE `ingestion` DROP COLUMN `amount`;
@@ -0,0 +1,4 @@
+-- Drop solution from "ingestion" table, ingestions keep their volume and mass
+ALTER TABLE `ingestion` DROP COLUMN `solution_id`;
+-- Drop "solution" table
+DROP TABLE `solution`;
@@ -0,0 +1,2 @@
+-- Drop "body_weight" table
+DROP TABLE `body_weight`;
@@ -0,0 +1,2 @@
+-- Drop "substance_bioavailability" table
+DROP TABLE `substance_bioavailability`;
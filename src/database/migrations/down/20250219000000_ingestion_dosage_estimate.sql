@@ -0,0 +1,42 @@
+-- Disable the enforcement of foreign-keys constraints
+PRAGMA
+foreign_keys = off;
+-- Create "new_ingestion" table with required dosage
+CREATE TABLE `new_ingestion`
+(
+    `id`                      integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `substance_name`          varchar       NOT NULL,
+    `route_of_administration` varchar       NOT NULL,
+    `dosage`                  float         NOT NULL,
+    `dosage_classification`   text NULL,
+    `amount`                  real NULL,
+    `amount_unit`             text NULL,
+    `solution_id`             integer NULL REFERENCES `solution` (`id`) ON DELETE SET NULL,
+    `ingested_at`             datetime_text NOT NULL,
+    `updated_at`              datetime_text NOT NULL,
+    `created_at`              datetime_text NOT NULL,
+    CHECK (`dosage_classification` IN
+           ('Threshold', 'Light', 'Common', 'Strong', 'Heavy'))
+);
+-- Copy rows from "ingestion" table, ranges keep their lower bound and unknown dosages become zero
+INSERT INTO `new_ingestion` (`id`, `substance_name`, `route_of_administration`, `dosage`, `dosage_classification`,
+                             `amount`, `amount_unit`, `solution_id`, `ingested_at`, `updated_at`, `created_at`)
+SELECT `id`,
+       `substance_name`,
+       `route_of_administration`,
+       COALESCE(`dosage`, 0),
+       `dosage_classification`,
+       `amount`,
+       `amount_unit`,
+       `solution_id`,
+       `ingested_at`,
+       `updated_at`,
+       `created_at`
+FROM `ingestion`;
+-- Drop "ingestion" table after copying rows
+DROP TABLE `ingestion`;
+-- Rename temporary table "new_ingestion" to "ingestion"
+ALTER TABLE `new_ingestion` RENAME TO `ingestion`;
+-- Enable back the enforcement of foreign-keys constraints
+PRAGMA
+foreign_keys = on;
@@ -0,0 +1,18 @@
+-- Create empty "ingestion_phase_backup" table, it's filled by reverting "20250210165025_ingestion_phase_add_bounds"
+CREATE TABLE IF NOT EXISTS `ingestion_phase_backup`
+(
+    `id`                   text    NOT NULL,
+    `ingestion_id`         integer NOT NULL,
+    `classification`       text    NOT NULL,
+    `start_date_min`       text    NOT NULL,
+    `start_date_max`       text    NOT NULL,
+    `end_date_min`         text    NOT NULL,
+    `end_date_max`         text    NOT NULL,
+    `common_dosage_weight` integer NOT NULL,
+    `duration_min`         integer NOT NULL,
+    `duration_max`         integer NOT NULL,
+    `notes`                text NULL,
+    `created_at`           text    NOT NULL,
+    `updated_at`           text    NOT NULL,
+    PRIMARY KEY (`id`)
+);
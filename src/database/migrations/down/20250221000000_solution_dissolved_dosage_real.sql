@@ -0,0 +1,35 @@
+-- Disable the enforcement of foreign-keys constraints
+PRAGMA
+foreign_keys = off;
+-- Create "new_solution" table with "dissolved_dosage" declared as float again
+CREATE TABLE `new_solution`
+(
+    `id`               integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `name`             text          NOT NULL,
+    `substance_name`   text          NOT NULL,
+    `dissolved_dosage` float         NOT NULL,
+    `solvent_volume`   real          NOT NULL,
+    `created_at`       datetime_text NOT NULL,
+    `updated_at`       datetime_text NOT NULL,
+    CHECK (`dissolved_dosage` > 0 AND `solvent_volume` > 0)
+);
+-- Copy rows from "solution" table
+INSERT INTO `new_solution` (`id`, `name`, `substance_name`, `dissolved_dosage`, `solvent_volume`, `created_at`,
+                            `updated_at`)
+SELECT `id`,
+       `name`,
+       `substance_name`,
+       `dissolved_dosage`,
+       `solvent_volume`,
+       `created_at`,
+       `updated_at`
+FROM `solution`;
+-- Drop "solution" table after copying rows
+DROP TABLE `solution`;
+-- Rename temporary table "new_solution" to "solution"
+ALTER TABLE `new_solution` RENAME TO `solution`;
+-- Create index "solution_name_key" to table: "solution"
+CREATE UNIQUE INDEX `solution_name_key` ON `solution` (`name`);
+-- Enable back the enforcement of foreign-keys constraints
+PRAGMA
+foreign_keys = on;
@@ -8,45 +8,144 @@ use rust_embed::Embed;
 pub struct Migrations;
 
 
-macro_rules! sql_migration {
-    ($name:ident, $migration_name:expr, $filename:expr) => {
-        pub struct $name;
+/// Migration written in SQL, its down migration is stored in `down`
+/// directory under the same file name. Migrations without one can't be
+/// reverted.
+pub struct SqlMigration
+{
+    name: &'static str,
+    filename: &'static str,
+}
 
-        impl MigrationName for $name
-        {
-            fn name(&self) -> &str { $migration_name }
-        }
+impl SqlMigration
+{
+    const fn new(name: &'static str, filename: &'static str) -> Self { Self { name, filename } }
+
+    pub fn is_reversible(&self) -> bool { Migrations::get(&self.down_filename()).is_some() }
+
+    fn down_filename(&self) -> String { format!("down/{}.sql", self.filename) }
+
+    async fn execute(manager: &SchemaManager<'_>, migration_filename: &str) -> Result<(), DbErr>
+    {
+        let migration_file = Migrations::get(migration_filename).ok_or_else(|| {
+            DbErr::Custom(format!("Migration file not found: {}", migration_filename))
+        })?;
+        let sql_statement = std::str::from_utf8(migration_file.data.as_ref())
+            .map_err(|e| DbErr::Custom(format!("Error decoding SQL: {}", e)))?;
+        manager
+            .get_connection()
+            .execute_unprepared(sql_statement)
+            .await?;
+        Ok(())
+    }
+}
+
+impl MigrationName for SqlMigration
+{
+    fn name(&self) -> &str { self.name }
+}
 
-        #[async_trait::async_trait]
-        impl MigrationTrait for $name
+#[async_trait::async_trait]
+impl MigrationTrait for SqlMigration
+{
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr>
+    {
+        Self::execute(manager, &format!("{}.sql", self.filename)).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr>
+    {
+        if !self.is_reversible()
         {
-            async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr>
-            {
-                let migration_filename = format!("{}.sql", $filename);
-                let migration_file = Migrations::get(&migration_filename).ok_or_else(|| {
-                    DbErr::Custom(format!("Migration file not found: {}", migration_filename))
-                })?;
-                let sql_statement = std::str::from_utf8(migration_file.data.as_ref())
-                    .map_err(|e| DbErr::Custom(format!("Error decoding SQL: {}", e)))?;
-                manager
-                    .get_connection()
-                    .execute_unprepared(sql_statement)
-                    .await?;
-                Ok(())
-            }
-
-            async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr>
-            {
-                panic!("Down migrations aren't supported before official release")
-            }
+            return Err(DbErr::Custom(format!(
+                "Migration {} can't be reverted",
+                self.name
+            )));
         }
-    };
+
+        Self::execute(manager, &self.down_filename()).await
+    }
 }
-macro_rules! import_migration {
-    ($name:ident, $migration_name:expr, $filename:expr) => {{
-        sql_migration!($name, $migration_name, $filename);
-        Box::new($name)
-    }};
+
+/// Migrations in the order in which they're applied.
+pub const SQL_MIGRATIONS: [SqlMigration; 17] = [
+    SqlMigration::new(
+        "m20220101_000001_create_table",
+        "20250101000001_add_ingestion_table",
+    ),
+    SqlMigration::new(
+        "m20020101_000002_create_database_schema",
+        "20250101000002_import_substance",
+    ),
+    SqlMigration::new(
+        "20250101235153_drop_unrelated_data",
+        "20250101235153_drop_unrelated_data",
+    ),
+    SqlMigration::new(
+        "20250104060831_update_dosage_bounds",
+        "20250104060831_update_dosage_bounds",
+    ),
+    SqlMigration::new(
+        "20250108183655_update_route_of_administration_classification_values",
+        "20250108183655_update_route_of_administration_classification_values",
+    ),
+    SqlMigration::new(
+        "20250127045746_add_ingestion_phase",
+        "20250127045746_add_ingestion_phase",
+    ),
+    SqlMigration::new(
+        "20250208131330_update_ingestion_model",
+        "20250208131330_update_ingestion_model",
+    ),
+    SqlMigration::new(
+        "20250210165025_ingestion_phase_add_bounds",
+        "20250210165025_ingestion_phase_add_bounds",
+    ),
+    SqlMigration::new(
+        "20250210175314_ingestion_phase_use_datetime",
+        "20250210175314_ingestion_phase_use_datetime",
+    ),
+    SqlMigration::new(
+        "20250211000000_fix_dosage_classification",
+        "20250211000000_fix_dosage_classification",
+    ),
+    SqlMigration::new(
+        "20250215000000_add_dosage_units",
+        "20250215000000_add_dosage_units",
+    ),
+    SqlMigration::new(
+        "20250216000000_add_solution",
+        "20250216000000_add_solution",
+    ),
+    SqlMigration::new(
+        "20250217000000_add_body_weight",
+        "20250217000000_add_body_weight",
+    ),
+    SqlMigration::new(
+        "20250218000000_add_bioavailability",
+        "20250218000000_add_bioavailability",
+    ),
+    SqlMigration::new(
+        "20250219000000_ingestion_dosage_estimate",
+        "20250219000000_ingestion_dosage_estimate",
+    ),
+    SqlMigration::new(
+        "20250220000000_restore_ingestion_phase_backup",
+        "20250220000000_restore_ingestion_phase_backup",
+    ),
+    SqlMigration::new(
+        "20250221000000_solution_dissolved_dosage_real",
+        "20250221000000_solution_dissolved_dosage_real",
+    ),
+];
+
+/// Schema version known to this build, it's the number of its migrations.
+pub const SCHEMA_VERSION: u32 = SQL_MIGRATIONS.len() as u32;
+
+/// Migration known to this build with given name.
+pub fn find_migration(name: &str) -> Option<&'static SqlMigration>
+{
+    SQL_MIGRATIONS.iter().find(|migration| migration.name == name)
 }
 
 pub struct Migrator;
@@ -56,57 +155,12 @@ impl MigratorTrait for Migrator
 {
     fn migrations() -> Vec<Box<dyn MigrationTrait>>
     {
-        vec![
-            import_migration!(
-                M20020101000002CreateDatabaseSchema,
-                "m20220101_000001_create_table",
-                "20250101000001_add_ingestion_table"
-            ),
-            import_migration!(
-                M20020101000002CreateDatabaseSchema,
-                "m20020101_000002_create_database_schema",
-                "20250101000002_import_substance"
-            ),
-            import_migration!(
-                M20250101235153DropUnrelatedData,
-                "20250101235153_drop_unrelated_data",
-                "20250101235153_drop_unrelated_data"
-            ),
-            import_migration!(
-                M20250104060831UpdateDosageBounds,
-                "20250104060831_update_dosage_bounds",
-                "20250104060831_update_dosage_bounds"
-            ),
-            import_migration!(
-                M20250108183655UpdateRouteOfAdministrationClassificationValues,
-                "20250108183655_update_route_of_administration_classification_values",
-                "20250108183655_update_route_of_administration_classification_values"
-            ),
-            import_migration!(
-                M20250127045746AddIngestionPhase,
-                "20250127045746_add_ingestion_phase",
-                "20250127045746_add_ingestion_phase"
-            ),
-            import_migration!(
-                M20250208131330UpdateIngestionModel,
-                "20250208131330_update_ingestion_model",
-                "20250208131330_update_ingestion_model"
-            ),
-            import_migration!(
-                M20250210165025IngestionPhaseAddBounds,
-                "20250210165025_ingestion_phase_add_bounds",
-                "20250210165025_ingestion_phase_add_bounds"
-            ),
-            import_migration!(
-                M20250210175314IngestionPhaseUseDatetime,
-                "20250210175314_ingestion_phase_use_datetime",
-                "20250210175314_ingestion_phase_use_datetime"
-            ),
-            import_migration!(
-                M20250211000000FixDosageClassification,
-                "20250211000000_fix_dosage_classification",
-                "20250211000000_fix_dosage_classification"
-            ),
-        ]
+        SQL_MIGRATIONS
+            .iter()
+            .map(|migration| {
+                Box::new(SqlMigration::new(migration.name, migration.filename))
+                    as Box<dyn MigrationTrait>
+            })
+            .collect()
     }
 }
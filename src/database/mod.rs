@@ -1,5 +1,9 @@
 pub mod entities;
+pub mod export;
+pub mod import;
+pub mod maintenance;
 pub mod migrator;
+pub mod psychonautwiki;
 
 pub use entities::prelude::*;
 pub use migrator::Migrator;
@@ -0,0 +1,387 @@
+//! Importer of exports from the PsychonautWiki Journal mobile app.
+//!
+//! The app exports a JSON file with experiences, every experience has its own
+//! ingestions with a substance, dose in given units, route and time. Doses in
+//! units of volume or count (e.g. "mL" or "drops") are converted to mass with
+//! unit conversions of their substance, ingestions which can't be represented
+//! in neuronek are reported as skipped.
+
+use crate::database::import::ImportedIngestion;
+use crate::database::import::parse_amount;
+use crate::substance::conversion;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::Estimate;
+use chrono::DateTime;
+use chrono::Local;
+use miette::IntoDiagnostic;
+use miette::WrapErr;
+use miette::miette;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalAppExport
+{
+    experiences: Vec<Experience>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Experience
+{
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    ingestions: Vec<AppIngestion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppIngestion
+{
+    #[serde(default)]
+    substance_name: Option<String>,
+    time: AppTime,
+    #[serde(default)]
+    administration_route: Option<String>,
+    #[serde(default)]
+    dose: Option<f64>,
+    #[serde(default)]
+    is_dose_an_estimate: bool,
+    #[serde(default)]
+    units: Option<String>,
+    #[serde(default)]
+    notes: Option<String>,
+}
+
+/// Times are exported as milliseconds since the Unix epoch, older versions
+/// of the app used ISO 8601 dates.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AppTime
+{
+    Milliseconds(i64),
+    Text(String),
+}
+
+impl AppTime
+{
+    fn to_local(&self) -> Option<DateTime<Local>>
+    {
+        match self
+        {
+            | AppTime::Milliseconds(milliseconds) => DateTime::from_timestamp_millis(*milliseconds)
+                .map(|date| date.with_timezone(&Local)),
+            | AppTime::Text(text) => DateTime::parse_from_rfc3339(text)
+                .ok()
+                .map(|date| date.with_timezone(&Local)),
+        }
+    }
+}
+
+/// Entry of the export which couldn't be imported.
+#[derive(Debug, Clone)]
+pub struct SkippedEntry
+{
+    pub entry: String,
+    pub reason: String,
+}
+
+/// Ingestion dosed in units other than mass, its dosage is unknown until
+/// it's converted by `JournalAppImport::convert_amounts`.
+#[derive(Debug)]
+struct UnconvertedIngestion
+{
+    index: usize,
+    entry: String,
+    has_notes: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct JournalAppImport
+{
+    pub ingestions: Vec<ImportedIngestion>,
+    pub skipped: Vec<SkippedEntry>,
+    /// Number of imported ingestions which had notes, neuronek doesn't store
+    /// notes of ingestions.
+    pub dropped_notes: usize,
+    unconverted: Vec<UnconvertedIngestion>,
+}
+
+impl JournalAppImport
+{
+    /// Resolve mass of ingestions dosed in units of volume or count with unit
+    /// conversions of their substance, ingestions of units without a
+    /// conversion are skipped.
+    pub async fn convert_amounts(
+        mut self,
+        database_connection: &DatabaseConnection,
+    ) -> miette::Result<Self>
+    {
+        let mut skipped = HashSet::new();
+
+        for unconverted in std::mem::take(&mut self.unconverted)
+        {
+            let ingestion = &mut self.ingestions[unconverted.index];
+            let amount = match &ingestion.amount
+            {
+                | Some(amount) => amount,
+                | None => continue,
+            };
+
+            match conversion::to_dosage_estimate(
+                database_connection,
+                &[&ingestion.substance_name],
+                amount,
+            )
+            .await
+            {
+                | Ok(dosage) => ingestion.dosage = dosage,
+                | Err(error) =>
+                {
+                    skipped.insert(unconverted.index);
+                    self.skipped.push(SkippedEntry {
+                        entry: unconverted.entry,
+                        reason: error.to_string(),
+                    });
+
+                    if unconverted.has_notes
+                    {
+                        self.dropped_notes -= 1;
+                    }
+                }
+            }
+        }
+
+        self.ingestions = std::mem::take(&mut self.ingestions)
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !skipped.contains(index))
+            .map(|(_, ingestion)| ingestion)
+            .collect();
+
+        Ok(self)
+    }
+}
+
+/// Whether given JSON looks like an export of the PsychonautWiki Journal app.
+pub fn is_journal_app_export(content: &str) -> bool
+{
+    serde_json::from_str::<serde_json::Value>(content)
+        .ok()
+        .is_some_and(|value| value.get("experiences").is_some_and(serde_json::Value::is_array))
+}
+
+/// Route names used by the app are upper case variants of ours, except for
+/// subcutaneous which has no equivalent.
+fn map_route(route: &str) -> Option<RouteOfAdministrationClassification>
+{
+    route.trim().to_lowercase().parse().ok()
+}
+
+/// Units of the app are mostly the same as ours, custom units such as
+/// "tabs" are counts.
+fn map_amount(dose: f64, units: &str) -> Option<Amount> { parse_amount(dose, units.trim()).ok() }
+
+fn map_ingestion(ingestion: &AppIngestion) -> Result<ImportedIngestion, String>
+{
+    let substance_name = ingestion
+        .substance_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .ok_or("substance is missing")?;
+
+    let ingested_at = ingestion.time.to_local().ok_or("time is invalid")?;
+
+    let route = match ingestion.administration_route.as_deref()
+    {
+        | Some(route) => map_route(route)
+            .ok_or_else(|| format!("route {} has no equivalent", route.to_lowercase()))?,
+        | None => return Err("route is missing".to_string()),
+    };
+
+    let units = ingestion.units.as_deref().unwrap_or_default();
+    let amount = match ingestion.dose
+    {
+        | Some(dose) if dose > 0.0 =>
+        {
+            let amount = map_amount(dose, units)
+                .ok_or_else(|| format!("unit \"{}\" is not supported", units))?;
+
+            match ingestion.is_dose_an_estimate
+            {
+                | true => Estimate::Approximate(amount),
+                | false => Estimate::Exact(amount),
+            }
+        }
+        | _ => Estimate::Unknown,
+    };
+
+    // Masses are known right away, other amounts once they're converted.
+    let (dosage, amount) = match amount.lower().map(Amount::as_dosage)
+    {
+        | Some(Some(dosage)) => (amount.map(|_| dosage), None),
+        | Some(None) => (Estimate::Unknown, Some(amount)),
+        | None => (Estimate::Unknown, None),
+    };
+
+    Ok(ImportedIngestion {
+        substance_name: substance_name.to_string(),
+        route,
+        dosage,
+        amount,
+        solution: None,
+        ingested_at,
+    })
+}
+
+/// Read ingestions of all experiences, entries which can't be represented
+/// are collected together with the reason.
+pub fn from_journal_app(content: &str) -> miette::Result<JournalAppImport>
+{
+    let export: JournalAppExport = serde_json::from_str(content)
+        .into_diagnostic()
+        .wrap_err("File is not a valid PsychonautWiki Journal export")?;
+
+    if export.experiences.is_empty()
+    {
+        return Err(miette!("Export doesn't contain any experiences"));
+    }
+
+    let mut import = JournalAppImport::default();
+
+    for experience in &export.experiences
+    {
+        for ingestion in &experience.ingestions
+        {
+            let entry = format!(
+                "{} in \"{}\"{}",
+                ingestion.substance_name.as_deref().unwrap_or("Unknown substance"),
+                experience.title.as_deref().unwrap_or("untitled experience"),
+                ingestion
+                    .time
+                    .to_local()
+                    .map(|time| format!(" at {}", time.format("%Y-%m-%d %H:%M")))
+                    .unwrap_or_default()
+            );
+
+            match map_ingestion(ingestion)
+            {
+                | Ok(imported) =>
+                {
+                    let has_notes =
+                        ingestion.notes.as_deref().is_some_and(|notes| !notes.trim().is_empty());
+
+                    if has_notes
+                    {
+                        import.dropped_notes += 1;
+                    }
+
+                    if imported.amount.is_some()
+                    {
+                        import.unconverted.push(UnconvertedIngestion {
+                            index: import.ingestions.len(),
+                            entry,
+                            has_notes,
+                        });
+                    }
+
+                    import.ingestions.push(imported);
+                }
+                | Err(reason) => import.skipped.push(SkippedEntry { entry, reason }),
+            }
+        }
+    }
+
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "experiences": [
+            {
+                "title": "Concert",
+                "text": "",
+                "creationDate": 1700000000000,
+                "ingestions": [
+                    {
+                        "substanceName": "MDMA",
+                        "time": 1700000000000,
+                        "administrationRoute": "ORAL",
+                        "dose": 100.0,
+                        "isDoseAnEstimate": false,
+                        "units": "mg",
+                        "notes": "Tested with reagents"
+                    },
+                    {
+                        "substanceName": "LSD",
+                        "time": 1700000600000,
+                        "administrationRoute": "SUBLINGUAL",
+                        "dose": 100.0,
+                        "isDoseAnEstimate": true,
+                        "units": "µg"
+                    },
+                    {
+                        "substanceName": "Ketamine",
+                        "time": 1700001200000,
+                        "administrationRoute": "SUBCUTANEOUS",
+                        "dose": 30.0,
+                        "units": "mg"
+                    },
+                    {
+                        "substanceName": "Cannabis",
+                        "time": 1700001800000,
+                        "administrationRoute": "SMOKED",
+                        "dose": null,
+                        "units": "mg"
+                    },
+                    {
+                        "substanceName": "Alcohol",
+                        "time": 1700002400000,
+                        "administrationRoute": "ORAL",
+                        "dose": 50.0,
+                        "units": "mL EtOH"
+                    }
+                ]
+            }
+        ],
+        "substanceCompanions": []
+    }"#;
+
+    #[test]
+    fn test_journal_app_import()
+    {
+        assert!(is_journal_app_export(EXPORT));
+        assert!(!is_journal_app_export(r#"{"format": "neuronek-journal"}"#));
+
+        let import = from_journal_app(EXPORT).unwrap();
+
+        assert_eq!(import.ingestions.len(), 4);
+        assert_eq!(import.ingestions[0].route, RouteOfAdministrationClassification::Oral);
+        assert_eq!(import.ingestions[1].route, RouteOfAdministrationClassification::Sublingual);
+        assert!(matches!(import.ingestions[1].dosage, Estimate::Approximate(_)));
+        assert!(
+            (import.ingestions[1].dosage.value().unwrap().as_base_units() - 1e-7).abs() < 1e-12
+        );
+        assert_eq!(import.ingestions[2].dosage, Estimate::Unknown);
+        assert_eq!(import.ingestions[2].amount, None);
+        // Dosage of other units is known once it's converted.
+        assert_eq!(import.ingestions[3].dosage, Estimate::Unknown);
+        assert_eq!(
+            import.ingestions[3].amount.as_ref().map(ToString::to_string),
+            Some("50 ml etoh".to_string())
+        );
+        assert_eq!(import.dropped_notes, 1);
+
+        let reasons: Vec<&str> = import.skipped.iter().map(|s| s.reason.as_str()).collect();
+        assert_eq!(reasons, ["route subcutaneous has no equivalent"]);
+    }
+}
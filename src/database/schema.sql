@@ -82,16 +82,69 @@ CREATE UNIQUE INDEX `route_of_administration_dosage_intensivity_routeOfAdministr
 -- Create "ingestion" table
 CREATE TABLE `ingestion`
 (
-    `id`                      integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
-    `substance_name`          varchar       NOT NULL,
-    `route_of_administration` varchar       NOT NULL,
-    `dosage`                  float         NOT NULL,
-    `dosage_classification`   text NULL,
-    `ingested_at`             datetime_text NOT NULL,
-    `updated_at`              datetime_text NOT NULL,
-    `created_at`              datetime_text NOT NULL,
+    `id`                        integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `substance_name`            varchar       NOT NULL,
+    `route_of_administration`   varchar       NOT NULL,
+    `dosage`                    float NULL,
+    `dosage_max`                float NULL,
+    `dosage_approximate`        boolean       NOT NULL DEFAULT false,
+    `dosage_classification`     text NULL,
+    `dosage_classification_max` text NULL,
+    `amount`                    real NULL,
+    `amount_max`                real NULL,
+    `amount_unit`               text NULL,
+    `solution_id`               integer NULL REFERENCES `solution` (`id`) ON DELETE SET NULL,
+    `ingested_at`               datetime_text NOT NULL,
+    `updated_at`                datetime_text NOT NULL,
+    `created_at`                datetime_text NOT NULL,
     CHECK (`dosage_classification` IN
-           ('Thereshold', 'Light', 'Common', 'Strong', 'Heavy'))
+           ('Threshold', 'Light', 'Common', 'Strong', 'Heavy')),
+    CHECK (`dosage_classification_max` IN
+           ('Threshold', 'Light', 'Common', 'Strong', 'Heavy')),
+    CHECK (`dosage_max` IS NULL OR `dosage` <= `dosage_max`)
+);
+-- Create "body_weight" table
+CREATE TABLE `body_weight`
+(
+    `id`          integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `weight`      real          NOT NULL,
+    `measured_at` datetime_text NOT NULL,
+    `created_at`  datetime_text NOT NULL,
+    CHECK (`weight` > 0)
+);
+-- Create index "body_weight_measured_at_idx" to table: "body_weight"
+CREATE INDEX `body_weight_measured_at_idx` ON `body_weight` (`measured_at`);
+-- Create "solution" table
+CREATE TABLE `solution`
+(
+    `id`               integer       NOT NULL PRIMARY KEY AUTOINCREMENT,
+    `name`             text          NOT NULL,
+    `substance_name`   text          NOT NULL,
+    `dissolved_dosage` float         NOT NULL,
+    `solvent_volume`   real          NOT NULL,
+    `created_at`       datetime_text NOT NULL,
+    `updated_at`       datetime_text NOT NULL,
+    CHECK (`dissolved_dosage` > 0 AND `solvent_volume` > 0)
+);
+-- Create index "solution_name_key" to table: "solution"
+CREATE UNIQUE INDEX `solution_name_key` ON `solution` (`name`);
+-- Create "substance_bioavailability" table
+CREATE TABLE `substance_bioavailability`
+(
+    `substance_name`          text  NOT NULL,
+    `route_of_administration` text  NOT NULL,
+    `bioavailability`         float NOT NULL,
+    PRIMARY KEY (`substance_name`, `route_of_administration`),
+    CHECK (`bioavailability` > 0 AND `bioavailability` <= 1)
+);
+-- Create "substance_unit_conversion" table
+CREATE TABLE `substance_unit_conversion`
+(
+    `substance_name` text  NOT NULL,
+    `unit`           text  NOT NULL,
+    `dosage`         float NOT NULL,
+    PRIMARY KEY (`substance_name`, `unit`),
+    CHECK (`dosage` > 0)
 );
 -- Create "ingestion_phase" table
 CREATE TABLE ingestion_phase
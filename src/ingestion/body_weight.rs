@@ -0,0 +1,117 @@
+//! History of body weight, used to normalise dosages per kilogram of body
+//! weight. Weight changes over time, so every ingestion is normalised with
+//! the weight measured last before it.
+
+use crate::database::entities::body_weight;
+use crate::substance::route_of_administration::dosage::Dosage;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::TimeZone;
+use miette::IntoDiagnostic;
+use sea_orm::ColumnTrait;
+use sea_orm::ConnectionTrait;
+use sea_orm::DatabaseConnection;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+
+/// Body weight measured at given date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BodyWeight
+{
+    pub id: i32,
+    /// Weight in kilograms.
+    pub weight: f64,
+    pub measured_at: DateTime<Local>,
+}
+
+impl From<body_weight::Model> for BodyWeight
+{
+    fn from(model: body_weight::Model) -> Self
+    {
+        Self {
+            id: model.id,
+            weight: model.weight,
+            measured_at: Local.from_utc_datetime(&model.measured_at),
+        }
+    }
+}
+
+/// Display body weight in kilograms, e.g. "72.5 kg".
+pub fn display_weight(weight: f64) -> String
+{
+    format!("{} kg", (weight * 10.0).round() / 10.0)
+}
+
+/// Display dosage per kilogram of body weight, e.g. "1.43 mg/kg".
+pub fn display_per_kg(dosage: impl std::fmt::Display) -> String { format!("{}/kg", dosage) }
+
+/// Body weight at given date, which is the last one measured before it. When
+/// weight was first measured only after the date, that measurement is used.
+pub async fn body_weight_at(
+    database_connection: &impl ConnectionTrait,
+    date: DateTime<Local>,
+) -> miette::Result<Option<BodyWeight>>
+{
+    let before = body_weight::Entity::find()
+        .filter(body_weight::Column::MeasuredAt.lte(date.to_utc().naive_utc()))
+        .order_by_desc(body_weight::Column::MeasuredAt)
+        .one(database_connection)
+        .await
+        .into_diagnostic()?;
+
+    let weight = match before
+    {
+        | Some(weight) => Some(weight),
+        | None => body_weight::Entity::find()
+            .order_by_asc(body_weight::Column::MeasuredAt)
+            .one(database_connection)
+            .await
+            .into_diagnostic()?,
+    };
+
+    Ok(weight.map(BodyWeight::from))
+}
+
+/// Dates of ingestions normalised with the weight measured at given date,
+/// from the date until the next measurement. When there is no earlier
+/// measurement, ingestions before the date are normalised with it as well.
+pub async fn normalised_period(
+    database_connection: &impl ConnectionTrait,
+    measured_at: DateTime<Local>,
+) -> miette::Result<(Option<DateTime<Local>>, Option<DateTime<Local>>)>
+{
+    let measured_at_utc = measured_at.to_utc().naive_utc();
+
+    let previous = body_weight::Entity::find()
+        .filter(body_weight::Column::MeasuredAt.lt(measured_at_utc))
+        .one(database_connection)
+        .await
+        .into_diagnostic()?;
+
+    let next = body_weight::Entity::find()
+        .filter(body_weight::Column::MeasuredAt.gt(measured_at_utc))
+        .order_by_asc(body_weight::Column::MeasuredAt)
+        .one(database_connection)
+        .await
+        .into_diagnostic()?;
+
+    Ok((
+        previous.map(|_| measured_at),
+        next.map(|next| Local.from_utc_datetime(&next.measured_at)),
+    ))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_display_body_weight()
+    {
+        assert_eq!(display_weight(72.46), "72.5 kg");
+        assert_eq!(display_weight(80.0), "80 kg");
+        assert_eq!(display_per_kg(Dosage::from_miligrams(2.5)), "2.50 mg/kg");
+    }
+}
@@ -1,5 +1,7 @@
-use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::Estimate;
 use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::core::config::Config;
 use chrono::DateTime;
 use chrono::Local;
 use chrono_english::Dialect;
@@ -38,16 +40,35 @@ providing insights into the long-term effects of different substances on physica
 pub struct LogIngestion
 {
     /// Name of substance.rs that is being ingested, e.g. "Paracetamol"
-    #[arg(short = 's', long = "substance", required = true)]
+    #[arg(
+        short = 's',
+        long = "substance",
+        required_unless_present = "solution",
+        conflicts_with = "solution",
+        default_value = ""
+    )]
     pub substance_name: String,
-    /// Dosage of given substance.rs provided as string with unit (e.g., 10 mg)
+    /// Dosage of given substance.rs provided as string with unit (e.g., 10 mg).
+    ///
+    /// Volumes (5 ml), international units (1000 IU) and countable units (2
+    /// cups, 3 drops) are converted to mass with conversions of the substance,
+    /// see `neuronek substance unit`. Ingestions of a solution take its volume
+    /// (e.g. --volume 0.5ml).
+    ///
+    /// Dosages which aren't known exactly can be approximate (~200mg), a
+    /// range (100-150mg) or "unknown".
     #[arg(
         short = 'd',
         long = "dosage",
+        visible_alias = "volume",
         required = true,
-        value_parser = Dosage::from_str
+        value_parser = Estimate::<Amount>::from_str
     )]
-    pub dosage: Dosage,
+    pub dosage: Estimate<Amount>,
+    /// Name of solution which was ingested instead of a substance, its dosage
+    /// is computed from the volume and concentration of the solution
+    #[arg(long = "solution", value_name = "SOLUTION")]
+    pub solution: Option<String>,
     /// Date of ingestion, by default current date is used if not provided.
     ///
     /// Date can be provided as timestamp and in human-readable format such as
@@ -60,8 +81,9 @@ pub struct LogIngestion
         value_parser=parse_date_string
     )]
     pub ingestion_date: DateTime<Local>,
-    /// Route of administration related to given ingestion (defaults to "oral")
-    #[arg(short = 'r', long = "roa", default_value = "oral", value_enum)]
+    /// Route of administration related to given ingestion (defaults to the
+    /// configured default route, "oral" unless changed)
+    #[arg(short = 'r', long = "roa", default_value_t = Config::current().default_route, value_enum)]
     pub route_of_administration: RouteOfAdministrationClassification,
 }
 
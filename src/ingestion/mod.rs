@@ -1,3 +1,4 @@
+pub(super) mod body_weight;
 pub(super) mod command;
 pub(super) mod phase;
 pub(super) mod model;
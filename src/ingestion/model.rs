@@ -1,6 +1,10 @@
 use crate::database::entities::ingestion::Model;
-use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::DisplayMass;
 use crate::substance::route_of_administration::dosage::DosageClassification;
+use crate::substance::route_of_administration::dosage::DosageEstimate;
+use crate::substance::route_of_administration::dosage::Estimate;
+use crate::substance::route_of_administration::dosage::MassUnit;
 use crate::substance::route_of_administration::phase::PhaseClassification;
 use crate::substance::route_of_administration::RouteOfAdministrationClassification;
 use chrono::DateTime;
@@ -21,19 +25,84 @@ pub struct Ingestion
 {
     pub id: Option<i32>,
     pub substance_name: String,
-    pub dosage: Dosage,
+    /// Dosage as precisely as it's known, it can be approximate, a range or
+    /// unknown.
+    pub dosage: DosageEstimate,
+    /// Amount in the unit it was logged in when it wasn't a mass, `dosage`
+    /// is its mass.
+    pub amount: Option<Estimate<Amount>>,
+    /// Body weight in kilograms at the time of ingestion, when it's known.
+    pub body_weight: Option<f64>,
+    /// Bioavailability of the substance through the route of ingestion, when
+    /// it's known.
+    pub bioavailability: Option<f64>,
     pub route: RouteOfAdministrationClassification,
     pub ingestion_date: IngestionDate,
     /// The classification of the dosage for this ingestion.
     /// This field is an `Option` to allow for cases where the dosage
     /// classification cannot be determined.
     pub dosage_classification: Option<DosageClassification>,
+    /// Classification of the upper bound of a dosage range, when it differs
+    /// from the classification of the lower bound.
+    pub dosage_classification_max: Option<DosageClassification>,
     pub substance: Option<Box<crate::substance::Substance>>,
     /// A vector of `IngestionPhase` structs representing the different phases
     /// of the ingestion event.
     pub phases: Vec<IngestionPhase>,
 }
 
+impl Ingestion
+{
+    /// Dosage together with the amount it was logged in, e.g. "2 cups (190
+    /// mg)", masses are displayed in given unit.
+    pub fn display_dosage(&self, unit: MassUnit) -> String
+    {
+        match &self.amount
+        {
+            | Some(amount) => amount.display_with_mass(&self.dosage, unit),
+            | None => self.dosage.display_in(unit),
+        }
+    }
+
+    /// Classification of the dosage, or range of classifications spanned by a
+    /// dosage range, e.g. "Light - Common".
+    pub fn display_classification(&self) -> Option<String>
+    {
+        display_classification(self.dosage_classification, self.dosage_classification_max)
+    }
+
+    /// Dosage which reaches systemic circulation, comparable between routes
+    /// of administration.
+    pub fn effective_dosage(&self) -> Option<DosageEstimate>
+    {
+        self.bioavailability
+            .filter(|_| self.dosage.is_known())
+            .map(|bioavailability| self.dosage.map(|dosage| *dosage * bioavailability))
+    }
+
+    /// Dosage per kilogram of body weight, when body weight is known.
+    pub fn dosage_per_kg(&self) -> Option<DosageEstimate>
+    {
+        self.body_weight
+            .filter(|weight| *weight > 0.0 && self.dosage.is_known())
+            .map(|weight| self.dosage.map(|dosage| *dosage / weight))
+    }
+}
+
+/// Classification, or range of classifications when the upper one is given,
+/// e.g. "Light - Common".
+pub fn display_classification(
+    classification: Option<DosageClassification>,
+    classification_max: Option<DosageClassification>,
+) -> Option<String>
+{
+    match (classification, classification_max)
+    {
+        | (Some(lower), Some(upper)) if lower != upper => Some(format!("{} - {}", lower, upper)),
+        | (classification, _) => classification.map(|classification| classification.to_string()),
+    }
+}
+
 impl From<Model> for Ingestion
 {
     fn from(value: Model) -> Self
@@ -41,13 +110,30 @@ impl From<Model> for Ingestion
         Ingestion {
             id: Some(value.id),
             substance_name: value.substance_name,
-            dosage: Dosage::from_base_units(value.dosage as f64),
+            dosage: DosageEstimate::from_stored(
+                value.dosage,
+                value.dosage_max,
+                value.dosage_approximate,
+            ),
+            amount: Estimate::<Amount>::from_stored(
+                value.amount,
+                value.amount_max,
+                value.amount_unit.as_deref(),
+                value.dosage_approximate,
+            ),
+            body_weight: None,
+            bioavailability: None,
             ingestion_date: Local.from_utc_datetime(&value.ingested_at),
             route: value
                 .route_of_administration
                 .parse()
                 .unwrap_or(RouteOfAdministrationClassification::Oral),
-            dosage_classification: None,
+            dosage_classification: value
+                .dosage_classification
+                .and_then(|c| DosageClassification::from_str(&c.to_lowercase()).ok()),
+            dosage_classification_max: value
+                .dosage_classification_max
+                .and_then(|c| DosageClassification::from_str(&c.to_lowercase()).ok()),
             substance: None,
             phases: vec![],
         }
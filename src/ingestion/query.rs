@@ -5,11 +5,15 @@ use crate::database::entities::ingestion::Entity as IngestionEntity;
 use crate::database::entities::ingestion_phase;
 use crate::ingestion::IngestionPhase;
 use crate::ingestion::model::Ingestion;
+use crate::substance::bioavailability::Bioavailabilities;
+use crate::substance::bioavailability::list_bioavailability;
 use crate::substance::repository::get_substance;
+use crate::substance::route_of_administration::Dosages;
 use crate::substance::route_of_administration::RouteOfAdministration;
 use crate::substance::route_of_administration::RouteOfAdministrationClassification;
 use crate::substance::route_of_administration::dosage::Dosage;
 use crate::substance::route_of_administration::dosage::DosageClassification;
+use crate::substance::route_of_administration::dosage::DosageEstimate;
 use crate::substance::route_of_administration::phase::PhaseClassification;
 use crate::utils::AppContext;
 use async_trait::async_trait;
@@ -18,16 +22,20 @@ use chrono::Duration as TimeDelta;
 use chrono::Local;
 use chrono::TimeZone;
 use clap::Parser;
+use clap::ValueEnum;
 use clap::arg;
 use clap::command;
 use derive_more::FromStr;
 use miette::IntoDiagnostic;
 use sea_orm::ActiveModelTrait;
 use sea_orm::ActiveValue;
+use sea_orm::ColumnTrait;
 use sea_orm::DatabaseConnection;
 use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
 use sea_orm::QueryOrder;
 use sea_orm::QuerySelect;
+use sea_orm::Select;
 use sea_orm_migration::IntoSchemaManagerConnection;
 use std::range::Range;
 use std::str::FromStr;
@@ -71,15 +79,16 @@ pub struct AnalyzeIngestion
     #[arg(short, long, value_name = "SUBSTANCE")]
     pub substance: String,
 
-    /// Dosage of the substance ingested (if not using `ingestion_id`).
+    /// Dosage of the substance ingested (if not using `ingestion_id`), bounds
+    /// of a dosage range (e.g. 100-150mg) are classified separately.
     #[arg(
         short,
         long,
         value_name = "DOSAGE",
         help = "Dosage of the substance",
-        value_parser = Dosage::from_str,
+        value_parser = DosageEstimate::from_str,
     )]
-    pub dosage: Dosage,
+    pub dosage: DosageEstimate,
 
     /// Date of ingestion (defaults to the current date if not provided).
     #[arg(
@@ -93,6 +102,11 @@ pub struct AnalyzeIngestion
     /// Route of administration of the substance (defaults to "oral").
     #[arg(short = 'r', long = "roa", default_value = "oral", value_enum)]
     pub roa: RouteOfAdministrationClassification,
+
+    /// Body weight in kilograms, dosage is classified per kilogram of body
+    /// weight when the substance has dosage ranges per kilogram.
+    #[arg(long = "body-weight", value_name = "KILOGRAMS")]
+    pub body_weight: Option<f64>,
 }
 
 impl From<Ingestion> for AnalyzeIngestion
@@ -105,6 +119,7 @@ impl From<Ingestion> for AnalyzeIngestion
             dosage: ingestion.dosage,
             date: ingestion.ingestion_date,
             roa: ingestion.route,
+            body_weight: ingestion.body_weight,
         }
     }
 }
@@ -134,6 +149,46 @@ impl crate::core::QueryHandler<Vec<Ingestion>> for ListIngestion
     }
 }
 
+/// Select ingestions which happened between given local dates (inclusive),
+/// ordered from the oldest one.
+pub fn ingested_between(from: DateTime<Local>, to: DateTime<Local>) -> Select<IngestionEntity>
+{
+    IngestionEntity::find()
+        .filter(ingestion::Column::IngestedAt.gte(from.to_utc().naive_utc()))
+        .filter(ingestion::Column::IngestedAt.lte(to.to_utc().naive_utc()))
+        .order_by_asc(ingestion::Column::IngestedAt)
+}
+
+/// Classification of dosage by the range it falls in, dosages outside of all
+/// ranges are heavy above them or threshold below them, dosages in a gap
+/// between two ranges belong to the lower one. Neighbouring ranges share
+/// their bounds, a dosage on a bound belongs to the stronger range.
+fn classify_dosage(dosages: &Dosages, dosage: &Dosage) -> Option<DosageClassification>
+{
+    // Ranges are kept in a map, which has no order of its own.
+    let ranges: Vec<_> = DosageClassification::value_variants()
+        .iter()
+        .rev()
+        .filter_map(|classification| Some((*classification, dosages.get(classification)?)))
+        .collect();
+
+    if let Some((classification, _)) = ranges.iter().find(|(_, range)| range.contains(dosage))
+    {
+        return Some(*classification);
+    }
+
+    // Nearest range which ends below the dosage, ranges go from the strongest.
+    match ranges
+        .iter()
+        .position(|(_, range)| range.end.as_ref().is_some_and(|end| dosage > end))
+    {
+        | Some(0) => Some(DosageClassification::Heavy),
+        | Some(index) => Some(ranges[index].0),
+        | None if ranges.is_empty() => None,
+        | None => Some(DosageClassification::Threshold),
+    }
+}
+
 #[async_trait]
 impl QueryHandler<Ingestion> for AnalyzeIngestion
 {
@@ -142,7 +197,7 @@ impl QueryHandler<Ingestion> for AnalyzeIngestion
         let db: &DatabaseConnection = &DATABASE_CONNECTION;
 
         let substance_name = &self.substance.clone();
-        let dosage = self.dosage;
+        let dosage = self.dosage.clone();
         let date = self.date;
         let route = self.roa;
 
@@ -158,6 +213,15 @@ impl QueryHandler<Ingestion> for AnalyzeIngestion
             route,
             ingestion_date: date,
             dosage_classification: None,
+            dosage_classification_max: None,
+            amount: None,
+            body_weight: self.body_weight,
+            // Bioavailability is known also for routes missing in substance data.
+            bioavailability: list_bioavailability(db, Some(substance_name))
+                .await?
+                .into_iter()
+                .collect::<Bioavailabilities>()
+                .get(substance_name, route),
             substance: substance.clone().map(Box::new),
             phases: Vec::new(),
         };
@@ -176,32 +240,25 @@ impl QueryHandler<Ingestion> for AnalyzeIngestion
         }
 
         let route_of_administration = route_of_administration.unwrap();
-        let dosages = &route_of_administration.dosages;
-        let ingestion_dosage = ingestion.dosage;
 
-        ingestion.dosage_classification = dosages
-            .iter()
-            .find(|(_, range)| range.contains(&ingestion_dosage))
-            .map(|(classification, _)| *classification)
-            .or_else(|| {
-                dosages
-                    .iter()
-                    .filter_map(|(_classification, range)| {
-                        match (range.start.as_ref(), range.end.as_ref())
-                        {
-                            | (Some(start), _) if &ingestion_dosage >= start =>
-                            {
-                                Some(DosageClassification::Heavy)
-                            }
-                            | (_, Some(end)) if &ingestion_dosage <= end =>
-                            {
-                                Some(DosageClassification::Threshold)
-                            }
-                            | _ => None,
-                        }
-                    })
-                    .next()
-            });
+        // Ranges per kilogram are preferred when body weight is known.
+        let (dosage, dosages) = match ingestion.dosage_per_kg()
+        {
+            | Some(dosage_per_kg) if !route_of_administration.dosages_per_kg.is_empty() =>
+            {
+                (dosage_per_kg, &route_of_administration.dosages_per_kg)
+            }
+            | _ => (ingestion.dosage.clone(), &route_of_administration.dosages),
+        };
+
+        // Bounds of a dosage range may fall into different classifications,
+        // unknown dosages are not classified at all.
+        let classification = dosage.lower().and_then(|lower| classify_dosage(dosages, lower));
+        ingestion.dosage_classification = classification;
+        ingestion.dosage_classification_max = dosage
+            .upper()
+            .and_then(|upper| classify_dosage(dosages, upper))
+            .filter(|upper| Some(*upper) != classification);
 
         let phases = &route_of_administration.phases;
         let mut current_time = ingestion.ingestion_date;
@@ -238,3 +295,58 @@ impl QueryHandler<Ingestion> for AnalyzeIngestion
         Ok(ingestion)
     }
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::substance::route_of_administration::dosage::DosageRange;
+
+    #[test]
+    fn test_classify_dosage_per_kg()
+    {
+        let mg = |milligrams: f64| Some(Dosage::from_miligrams(milligrams));
+        let dosages_per_kg = Dosages::from_iter([
+            (DosageClassification::Light, DosageRange::from_bounds(mg(3.0), mg(8.0))),
+            (DosageClassification::Common, DosageRange::from_bounds(mg(8.0), mg(15.0))),
+        ]);
+
+        let dosage = Dosage::from_miligrams(500.0);
+        let classify_at = |weight: f64| classify_dosage(&dosages_per_kg, &(dosage / weight));
+
+        assert_eq!(classify_at(75.0), Some(DosageClassification::Light));
+        assert_eq!(classify_at(50.0), Some(DosageClassification::Common));
+        assert_eq!(classify_at(20.0), Some(DosageClassification::Heavy));
+    }
+
+    #[test]
+    fn test_classify_dosage_on_shared_bound()
+    {
+        let mg = |milligrams: f64| Some(Dosage::from_miligrams(milligrams));
+        let dosages = Dosages::from_iter([
+            (DosageClassification::Common, DosageRange::from_bounds(mg(50.0), mg(150.0))),
+            (DosageClassification::Strong, DosageRange::from_bounds(mg(150.0), mg(300.0))),
+        ]);
+
+        assert_eq!(
+            classify_dosage(&dosages, &Dosage::from_miligrams(150.0)),
+            Some(DosageClassification::Strong)
+        );
+    }
+
+    #[test]
+    fn test_classify_dosage_in_gap_between_ranges()
+    {
+        let mg = |milligrams: f64| Some(Dosage::from_miligrams(milligrams));
+        let dosages = Dosages::from_iter([
+            (DosageClassification::Light, DosageRange::from_bounds(mg(3.0), mg(8.0))),
+            (DosageClassification::Common, DosageRange::from_bounds(mg(10.0), mg(15.0))),
+        ]);
+        let classify =
+            |milligrams: f64| classify_dosage(&dosages, &Dosage::from_miligrams(milligrams));
+
+        assert_eq!(classify(1.0), Some(DosageClassification::Threshold));
+        assert_eq!(classify(9.0), Some(DosageClassification::Light));
+        assert_eq!(classify(20.0), Some(DosageClassification::Heavy));
+    }
+}
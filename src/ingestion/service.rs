@@ -1,16 +1,30 @@
 use crate::core::QueryHandler;
+use crate::core::config::Config;
 use crate::database::IngestionPhase;
 use crate::database::entities::ingestion;
 use crate::database::entities::ingestion_phase;
 use crate::ingestion::Ingestion;
 use crate::ingestion::LogIngestion;
+use crate::ingestion::body_weight::body_weight_at;
 use crate::ingestion::query::AnalyzeIngestion;
+use crate::substance::conversion;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::route_of_administration::dosage::DosageEstimate;
+use crate::substance::solution::find_solution;
 use crate::utils::DATABASE_CONNECTION;
+use chrono::DateTime;
 use chrono::Local;
+use chrono::TimeZone;
 use miette::IntoDiagnostic;
+use miette::miette;
 use sea_orm::ActiveModelTrait;
 use sea_orm::ActiveValue;
+use sea_orm::ColumnTrait;
+use sea_orm::ConnectionTrait;
+use sea_orm::DatabaseConnection;
 use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
 use std::ops::Deref;
 use tracing::Level;
 use tracing::event;
@@ -20,122 +34,317 @@ pub struct IngestionService {}
 
 impl IngestionService
 {
+    /// Log ingestion, its substance name is looked up in PubChem unless
+    /// neuronek is offline.
     pub async fn log(command: &LogIngestion) -> miette::Result<Ingestion>
     {
-        let substance_name = pubchem::Compound::with_name(&command.substance_name)
-            .title()
-            .into_diagnostic()
-            .unwrap_or(command.substance_name.clone());
+        Self::log_with_lookup(command, !Config::current().offline).await
+    }
+
+    /// Log ingestion without looking its substance up in PubChem, for callers
+    /// which can't wait for the blocking request, such as the TUI.
+    pub async fn log_offline(command: &LogIngestion) -> miette::Result<Ingestion>
+    {
+        Self::log_with_lookup(command, false).await
+    }
+
+    async fn log_with_lookup(command: &LogIngestion, lookup: bool) -> miette::Result<Ingestion>
+    {
+        if let Some(solution) = &command.solution
+        {
+            let solution = find_solution(DATABASE_CONNECTION.deref(), solution).await?;
+            // Bounds of a range are always in the same unit.
+            let dosage = match command.dosage.lower()
+            {
+                | Some(Amount::Volume(_)) | None =>
+                {
+                    command.dosage.map(|volume| solution.dosage_of(volume.value()))
+                }
+                | Some(amount) =>
+                {
+                    return Err(miette!("Dosage of solution has to be a volume, not {}", amount));
+                }
+            };
+
+            let analysis = Self::analyze(
+                DATABASE_CONNECTION.deref(),
+                &solution.substance_name,
+                &dosage,
+                command.route_of_administration,
+                command.ingestion_date,
+            )
+            .await?;
+
+            return Self::record(
+                DATABASE_CONNECTION.deref(),
+                command,
+                &solution.substance_name,
+                dosage,
+                Some(solution.id),
+                analysis,
+            )
+            .await;
+        }
+
+        let substance_name = match lookup
+        {
+            | false => command.substance_name.clone(),
+            | true => pubchem::Compound::with_name(&command.substance_name)
+                .title()
+                .into_diagnostic()
+                .unwrap_or(command.substance_name.clone()),
+        };
+
+        let dosage = conversion::to_dosage_estimate(
+            DATABASE_CONNECTION.deref(),
+            &[&substance_name, &command.substance_name],
+            &command.dosage,
+        )
+        .await?;
+
+        let analysis = Self::analyze(
+            DATABASE_CONNECTION.deref(),
+            &substance_name,
+            &dosage,
+            command.route_of_administration,
+            command.ingestion_date,
+        )
+        .await?;
+
+        Self::record(DATABASE_CONNECTION.deref(), command, &substance_name, dosage, None, analysis)
+            .await
+    }
+
+    /// Store ingestion under given substance name as it is, without looking
+    /// it up, together with its analysis. Dosage is the mass of the logged
+    /// amount, which is the volume of solution when it's given. Ingestion
+    /// and its phases are written through given connection, which may be a
+    /// transaction.
+    pub async fn record(
+        database_connection: &impl ConnectionTrait,
+        command: &LogIngestion,
+        substance_name: &str,
+        dosage: DosageEstimate,
+        solution_id: Option<i32>,
+        analysis: Option<Ingestion>,
+    ) -> miette::Result<Ingestion>
+    {
+        let (amount, amount_max, amount_unit) = command.dosage.to_stored();
+        let (dosage_value, dosage_max, dosage_approximate) = dosage.to_stored();
 
         let ingestion = crate::database::Ingestion::insert(ingestion::ActiveModel {
             id: ActiveValue::default(),
             substance_name: ActiveValue::Set(substance_name.to_lowercase().clone()),
-            route_of_administration: ActiveValue::Set(
-                serde_json::to_value(command.route_of_administration)
-                    .unwrap()
-                    .as_str()
-                    .unwrap()
-                    .to_string(),
-            ),
-            dosage: ActiveValue::Set(command.dosage.as_base_units() as f32),
+            route_of_administration: ActiveValue::Set(stored_route(
+                command.route_of_administration,
+            )),
+            dosage: ActiveValue::Set(dosage_value),
+            dosage_max: ActiveValue::Set(dosage_max),
+            dosage_approximate: ActiveValue::Set(dosage_approximate),
             dosage_classification: ActiveValue::NotSet,
+            dosage_classification_max: ActiveValue::NotSet,
+            amount: ActiveValue::Set(amount),
+            amount_max: ActiveValue::Set(amount_max),
+            amount_unit: ActiveValue::Set(amount_unit),
+            solution_id: ActiveValue::Set(solution_id),
             ingested_at: ActiveValue::Set(command.ingestion_date.to_utc().naive_local()),
             updated_at: ActiveValue::Set(Local::now().to_utc().naive_local()),
             created_at: ActiveValue::Set(Local::now().to_utc().naive_local()),
         })
-        .exec_with_returning(DATABASE_CONNECTION.deref())
+        .exec_with_returning(database_connection)
         .await
         .into_diagnostic()?;
 
         event!(name: "ingestion_logged", Level::INFO, ingestion=?&ingestion);
 
+        Self::store_analysis(database_connection, ingestion.id, analysis.as_ref()).await?;
+
+        Ok(Ingestion::from(ingestion))
+    }
+
+    /// Classify dosage of an ingestion and estimate its phases. Substance is
+    /// read through the shared connection, so the analysis has to be done
+    /// before a transaction holding it is started. Failed analysis is only
+    /// logged, the ingestion is stored without it.
+    pub async fn analyze(
+        database_connection: &impl ConnectionTrait,
+        substance_name: &str,
+        dosage: &DosageEstimate,
+        route: RouteOfAdministrationClassification,
+        ingestion_date: DateTime<Local>,
+    ) -> miette::Result<Option<Ingestion>>
+    {
+        let body_weight = body_weight_at(database_connection, ingestion_date).await?;
+
         let analysis_query = AnalyzeIngestion::builder()
-            .substance(command.substance_name.clone())
-            .date(command.ingestion_date)
-            .dosage(command.dosage)
-            .roa(command.route_of_administration)
-            .ingestion_id(ingestion.id)
+            .substance(substance_name.to_string())
+            .date(ingestion_date)
+            .dosage(dosage.clone())
+            .roa(route)
+            .maybe_body_weight(body_weight.map(|body_weight| body_weight.weight))
             .build();
 
         match analysis_query.query().await
         {
-            | Ok(analysis) =>
-            {
-                let mut analysis = analysis;
-                analysis.id = Some(ingestion.id);
-
-                if analysis.dosage_classification.is_some()
-                {
-                    let update_model = ingestion::ActiveModel {
-                        id: ActiveValue::Set(ingestion.id),
-                        dosage_classification: ActiveValue::Set(
-                            analysis.dosage_classification.map(|d| d.to_string()),
-                        ),
-                        ..Default::default()
-                    };
-
-                    update_model
-                        .update(DATABASE_CONNECTION.deref())
-                        .await
-                        .into_diagnostic()?;
-
-                    if !analysis.phases.is_empty()
-                    {
-                        let phase_models = analysis
-                            .phases
-                            .clone()
-                            .into_iter()
-                            .map(|phase| ingestion_phase::ActiveModel {
-                                id: ActiveValue::Set(Uuid::new_v4().to_string()),
-                                ingestion_id: ActiveValue::Set(ingestion.id),
-                                classification: ActiveValue::Set(phase.class.to_string()),
-                                start_date_min: ActiveValue::Set(
-                                    phase.start_time.start.naive_utc(),
-                                ),
-                                start_date_max: ActiveValue::Set(phase.start_time.end.naive_utc()),
-                                end_date_min: ActiveValue::Set(phase.end_time.start.naive_utc()),
-                                end_date_max: ActiveValue::Set(phase.end_time.end.naive_utc()),
-                                common_dosage_weight: ActiveValue::Set(
-                                    command.dosage.as_base_units() as i32,
-                                ),
-                                duration_min: ActiveValue::Set(
-                                    phase.duration.start.num_minutes() as i32
-                                ),
-                                duration_max: ActiveValue::Set(
-                                    phase.duration.end.num_minutes() as i32
-                                ),
-                                notes: ActiveValue::NotSet,
-                                created_at: ActiveValue::Set(Local::now().to_string()),
-                                updated_at: ActiveValue::Set(Local::now().to_string()),
-                            })
-                            .collect::<Vec<_>>();
-
-                        IngestionPhase::insert_many(phase_models.clone())
-                            .exec(DATABASE_CONNECTION.deref())
-                            .await
-                            .into_diagnostic()?;
-
-                        event!(
-                            name: "ingestion_analyzed",
-                            Level::INFO,
-                            ingestion = ?&analysis,
-                        );
-
-                        analysis.phases = vec![];
-                    }
-                }
-            }
+            | Ok(analysis) => Ok(Some(analysis)),
             | Err(e) =>
             {
                 event!(
                     name: "ingestion_analysis_failed",
                     Level::WARN,
                     error = ?e,
-                    ingestion_id = ingestion.id
+                    substance = substance_name
                 );
+                Ok(None)
             }
         }
+    }
+
+    /// Store dosage classification of stored ingestion, which besides the
+    /// analysed ingestion depends on body weight at the time of ingestion.
+    pub async fn store_classification(
+        database_connection: &impl ConnectionTrait,
+        ingestion_id: i32,
+        analysis: Option<&Ingestion>,
+    ) -> miette::Result<()>
+    {
+        // Classification of previous analysis is cleared when the ingestion
+        // can't be classified anymore.
+        ingestion::ActiveModel {
+            id: ActiveValue::Set(ingestion_id),
+            dosage_classification: ActiveValue::Set(
+                analysis
+                    .and_then(|analysis| analysis.dosage_classification)
+                    .map(|d| d.to_string()),
+            ),
+            dosage_classification_max: ActiveValue::Set(
+                analysis
+                    .and_then(|analysis| analysis.dosage_classification_max)
+                    .map(|d| d.to_string()),
+            ),
+            ..Default::default()
+        }
+        .update(database_connection)
+        .await
+        .into_diagnostic()?;
 
-        Ok(Ingestion::from(ingestion))
+        Ok(())
+    }
+
+    /// Classify again ingestions which happened within given dates, after
+    /// body weight they're normalised with has changed. Phases don't depend
+    /// on body weight, so they're kept together with their notes.
+    pub async fn reclassify_between(
+        database_connection: &DatabaseConnection,
+        from: Option<DateTime<Local>>,
+        to: Option<DateTime<Local>>,
+    ) -> miette::Result<usize>
+    {
+        let mut query = ingestion::Entity::find();
+
+        if let Some(from) = from
+        {
+            query = query.filter(ingestion::Column::IngestedAt.gte(from.to_utc().naive_utc()));
+        }
+
+        if let Some(to) = to
+        {
+            query = query.filter(ingestion::Column::IngestedAt.lt(to.to_utc().naive_utc()));
+        }
+
+        let ingestions = query.all(database_connection).await.into_diagnostic()?;
+
+        for ingestion in &ingestions
+        {
+            let analysis = Self::analyze(
+                database_connection,
+                &ingestion.substance_name,
+                &DosageEstimate::from_stored(
+                    ingestion.dosage,
+                    ingestion.dosage_max,
+                    ingestion.dosage_approximate,
+                ),
+                ingestion
+                    .route_of_administration
+                    .parse()
+                    .unwrap_or(RouteOfAdministrationClassification::Oral),
+                Local.from_utc_datetime(&ingestion.ingested_at),
+            )
+            .await?;
+
+            Self::store_classification(database_connection, ingestion.id, analysis.as_ref())
+                .await?;
+        }
+
+        Ok(ingestions.len())
+    }
+
+    /// Store classification and phases of stored ingestion, replacing results
+    /// of its previous analysis. Analysis depends on the substance, dosage,
+    /// route and date of the ingestion, so it's repeated whenever one of them
+    /// changes.
+    pub async fn store_analysis(
+        database_connection: &impl ConnectionTrait,
+        ingestion_id: i32,
+        analysis: Option<&Ingestion>,
+    ) -> miette::Result<()>
+    {
+        Self::store_classification(database_connection, ingestion_id, analysis).await?;
+
+        IngestionPhase::delete_many()
+            .filter(ingestion_phase::Column::IngestionId.eq(ingestion_id))
+            .exec(database_connection)
+            .await
+            .into_diagnostic()?;
+
+        // Phases don't depend on dosage, so they're known also for unknown
+        // dosages.
+        if let Some(analysis) = analysis.filter(|analysis| !analysis.phases.is_empty())
+        {
+            let phase_models = analysis
+                .phases
+                .iter()
+                .map(|phase| ingestion_phase::ActiveModel {
+                    id: ActiveValue::Set(Uuid::new_v4().to_string()),
+                    ingestion_id: ActiveValue::Set(ingestion_id),
+                    classification: ActiveValue::Set(phase.class.to_string()),
+                    start_date_min: ActiveValue::Set(phase.start_time.start.naive_utc()),
+                    start_date_max: ActiveValue::Set(phase.start_time.end.naive_utc()),
+                    end_date_min: ActiveValue::Set(phase.end_time.start.naive_utc()),
+                    end_date_max: ActiveValue::Set(phase.end_time.end.naive_utc()),
+                    common_dosage_weight: ActiveValue::Set(
+                        analysis
+                            .dosage
+                            .value()
+                            .map_or(0, |dosage| dosage.as_base_units() as i32),
+                    ),
+                    duration_min: ActiveValue::Set(phase.duration.start.num_minutes() as i32),
+                    duration_max: ActiveValue::Set(phase.duration.end.num_minutes() as i32),
+                    notes: ActiveValue::NotSet,
+                    created_at: ActiveValue::Set(Local::now().to_string()),
+                    updated_at: ActiveValue::Set(Local::now().to_string()),
+                })
+                .collect::<Vec<_>>();
+
+            IngestionPhase::insert_many(phase_models)
+                .exec(database_connection)
+                .await
+                .into_diagnostic()?;
+
+            event!(
+                name: "ingestion_analyzed",
+                Level::INFO,
+                ingestion_id = ingestion_id,
+                ingestion = ?analysis,
+            );
+        }
+
+        Ok(())
     }
 }
+
+/// Route of administration as it's stored in the journal, e.g. "oral".
+pub fn stored_route(route: RouteOfAdministrationClassification) -> String
+{
+    serde_json::to_value(route).unwrap().as_str().unwrap().to_string()
+}
@@ -24,6 +24,7 @@ mod database;
 mod ingestion;
 mod prelude;
 mod substance;
+#[cfg(feature = "tui")]
 mod tui;
 mod utils;
 
@@ -33,9 +34,7 @@ async fn main() -> Result<()>
     setup_diagnostics();
     let _guard = setup_logger().unwrap();
 
-    migrate_database(&DATABASE_CONNECTION)
-        .await
-        .expect("Database database failed");
+    cli::apply_configuration_options()?;
 
     // TODO: Perform a check of completion scripts existence and update them or
     // install them https://askubuntu.com/a/1188315
@@ -43,16 +42,25 @@ async fn main() -> Result<()>
     // https://apple.github.io/swift-argument-parser/documentation/argumentparser/installingcompletionscripts/
     // https://unix.stackexchange.com/a/605051
 
-    // let no_args_provided = env::args().len() == 1;
-    // let is_interactive_terminal = atty::is(Stream::Stdout);
-    //
-    // if no_args_provided && is_interactive_terminal
-    // {
-    //     return tui::run();
-    // }
+    #[cfg(feature = "tui")]
+    {
+        let no_args_provided = env::args().len() == 1;
+        let is_interactive_terminal = atty::is(Stream::Stdout) && atty::is(Stream::Stdin);
+
+        if no_args_provided && is_interactive_terminal
+        {
+            migrate_database(&DATABASE_CONNECTION).await?;
+            return tui::run().await;
+        }
+    }
 
     let cli = CommandLineInterface::parse();
 
+    if cli.command.migrates_database()
+    {
+        migrate_database(&DATABASE_CONNECTION).await?;
+    }
+
     let context = AppContext {
         database_connection: &DATABASE_CONNECTION,
         stdout_format: cli.format,
@@ -0,0 +1,240 @@
+//! Bioavailability of substances by route of administration, the fraction of
+//! ingested dosage which reaches systemic circulation. Effective dosage
+//! (dosage multiplied by bioavailability) makes ingestions of the same
+//! substance through different routes comparable.
+
+use crate::database::entities::substance_bioavailability;
+use crate::ingestion::service::stored_route;
+use crate::substance::conversion::normalize_name;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use hashbrown::HashMap;
+use miette::IntoDiagnostic;
+use miette::miette;
+use sea_orm::ActiveValue;
+use sea_orm::ColumnTrait;
+use sea_orm::DatabaseConnection;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use sea_orm::sea_query::OnConflict;
+use std::str::FromStr;
+
+/// Bioavailability of a substance ingested through a route, e.g. 0.17 of
+/// ketamine taken orally.
+#[derive(Debug, Clone)]
+pub struct Bioavailability
+{
+    pub substance_name: String,
+    pub route: RouteOfAdministrationClassification,
+    pub bioavailability: f64,
+}
+
+impl TryFrom<substance_bioavailability::Model> for Bioavailability
+{
+    type Error = miette::Report;
+
+    fn try_from(model: substance_bioavailability::Model) -> Result<Self, Self::Error>
+    {
+        Ok(Self {
+            route: RouteOfAdministrationClassification::from_str(&model.route_of_administration)
+                .map_err(|_| {
+                    miette!("Unknown route of administration {}", model.route_of_administration)
+                })?,
+            substance_name: model.substance_name,
+            bioavailability: model.bioavailability as f64,
+        })
+    }
+}
+
+/// Bioavailability of every known substance and route, keyed by lowercase
+/// substance name.
+#[derive(Debug, Clone, Default)]
+pub struct Bioavailabilities(HashMap<(String, RouteOfAdministrationClassification), f64>);
+
+impl Bioavailabilities
+{
+    /// Bioavailability of substance ingested through given route, intravenous
+    /// route is complete unless stated otherwise.
+    pub fn get(
+        &self,
+        substance_name: &str,
+        route: RouteOfAdministrationClassification,
+    ) -> Option<f64>
+    {
+        self.0
+            .get(&(normalize_name(substance_name), route))
+            .copied()
+            .or_else(|| default_bioavailability(route))
+    }
+}
+
+impl FromIterator<Bioavailability> for Bioavailabilities
+{
+    fn from_iter<T: IntoIterator<Item = Bioavailability>>(iter: T) -> Self
+    {
+        Self(
+            iter.into_iter()
+                .map(|b| ((normalize_name(&b.substance_name), b.route), b.bioavailability))
+                .collect(),
+        )
+    }
+}
+
+/// Bioavailability which doesn't depend on substance.
+pub fn default_bioavailability(route: RouteOfAdministrationClassification) -> Option<f64>
+{
+    match route
+    {
+        | RouteOfAdministrationClassification::Intravenous => Some(1.0),
+        | _ => None,
+    }
+}
+
+/// Display bioavailability as percentage, e.g. "17%".
+pub fn display_bioavailability(bioavailability: f64) -> String
+{
+    format!("{}%", (bioavailability * 1_000.0).round() / 10.0)
+}
+
+/// Parse bioavailability given as percentage ("17%") or fraction ("0.17").
+pub fn parse_bioavailability(s: &str) -> Result<f64, String>
+{
+    let s = s.trim();
+    let bioavailability = match s.strip_suffix('%')
+    {
+        | Some(percentage) => percentage.trim().parse::<f64>().map(|p| p / 100.0),
+        | None => s.parse::<f64>(),
+    }
+    .map_err(|_| format!("Invalid bioavailability \"{}\", expected e.g. 17% or 0.17", s))?;
+
+    if bioavailability > 0.0 && bioavailability <= 1.0
+    {
+        Ok(bioavailability)
+    }
+    else
+    {
+        Err(format!("Bioavailability \"{}\" has to be above 0% and at most 100%", s))
+    }
+}
+
+/// Bioavailability of all substances, or of given substance, ordered by
+/// substance and route.
+pub async fn list_bioavailability(
+    database_connection: &DatabaseConnection,
+    substance_name: Option<&str>,
+) -> miette::Result<Vec<Bioavailability>>
+{
+    let mut query = substance_bioavailability::Entity::find()
+        .order_by_asc(substance_bioavailability::Column::SubstanceName)
+        .order_by_asc(substance_bioavailability::Column::RouteOfAdministration);
+
+    if let Some(substance_name) = substance_name
+    {
+        query = query.filter(
+            substance_bioavailability::Column::SubstanceName.eq(normalize_name(substance_name)),
+        );
+    }
+
+    query
+        .all(database_connection)
+        .await
+        .into_diagnostic()?
+        .into_iter()
+        .map(Bioavailability::try_from)
+        .collect()
+}
+
+/// Bioavailability of all substances and routes.
+pub async fn find_bioavailabilities(
+    database_connection: &DatabaseConnection,
+) -> miette::Result<Bioavailabilities>
+{
+    Ok(list_bioavailability(database_connection, None)
+        .await?
+        .into_iter()
+        .collect())
+}
+
+/// Store bioavailability of substance ingested through given route,
+/// replacing the existing one.
+pub async fn set_bioavailability(
+    database_connection: &DatabaseConnection,
+    substance_name: &str,
+    route: RouteOfAdministrationClassification,
+    bioavailability: f64,
+) -> miette::Result<Bioavailability>
+{
+    let model = substance_bioavailability::ActiveModel {
+        substance_name: ActiveValue::Set(normalize_name(substance_name)),
+        route_of_administration: ActiveValue::Set(stored_route(route)),
+        bioavailability: ActiveValue::Set(bioavailability as f32),
+    };
+
+    substance_bioavailability::Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                substance_bioavailability::Column::SubstanceName,
+                substance_bioavailability::Column::RouteOfAdministration,
+            ])
+            .update_column(substance_bioavailability::Column::Bioavailability)
+            .to_owned(),
+        )
+        .exec_with_returning(database_connection)
+        .await
+        .into_diagnostic()
+        .and_then(Bioavailability::try_from)
+}
+
+/// Remove bioavailability of substance through given route, returns whether
+/// there was one.
+pub async fn remove_bioavailability(
+    database_connection: &DatabaseConnection,
+    substance_name: &str,
+    route: RouteOfAdministrationClassification,
+) -> miette::Result<bool>
+{
+    let result = substance_bioavailability::Entity::delete_by_id((
+        normalize_name(substance_name),
+        stored_route(route),
+    ))
+    .exec(database_connection)
+    .await
+    .into_diagnostic()?;
+
+    Ok(result.rows_affected > 0)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_bioavailability()
+    {
+        assert_eq!(parse_bioavailability("17%"), Ok(0.17));
+        assert_eq!(parse_bioavailability("0.5"), Ok(0.5));
+        assert!(parse_bioavailability("120%").is_err());
+        assert!(parse_bioavailability("0").is_err());
+        assert_eq!(display_bioavailability(0.935), "93.5%");
+
+        let bioavailabilities = Bioavailabilities::from_iter([Bioavailability {
+            substance_name: "Ketamine".to_string(),
+            route: RouteOfAdministrationClassification::Oral,
+            bioavailability: 0.17,
+        }]);
+
+        assert_eq!(
+            bioavailabilities.get("ketamine", RouteOfAdministrationClassification::Oral),
+            Some(0.17)
+        );
+        assert_eq!(
+            bioavailabilities.get("ketamine", RouteOfAdministrationClassification::Intravenous),
+            Some(1.0)
+        );
+        assert_eq!(
+            bioavailabilities.get("ketamine", RouteOfAdministrationClassification::Smoked),
+            None
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Conversions of amounts in units other than mass (volume, international
+//! units, drops, tablets...) to mass of a substance. Ingestions always keep
+//! their mass, so they're classified the same way however they were logged.
+
+use crate::database::entities::substance_unit_conversion;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::DosageEstimate;
+use crate::substance::route_of_administration::dosage::Estimate;
+use hashbrown::HashMap;
+use miette::IntoDiagnostic;
+use miette::miette;
+use sea_orm::ActiveValue;
+use sea_orm::ColumnTrait;
+use sea_orm::DatabaseConnection;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use sea_orm::sea_query::OnConflict;
+
+/// Mass of a single unit of a substance, e.g. 95 mg per cup of coffee.
+#[derive(Debug, Clone)]
+pub struct UnitConversion
+{
+    pub substance_name: String,
+    pub unit: String,
+    pub dosage: Dosage,
+}
+
+impl From<substance_unit_conversion::Model> for UnitConversion
+{
+    fn from(model: substance_unit_conversion::Model) -> Self
+    {
+        Self {
+            substance_name: model.substance_name,
+            unit: model.unit,
+            dosage: Dosage::from_base_units(model.dosage as f64),
+        }
+    }
+}
+
+/// Substance names are stored trimmed and lowercase.
+pub(crate) fn normalize_name(substance_name: &str) -> String
+{
+    substance_name.trim().to_lowercase()
+}
+
+/// Conversions of given substance by their unit.
+pub async fn find_conversions(
+    database_connection: &DatabaseConnection,
+    substance_name: &str,
+) -> miette::Result<HashMap<String, Dosage>>
+{
+    Ok(list_conversions(database_connection, Some(substance_name))
+        .await?
+        .into_iter()
+        .map(|conversion| (conversion.unit, conversion.dosage))
+        .collect())
+}
+
+/// All conversions, or conversions of given substance, ordered by substance
+/// and unit.
+pub async fn list_conversions(
+    database_connection: &DatabaseConnection,
+    substance_name: Option<&str>,
+) -> miette::Result<Vec<UnitConversion>>
+{
+    let mut query = substance_unit_conversion::Entity::find()
+        .order_by_asc(substance_unit_conversion::Column::SubstanceName)
+        .order_by_asc(substance_unit_conversion::Column::Unit);
+
+    if let Some(substance_name) = substance_name
+    {
+        query = query.filter(
+            substance_unit_conversion::Column::SubstanceName.eq(normalize_name(substance_name)),
+        );
+    }
+
+    Ok(query
+        .all(database_connection)
+        .await
+        .into_diagnostic()?
+        .into_iter()
+        .map(UnitConversion::from)
+        .collect())
+}
+
+/// Mass of the amount of substance, known under any of given names. Amounts
+/// in units of mass are returned as they are.
+pub async fn to_dosage(
+    database_connection: &DatabaseConnection,
+    substance_names: &[&str],
+    amount: &Amount,
+) -> miette::Result<Dosage>
+{
+    if let Some(dosage) = amount.as_dosage()
+    {
+        return Ok(dosage);
+    }
+
+    let unit = amount.conversion_unit().unwrap_or_default();
+
+    for substance_name in substance_names
+    {
+        if let Some(dosage) = find_conversions(database_connection, substance_name)
+            .await?
+            .get(&unit)
+        {
+            return Ok(amount.convert(*dosage));
+        }
+    }
+
+    let substance_name = substance_names.first().copied().unwrap_or_default();
+
+    Err(miette!(
+        help = format!(
+            "Add mass of a single unit with `neuronek substance unit set \"{}\" \"1 {}\" \
+             <DOSAGE>`",
+            substance_name, unit
+        ),
+        "Can't convert {} of {} to mass",
+        amount,
+        substance_name
+    ))
+}
+
+/// Estimate of mass of the estimated amount, every bound of a range is
+/// converted with `to_dosage`.
+pub async fn to_dosage_estimate(
+    database_connection: &DatabaseConnection,
+    substance_names: &[&str],
+    amount: &Estimate<Amount>,
+) -> miette::Result<DosageEstimate>
+{
+    let to_dosage = |amount| to_dosage(database_connection, substance_names, amount);
+
+    Ok(match amount
+    {
+        | Estimate::Exact(amount) => Estimate::Exact(to_dosage(amount).await?),
+        | Estimate::Approximate(amount) => Estimate::Approximate(to_dosage(amount).await?),
+        | Estimate::Range(lower, upper) =>
+        {
+            Estimate::Range(to_dosage(lower).await?, to_dosage(upper).await?)
+        }
+        | Estimate::Unknown => Estimate::Unknown,
+    })
+}
+
+/// Store conversion given as an amount and its mass, e.g. "40 IU" of vitamin
+/// D3 weighs "1 µg". Existing conversion of the same unit is replaced.
+pub async fn set_conversion(
+    database_connection: &DatabaseConnection,
+    substance_name: &str,
+    amount: &Amount,
+    dosage: Dosage,
+) -> miette::Result<UnitConversion>
+{
+    let unit = amount
+        .conversion_unit()
+        .ok_or_else(|| miette!("Amount {} is already a mass, it needs no conversion", amount))?;
+
+    if amount.value() <= 0.0 || dosage.as_base_units() <= 0.0
+    {
+        return Err(miette!("Amount and dosage of a conversion have to be positive"));
+    }
+
+    let model = substance_unit_conversion::ActiveModel {
+        substance_name: ActiveValue::Set(normalize_name(substance_name)),
+        unit: ActiveValue::Set(unit),
+        dosage: ActiveValue::Set((dosage.as_base_units() / amount.value()) as f32),
+    };
+
+    substance_unit_conversion::Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                substance_unit_conversion::Column::SubstanceName,
+                substance_unit_conversion::Column::Unit,
+            ])
+            .update_column(substance_unit_conversion::Column::Dosage)
+            .to_owned(),
+        )
+        .exec_with_returning(database_connection)
+        .await
+        .into_diagnostic()
+        .map(UnitConversion::from)
+}
+
+/// Remove conversion of given unit, returns whether there was one.
+pub async fn remove_conversion(
+    database_connection: &DatabaseConnection,
+    substance_name: &str,
+    amount: &Amount,
+) -> miette::Result<bool>
+{
+    let unit = match amount.conversion_unit()
+    {
+        | Some(unit) => unit,
+        | None => return Ok(false),
+    };
+
+    let result =
+        substance_unit_conversion::Entity::delete_by_id((normalize_name(substance_name), unit))
+            .exec(database_connection)
+            .await
+            .into_diagnostic()?;
+
+    Ok(result.rows_affected > 0)
+}
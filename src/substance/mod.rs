@@ -3,8 +3,11 @@ pub mod route_of_administration;
 use crate::core::CommandHandler;
 use clap::Parser;
 use clap::Subcommand;
+pub mod bioavailability;
+pub mod conversion;
 pub mod error;
 pub mod repository;
+pub mod solution;
 
 use crate::substance::route_of_administration::RouteOfAdministrationClassification;
 use hashbrown::HashMap;
@@ -1,7 +1,11 @@
+use crate::core::config::Config;
 use crate::database::entities;
 use crate::database::entities::substance;
+use crate::substance::bioavailability::Bioavailabilities;
+use crate::substance::bioavailability::list_bioavailability;
+use crate::substance::conversion;
 use crate::substance::error::SubstanceError;
-use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::Amount;
 use crate::substance::route_of_administration::dosage::DosageClassification;
 use crate::substance::route_of_administration::dosage::DosageRange;
 use crate::substance::route_of_administration::phase::DurationRange;
@@ -20,7 +24,10 @@ use sea_orm::ColumnTrait;
 use sea_orm::EntityTrait;
 use sea_orm::ModelTrait;
 use sea_orm::QueryFilter;
+use sea_orm::prelude::Decimal;
 use std::str::FromStr;
+use tracing::Level;
+use tracing::event;
 
 // #[io_cached(
 //     disk = true,
@@ -30,6 +37,11 @@ use std::str::FromStr;
 // )]
 async fn enrich_substance_name_query(name: &str) -> Result<String, SubstanceError>
 {
+    if Config::current().offline
+    {
+        return Ok(name.to_string());
+    }
+
     Ok(pubchem::Compound::with_name(name)
         .title()
         .into_diagnostic()
@@ -72,16 +84,25 @@ pub async fn get_substance(
         routes_of_administration: RoutesOfAdministration::new(),
     };
 
+    let conversions = conversion::find_conversions(db, &substance.name).await?;
+    let bioavailabilities: Bioavailabilities =
+        list_bioavailability(db, Some(&substance.name)).await?.into_iter().collect();
+
     let db_connection = db.clone();
     let route_futures = routes_of_administration.into_iter().map(|route| {
         let db = db_connection.clone();
+        let conversions = &conversions;
+        let bioavailabilities = &bioavailabilities;
+        let substance_name = substance.name.clone();
         async move {
             let classification = RouteOfAdministrationClassification::from_str(&route.name)
                 .map_err(|e| miette!(format!("{:?}", e)))?;
             let mut roa = RouteOfAdministration {
                 classification,
                 dosages: Default::default(),
+                dosages_per_kg: Default::default(),
                 phases: Default::default(),
+                bioavailability: bioavailabilities.get(&substance_name, classification),
             };
 
             let dosages = route
@@ -95,23 +116,54 @@ pub async fn get_substance(
                 let dosage_classification = DosageClassification::from_str(&dosage.intensity)
                     .map_err(|_| miette!("Failed to parse dosage classification"))?;
 
-                let lower_bound = dosage
-                    .lower_bound_amount
-                    .map(|amount| Dosage::from_str(&format!("{} {}", amount, dosage.unit)))
-                    .transpose()
-                    .map_err(|e| {
-                        miette!("Failed to parse lower_bound_amount into Dosage: {}", e)
-                    })?;
-
-                let upper_bound = dosage
-                    .upper_bound_amount
-                    .map(|amount| Dosage::from_str(&format!("{} {}", amount, dosage.unit)))
-                    .transpose()
-                    .map_err(|e| {
-                        miette!("Failed to parse upper_bound_amount into Dosage: {}", e)
-                    })?;
-
-                roa.dosages.insert(
+                // Ranges per kilogram of body weight are kept apart, their
+                // unit is mass followed by "/kg", e.g. "mg/kg of body weight".
+                let (unit, per_kg) = match dosage.unit.split_once("/kg")
+                {
+                    | Some((unit, _)) => (unit, true),
+                    | None => (dosage.unit.as_str(), false),
+                };
+
+                // Ranges in other units than mass (e.g. ml or seeds) are
+                // usable only when the substance has a conversion of the unit.
+                let to_dosage = |amount: Decimal| {
+                    let amount = Amount::from_str(&format!("{} {}", amount, unit)).ok()?;
+
+                    match amount.conversion_unit()
+                    {
+                        | Some(unit) => conversions
+                            .get(&unit)
+                            .map(|dosage_per_unit| amount.convert(*dosage_per_unit)),
+                        | None => amount.as_dosage(),
+                    }
+                };
+
+                let lower_bound = dosage.lower_bound_amount.map(to_dosage);
+                let upper_bound = dosage.upper_bound_amount.map(to_dosage);
+
+                if lower_bound.is_some_and(|bound| bound.is_none())
+                    || upper_bound.is_some_and(|bound| bound.is_none())
+                {
+                    event!(
+                        Level::DEBUG,
+                        unit = %dosage.unit,
+                        "Skipping dosage range in unit without conversion"
+                    );
+                    continue;
+                }
+
+                let lower_bound = lower_bound.flatten();
+                let upper_bound = upper_bound.flatten();
+
+                // Range without bounds would classify any normalised dosage.
+                if per_kg && lower_bound.is_none() && upper_bound.is_none()
+                {
+                    continue;
+                }
+
+                let dosages = if per_kg { &mut roa.dosages_per_kg } else { &mut roa.dosages };
+
+                dosages.insert(
                     dosage_classification,
                     DosageRange::from_bounds(lower_bound, upper_bound),
                 );
@@ -15,24 +15,532 @@ impl std::str::FromStr for Dosage
 {
     type Err = String;
 
-    /// Parse a &str into a valid `Dosage`.
+    /// Parse a &str into a valid `Dosage`, only units of mass are accepted.
     fn from_str(s: &str) -> Result<Self, Self::Err>
     {
-        let mass = Mass::from_str(s).unwrap();
-        Ok(Dosage(mass))
+        match Amount::from_str(s)?
+        {
+            | Amount::Mass(dosage) => Ok(dosage),
+            | amount => Err(format!(
+                "Dosage \"{}\" is not a mass, expected amount in µg, mg, g or kg (e.g. 100mg)",
+                amount
+            )),
+        }
+    }
+}
+
+/// Amount of substance as it was ingested. Masses are classified directly,
+/// other units are converted to mass with conversions of the substance (see
+/// `crate::substance::conversion`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Amount
+{
+    Mass(Dosage),
+    /// Volume in milliliters, e.g. of a tincture or a liquid substance.
+    Volume(f64),
+    /// International units, e.g. of vitamins.
+    InternationalUnits(f64),
+    /// Countable unit, e.g. drops, puffs, cups or tablets.
+    Count
+    {
+        count: f64,
+        unit: String,
+    },
+}
+
+/// Split amount such as "10 mg" into its value and unit.
+fn split_amount(s: &str) -> Option<(f64, &str)>
+{
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .map(|value| (value, unit.trim()))
+}
+
+/// Countable units are looked up in singular, so "2 cups" and "1 cup" share
+/// a conversion.
+fn singular(unit: &str) -> &str
+{
+    match unit
+    {
+        | unit if unit.ends_with("sses") || unit.ends_with("ches") || unit.ends_with("shes") =>
+        {
+            &unit[..unit.len() - 2]
+        }
+        | unit if unit.ends_with('s') && !unit.ends_with("ss") => &unit[..unit.len() - 1],
+        | unit => unit,
+    }
+}
+
+impl FromStr for Amount
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let (value, unit) = split_amount(s).filter(|(_, unit)| !unit.is_empty()).ok_or_else(|| {
+            format!(
+                "Invalid dosage \"{}\", expected amount followed by unit (e.g. 100mg, 5ml, \
+                 1000IU or 2 tablets)",
+                s
+            )
+        })?;
+
+        let amount = match unit.to_lowercase().as_str()
+        {
+            | "µg" | "μg" | "ug" | "mcg" => Amount::Mass(Dosage(Mass::from_micrograms(value))),
+            | "mg" => Amount::Mass(Dosage(Mass::from_milligrams(value))),
+            | "g" => Amount::Mass(Dosage(Mass::from_grams(value))),
+            | "kg" => Amount::Mass(Dosage(Mass::from_kilograms(value))),
+            | "µl" | "μl" | "ul" => Amount::Volume(value / 1_000.0),
+            | "ml" => Amount::Volume(value),
+            | "cl" => Amount::Volume(value * 10.0),
+            | "dl" => Amount::Volume(value * 100.0),
+            | "l" => Amount::Volume(value * 1_000.0),
+            | "iu" => Amount::InternationalUnits(value),
+            | unit if unit.chars().all(|c| c.is_alphabetic() || c == ' ') => Amount::Count {
+                count: value,
+                unit: unit.to_string(),
+            },
+            | _ => return Err(format!("Unknown unit \"{}\" of dosage \"{}\"", unit, s)),
+        };
+
+        Ok(amount)
+    }
+}
+
+impl fmt::Display for Amount
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let value = |value: f64| (value * 1_000.0).round() / 1_000.0;
+
+        match self
+        {
+            | Amount::Mass(dosage) => write!(f, "{}", dosage),
+            | Amount::Volume(milliliters) => write!(f, "{} ml", value(*milliliters)),
+            | Amount::InternationalUnits(units) => write!(f, "{} IU", value(*units)),
+            | Amount::Count { count, unit } => write!(f, "{} {}", value(*count), unit),
+        }
+    }
+}
+
+impl DisplayMass for Amount
+{
+    fn display_in(&self, unit: MassUnit) -> String
+    {
+        match self
+        {
+            | Amount::Mass(dosage) => dosage.display_in(unit),
+            | amount => amount.to_string(),
+        }
+    }
+}
+
+impl From<Dosage> for Amount
+{
+    fn from(dosage: Dosage) -> Self { Amount::Mass(dosage) }
+}
+
+impl Amount
+{
+    /// Mass of the amount when it's given in units of mass.
+    pub fn as_dosage(&self) -> Option<Dosage>
+    {
+        match self
+        {
+            | Amount::Mass(dosage) => Some(*dosage),
+            | _ => None,
+        }
+    }
+
+    /// Unit under which conversion of the amount to mass is stored, `None`
+    /// for masses which need no conversion.
+    pub fn conversion_unit(&self) -> Option<String>
+    {
+        match self
+        {
+            | Amount::Mass(_) => None,
+            | Amount::Volume(_) => Some("ml".to_string()),
+            | Amount::InternationalUnits(_) => Some("iu".to_string()),
+            | Amount::Count { unit, .. } => Some(singular(unit).to_string()),
+        }
+    }
+
+    /// Value of the amount in the unit of its conversion, e.g. milliliters
+    /// for volumes.
+    pub fn value(&self) -> f64
+    {
+        match self
+        {
+            | Amount::Mass(dosage) => dosage.as_base_units(),
+            | Amount::Volume(value)
+            | Amount::InternationalUnits(value)
+            | Amount::Count { count: value, .. } => *value,
+        }
+    }
+
+    /// Mass of the amount given mass of a single unit.
+    pub fn convert(&self, dosage_per_unit: Dosage) -> Dosage
+    {
+        match self
+        {
+            | Amount::Mass(dosage) => *dosage,
+            | amount => dosage_per_unit * amount.value(),
+        }
+    }
+
+    /// Value and unit stored alongside the mass of an ingestion, masses are
+    /// stored only as the mass itself.
+    pub fn to_stored(&self) -> (Option<f64>, Option<String>)
+    {
+        match self
+        {
+            | Amount::Mass(_) => (None, None),
+            | Amount::Volume(milliliters) => (Some(*milliliters), Some("ml".to_string())),
+            | Amount::InternationalUnits(units) => (Some(*units), Some("IU".to_string())),
+            | Amount::Count { count, unit } => (Some(*count), Some(unit.clone())),
+        }
+    }
+
+    /// Amount stored with an ingestion, see `Amount::to_stored`.
+    pub fn from_stored(value: Option<f64>, unit: Option<&str>) -> Option<Amount>
+    {
+        match (value, unit)
+        {
+            | (Some(value), Some(unit)) => Amount::from_str(&format!("{} {}", value, unit)).ok(),
+            | _ => None,
+        }
+    }
+}
+
+
+/// Dosage or amount as precisely as it's known, e.g. "200mg", "~200mg",
+/// "100-150mg" or "unknown".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Estimate<T>
+{
+    Exact(T),
+    Approximate(T),
+    /// Range between lower and upper bound (inclusive).
+    Range(T, T),
+    Unknown,
+}
+
+pub type DosageEstimate = Estimate<Dosage>;
+
+impl<T> Estimate<T>
+{
+    /// Lower bound of a range, otherwise the value itself.
+    pub fn lower(&self) -> Option<&T>
+    {
+        match self
+        {
+            | Estimate::Exact(value) | Estimate::Approximate(value) | Estimate::Range(value, _) =>
+            {
+                Some(value)
+            }
+            | Estimate::Unknown => None,
+        }
+    }
+
+    /// Upper bound of a range, otherwise the value itself.
+    pub fn upper(&self) -> Option<&T>
+    {
+        match self
+        {
+            | Estimate::Exact(value) | Estimate::Approximate(value) | Estimate::Range(_, value) =>
+            {
+                Some(value)
+            }
+            | Estimate::Unknown => None,
+        }
+    }
+
+    pub fn is_known(&self) -> bool { !matches!(self, Estimate::Unknown) }
+
+    /// Estimate of a value, which is the lower bound of a range when its upper
+    /// bound is given, and unknown without any value.
+    pub fn from_bounds(value: Option<T>, upper: Option<T>, approximate: bool) -> Self
+    {
+        match (value, upper)
+        {
+            | (Some(lower), Some(upper)) => Estimate::Range(lower, upper),
+            | (Some(value), None) if approximate => Estimate::Approximate(value),
+            | (Some(value), None) => Estimate::Exact(value),
+            | (None, _) => Estimate::Unknown,
+        }
+    }
+
+    /// Estimate of the same precision with every value mapped, e.g. amounts
+    /// converted to mass.
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Estimate<U>
+    {
+        match self
+        {
+            | Estimate::Exact(value) => Estimate::Exact(f(value)),
+            | Estimate::Approximate(value) => Estimate::Approximate(f(value)),
+            | Estimate::Range(lower, upper) => Estimate::Range(f(lower), f(upper)),
+            | Estimate::Unknown => Estimate::Unknown,
+        }
+    }
+}
+
+impl<T> From<T> for Estimate<T>
+{
+    fn from(value: T) -> Self { Estimate::Exact(value) }
+}
+
+/// Parse estimate with given parser of a single value. Lower bound of a range
+/// may omit the unit, which is then taken from the upper bound ("100-150mg").
+fn parse_estimate<T>(s: &str, parse: fn(&str) -> Result<T, String>) -> Result<Estimate<T>, String>
+{
+    let s = s.trim();
+
+    if s.eq_ignore_ascii_case("unknown") || s == "?"
+    {
+        return Ok(Estimate::Unknown);
+    }
+
+    if let Some(value) = s.strip_prefix('~').or_else(|| s.strip_prefix('≈'))
+    {
+        return Ok(Estimate::Approximate(parse(value)?));
+    }
+
+    match s.split_once(['-', '–'])
+    {
+        | Some((lower, upper)) =>
+        {
+            let lower = lower.trim();
+            let lower = match (lower.parse::<f64>(), split_amount(upper))
+            {
+                | (Ok(_), Some((_, unit))) => parse(&format!("{} {}", lower, unit))?,
+                | _ => parse(lower)?,
+            };
+
+            Ok(Estimate::Range(lower, parse(upper)?))
+        }
+        | None => Ok(Estimate::Exact(parse(s)?)),
+    }
+}
+
+impl FromStr for Estimate<Dosage>
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let estimate = parse_estimate(s, Dosage::from_str)?;
+
+        match &estimate
+        {
+            | Estimate::Range(lower, upper) if lower > upper =>
+            {
+                Err(format!("Lower bound of dosage range \"{}\" is above its upper bound", s))
+            }
+            | _ => Ok(estimate),
+        }
+    }
+}
+
+impl FromStr for Estimate<Amount>
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let estimate = parse_estimate(s, Amount::from_str)?;
+
+        match &estimate
+        {
+            | Estimate::Range(lower, upper)
+                if lower.conversion_unit() != upper.conversion_unit() =>
+            {
+                Err(format!("Bounds of dosage range \"{}\" are in different units", s))
+            }
+            | Estimate::Range(lower, upper) if lower.value() > upper.value() =>
+            {
+                Err(format!("Lower bound of dosage range \"{}\" is above its upper bound", s))
+            }
+            | _ => Ok(estimate),
+        }
+    }
+}
+
+impl<T> Estimate<T>
+{
+    /// Estimate with its values displayed by given function.
+    fn display_with(&self, display: impl Fn(&T) -> String) -> String
+    {
+        match self
+        {
+            | Estimate::Exact(value) => display(value),
+            | Estimate::Approximate(value) => format!("~{}", display(value)),
+            | Estimate::Range(lower, upper) =>
+            {
+                let (lower, upper) = (display(lower), display(upper));
+
+                // Bounds in the same unit share it, e.g. "100-150 mg".
+                match (lower.split_once(' '), upper.split_once(' '))
+                {
+                    | (Some((value, unit)), Some((_, upper_unit))) if unit == upper_unit =>
+                    {
+                        format!("{}-{}", value, upper)
+                    }
+                    | _ => format!("{} - {}", lower, upper),
+                }
+            }
+            | Estimate::Unknown => "unknown".to_string(),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Estimate<T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}", self.display_with(T::to_string))
+    }
+}
+
+impl<T: DisplayMass> DisplayMass for Estimate<T>
+{
+    fn display_in(&self, unit: MassUnit) -> String
+    {
+        self.display_with(|value| value.display_in(unit))
+    }
+}
+
+impl Estimate<Dosage>
+{
+    /// Single dosage standing for the estimate, the middle of a range. Used
+    /// where dosages are summed.
+    pub fn value(&self) -> Option<Dosage>
+    {
+        match self
+        {
+            | Estimate::Range(lower, upper) => Some((*lower + *upper) / 2.0),
+            | estimate => estimate.lower().copied(),
+        }
+    }
+
+    /// Estimate stored with an ingestion as its dosage (lower bound of a
+    /// range), upper bound of a range and whether it's approximate.
+    pub fn from_stored(dosage: Option<f32>, dosage_max: Option<f32>, approximate: bool) -> Self
+    {
+        let to_dosage = |value: f32| Dosage::from_base_units(value as f64);
+
+        Self::from_bounds(dosage.map(to_dosage), dosage_max.map(to_dosage), approximate)
+    }
+
+    /// See `Estimate::<Dosage>::from_stored`.
+    pub fn to_stored(&self) -> (Option<f32>, Option<f32>, bool)
+    {
+        let stored = |dosage: &Dosage| dosage.as_base_units() as f32;
+
+        match self
+        {
+            | Estimate::Range(lower, upper) => (Some(stored(lower)), Some(stored(upper)), false),
+            | estimate => (
+                estimate.lower().map(stored),
+                None,
+                matches!(estimate, Estimate::Approximate(_)),
+            ),
+        }
+    }
+}
+
+impl Estimate<Amount>
+{
+    /// Estimate of the amount followed by estimate of its mass, e.g. "1-2 cups
+    /// (95-190 mg)", masses are shown only once and in given unit.
+    pub fn display_with_mass(&self, dosage: &DosageEstimate, unit: MassUnit) -> String
+    {
+        match self.lower()
+        {
+            | Some(Amount::Mass(_)) | None => dosage.display_in(unit),
+            | Some(_) => format!("{} ({})", self, dosage.display_in(unit)),
+        }
+    }
+
+    /// Value, upper bound of a range and unit stored alongside the mass of an
+    /// ingestion, see `Amount::to_stored`.
+    pub fn to_stored(&self) -> (Option<f64>, Option<f64>, Option<String>)
+    {
+        let (amount, unit) = self.lower().map(Amount::to_stored).unwrap_or_default();
+        let amount_max = match self
+        {
+            | Estimate::Range(_, upper) => upper.to_stored().0,
+            | _ => None,
+        };
+
+        (amount, amount_max, unit)
     }
+
+    /// Amount stored with an ingestion, see `Estimate::<Amount>::to_stored`.
+    pub fn from_stored(
+        amount: Option<f64>,
+        amount_max: Option<f64>,
+        unit: Option<&str>,
+        approximate: bool,
+    ) -> Option<Self>
+    {
+        Amount::from_stored(amount, unit).map(|amount| {
+            Self::from_bounds(Some(amount), Amount::from_stored(amount_max, unit), approximate)
+        })
+    }
+}
+
+/// Unit in which dosages are displayed, `Auto` picks the most readable unit
+/// for every value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MassUnit
+{
+    #[default]
+    Auto,
+    Microgram,
+    Milligram,
+    Gram,
+}
+
+/// Values which contain a mass and can be displayed in a chosen unit, e.g.
+/// in the unit preferred by the user. `Display` always picks the most
+/// readable unit, so output read by other programs doesn't depend on
+/// preferences.
+pub trait DisplayMass
+{
+    fn display_in(&self, unit: MassUnit) -> String;
 }
 
+impl DisplayMass for Dosage
+{
+    fn display_in(&self, unit: MassUnit) -> String
+    {
+        let (unit, value) = match unit
+        {
+            | MassUnit::Auto => self.0.get_appropriate_units(),
+            | MassUnit::Microgram => ("µg", self.0.as_micrograms()),
+            | MassUnit::Milligram => ("mg", self.0.as_milligrams()),
+            | MassUnit::Gram => ("g", self.0.as_grams()),
+        };
+        let value_of_unit = format!("{:4.4}", PrettyPrintFloat(value));
+        format!("{} {}", value_of_unit.trim_start(), unit)
+    }
+}
 
 impl fmt::Display for Dosage
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
-        let suggested_unit = self.0.get_appropriate_units();
-        let value_of_unit = format!("{:4.4}", PrettyPrintFloat(suggested_unit.1));
-        let unit = suggested_unit.0;
-        let formatted = format!("{} {}", value_of_unit.trim_start(), unit);
-        write!(f, "{}", formatted)
+        write!(f, "{}", self.display_in(MassUnit::Auto))
     }
 }
 
@@ -49,6 +557,32 @@ impl Dosage
     }
 }
 
+impl std::ops::Add for Dosage
+{
+    type Output = Dosage;
+
+    fn add(self, rhs: Self) -> Self::Output { Dosage(self.0 + rhs.0) }
+}
+
+impl std::ops::Mul<f64> for Dosage
+{
+    type Output = Dosage;
+
+    fn mul(self, rhs: f64) -> Self::Output { Dosage(self.0 * rhs) }
+}
+
+impl std::ops::Div<f64> for Dosage
+{
+    type Output = Dosage;
+
+    fn div(self, rhs: f64) -> Self::Output { Dosage(self.0 / rhs) }
+}
+
+impl std::iter::Sum for Dosage
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self { iter.fold(Dosage::default(), |a, b| a + b) }
+}
+
 impl TryInto<Dosage> for Option<f64>
 {
     type Error = sea_orm::error::DbErr;
@@ -89,15 +623,87 @@ mod tests
         );
     }
 
+    #[test]
+    fn test_parse_amount()
+    {
+        assert_eq!(
+            Amount::from_str("250µg").unwrap(),
+            Amount::Mass(Dosage(Mass::from_micrograms(250f64)))
+        );
+        assert_eq!(Amount::from_str("0.5 mL").unwrap(), Amount::Volume(0.5));
+        assert_eq!(Amount::from_str("1 l").unwrap(), Amount::Volume(1000.0));
+        assert_eq!(Amount::from_str("1000IU").unwrap(), Amount::InternationalUnits(1000.0));
+
+        let cups = Amount::from_str("2 Cups").unwrap();
+        assert_eq!(cups.to_string(), "2 cups");
+        assert_eq!(cups.conversion_unit().as_deref(), Some("cup"));
+        assert!((cups.convert(Dosage::from_miligrams(95.0)).as_base_units() - 190e-6).abs() < 1e-12);
+
+        assert!(Amount::from_str("mg").is_err());
+        assert!(Amount::from_str("10 mg/kg").is_err());
+        assert!(Dosage::from_str("5ml").is_err());
+        assert!(Dosage::from_str("1000IU").is_err());
+    }
+
+    #[test]
+    fn test_parse_estimate()
+    {
+        let mg = Dosage::from_miligrams;
+
+        assert_eq!(DosageEstimate::from_str("200mg"), Ok(Estimate::Exact(mg(200.0))));
+        assert_eq!(DosageEstimate::from_str("~200mg"), Ok(Estimate::Approximate(mg(200.0))));
+        assert_eq!(
+            DosageEstimate::from_str("100-150mg"),
+            Ok(Estimate::Range(mg(100.0), mg(150.0)))
+        );
+        assert_eq!(
+            DosageEstimate::from_str("500mg - 1g"),
+            Ok(Estimate::Range(mg(500.0), mg(1_000.0)))
+        );
+        assert_eq!(DosageEstimate::from_str("unknown"), Ok(Estimate::Unknown));
+        assert_eq!(DosageEstimate::from_str("?"), Ok(Estimate::Unknown));
+        assert!(DosageEstimate::from_str("150-100mg").is_err());
+        assert!(DosageEstimate::from_str("~5ml").is_err());
+
+        let cups = Estimate::<Amount>::from_str("1-2 cups").unwrap();
+        assert_eq!(cups.to_string(), "1-2 cups");
+        assert_eq!(cups.to_stored(), (Some(1.0), Some(2.0), Some("cups".to_string())));
+        assert!(Estimate::<Amount>::from_str("1 cup-5ml").is_err());
+
+        assert_eq!(DosageEstimate::from_stored(None, None, false), Estimate::Unknown);
+        assert_eq!(
+            Estimate::Range(mg(100.0), mg(150.0)).value(),
+            Some(mg(125.0))
+        );
+    }
+
     #[test]
     fn test_format_dosage()
     {
         let dosage = Dosage(Mass::from_grams(0.1));
         assert_eq!(dosage.to_string(), "100 mg");
+        assert_eq!(Estimate::Approximate(dosage).to_string(), "~100 mg");
+        assert_eq!(Estimate::Range(dosage, dosage * 1.5).to_string(), "100-150 mg");
+        assert_eq!(Estimate::Range(dosage, dosage * 20.0).to_string(), "100 mg - 2.00 g");
+        assert_eq!(DosageEstimate::Unknown.to_string(), "unknown");
+    }
+
+    #[test]
+    fn test_display_dosage_in_unit()
+    {
+        let dosage = Dosage(Mass::from_grams(0.1));
+        assert_eq!(dosage.display_in(MassUnit::Gram), "0.10 g");
+        let microdose = Dosage(Mass::from_milligrams(0.5));
+        assert_eq!(microdose.display_in(MassUnit::Microgram), "500 µg");
+        assert_eq!(
+            Estimate::Range(dosage, dosage * 1.5).display_in(MassUnit::Gram),
+            "0.10-0.15 g"
+        );
+        assert_eq!(Amount::Volume(5.0).display_in(MassUnit::Gram), "5 ml");
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub enum DosageClassification
 {
     Threshold,
@@ -87,7 +87,12 @@ pub struct RouteOfAdministration
     #[allow(dead_code)] // This field is part of the public API
     pub classification: RouteOfAdministrationClassification,
     pub dosages: Dosages,
+    /// Dosage ranges per kilogram of body weight (e.g. "mg/kg of body
+    /// weight"), only some substances are described this way.
+    pub dosages_per_kg: Dosages,
     pub phases: Phases,
+    /// Fraction of dosage which reaches systemic circulation, when it's known.
+    pub bioavailability: Option<f64>,
 }
 
 pub type Dosages = HashMap<DosageClassification, DosageRange>;
@@ -0,0 +1,90 @@
+//! Solutions of a substance dissolved in a solvent, used for volumetric
+//! dosing. Ingestions of a solution store its volume together with the mass
+//! computed from concentration at the time, so they keep their dosage when
+//! the solution changes.
+
+use crate::database::entities::solution;
+use crate::substance::route_of_administration::dosage::Dosage;
+use miette::IntoDiagnostic;
+use miette::miette;
+use sea_orm::ColumnTrait;
+use sea_orm::DatabaseConnection;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+
+#[derive(Debug, Clone)]
+pub struct Solution
+{
+    pub id: i32,
+    pub name: String,
+    pub substance_name: String,
+    /// Mass of substance dissolved in the solvent.
+    pub dissolved: Dosage,
+    /// Volume of solvent in milliliters.
+    pub volume: f64,
+}
+
+impl From<solution::Model> for Solution
+{
+    fn from(model: solution::Model) -> Self
+    {
+        Self {
+            id: model.id,
+            name: model.name,
+            substance_name: model.substance_name,
+            dissolved: Dosage::from_base_units(model.dissolved_dosage),
+            volume: model.solvent_volume,
+        }
+    }
+}
+
+impl Solution
+{
+    /// Mass of substance in a single milliliter.
+    pub fn concentration(&self) -> Dosage { self.dissolved / self.volume }
+
+    /// Mass of substance in given volume in milliliters.
+    pub fn dosage_of(&self, volume: f64) -> Dosage { self.concentration() * volume }
+}
+
+/// Solution with given name.
+pub async fn find_solution(
+    database_connection: &DatabaseConnection,
+    name: &str,
+) -> miette::Result<Solution>
+{
+    solution::Entity::find()
+        .filter(solution::Column::Name.eq(name.trim()))
+        .one(database_connection)
+        .await
+        .into_diagnostic()?
+        .map(Solution::from)
+        .ok_or_else(|| {
+            miette!(
+                help = "Create it with `neuronek solution create`",
+                "Solution \"{}\" not found",
+                name
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_solution_dosage()
+    {
+        let solution = Solution {
+            id: 1,
+            name: "lsd".to_string(),
+            substance_name: "lsd".to_string(),
+            dissolved: Dosage::from_miligrams(1.0),
+            volume: 10.0,
+        };
+
+        assert_eq!(solution.concentration().to_string(), "100 µg");
+        assert!((solution.dosage_of(0.5).as_base_units() - 5e-8).abs() < 1e-15);
+    }
+}
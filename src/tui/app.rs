@@ -1,10 +1,123 @@
+use crate::core::QueryHandler;
+use crate::core::profile::Profile;
+use crate::database::entities::ingestion::Model as IngestionModel;
+use crate::database::entities::ingestion_phase;
+use crate::database::entities::substance;
+use crate::ingestion::Ingestion;
+use crate::ingestion::IngestionPhase;
+use crate::ingestion::query::AnalyzeIngestion;
+use crate::ingestion::query::ingested_between;
+use crate::ingestion::service::IngestionService;
+use crate::substance::route_of_administration::phase::PhaseClassification;
+use crate::utils::DATABASE_CONNECTION;
+use crate::core::config::Config;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Local;
+use chrono::NaiveDateTime;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
+use miette::IntoDiagnostic;
 use ratatui::prelude::*;
+use sea_orm::ColumnTrait;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use std::collections::HashMap;
 use std::error::Error;
 
+use super::components::log_ingestion_form::FormAction;
+use super::components::log_ingestion_form::LogIngestionForm;
+use super::keymap::Action;
+use super::keymap::Keymap;
+use super::tabs::settings::SettingsTab;
+use super::tabs::stats::StatsTab;
+use super::tabs::substances::SubstancesTab;
+use super::ui::AppLayout;
+use super::ui::list_index_at;
+use super::ui::tab_at;
+
+pub const HOME_TAB: usize = 0;
+pub const SUBSTANCES_TAB: usize = 1;
+pub const STATS_TAB: usize = 2;
+pub const SETTINGS_TAB: usize = 3;
+const TAB_COUNT: usize = 4;
+
+/// How far back ingestions are looked up when searching for the ones which
+/// are still active, no substance known to the application acts for longer.
+const ACTIVE_INGESTION_LOOKBACK: Duration = Duration::days(2);
+
+/// Ingestion which effects are still expected to be felt.
+#[derive(Debug, Clone)]
+pub struct ActiveIngestion
+{
+    pub ingestion: Ingestion,
+    pub current_phase: Option<PhaseClassification>,
+    /// Time left until the current phase ends.
+    pub phase_remaining: Option<Duration>,
+    /// Time left until the last phase of the ingestion ends.
+    pub remaining: Duration,
+    /// Part of the ingestion's total duration which has already passed, in
+    /// range from `0.0` to `1.0`.
+    pub progress: f64,
+}
+
+impl ActiveIngestion
+{
+    /// Build an active ingestion from analyzed ingestion, ingestions without
+    /// known phases or with all phases already finished are not active.
+    fn new(ingestion: &Ingestion, now: DateTime<Local>) -> Option<Self>
+    {
+        let end = ingestion.phases.iter().map(|p| p.end_time.end).max()?;
+
+        if now < ingestion.ingestion_date || now > end
+        {
+            return None;
+        }
+
+        let current_phase = ingestion
+            .phases
+            .iter()
+            .find(|phase| now >= phase.start_time.start && now <= phase.end_time.end);
+
+        let total = (end - ingestion.ingestion_date).num_seconds().max(1) as f64;
+        let elapsed = (now - ingestion.ingestion_date).num_seconds() as f64;
+
+        Some(Self {
+            ingestion: ingestion.clone(),
+            current_phase: current_phase.map(|phase| phase.class),
+            phase_remaining: current_phase.map(|phase| phase.end_time.end - now),
+            remaining: end - now,
+            progress: (elapsed / total).clamp(0.0, 1.0),
+        })
+    }
+}
+
 pub struct App
 {
     pub running: bool,
     pub selected_tab: usize,
+    pub active_ingestions: Vec<ActiveIngestion>,
+    /// Error which occurred during the last refresh, displayed in the footer.
+    pub error: Option<String>,
+    /// Message about result of the last action, displayed in the footer.
+    pub status: Option<String>,
+    /// Form used to log a new ingestion, displayed as a modal when open.
+    pub form: Option<LogIngestionForm>,
+    pub substances: SubstancesTab,
+    pub stats: StatsTab,
+    pub settings: SettingsTab,
+    pub keymap: Keymap,
+    pub profile: Profile,
+    /// Whether overlay listing key bindings is displayed.
+    pub show_help: bool,
+    /// Analyzed ingestions by their identifier together with the date of
+    /// their last update, analysis requires substance lookup so it's done
+    /// only once for each version of an ingestion.
+    analyzed: HashMap<i32, (NaiveDateTime, Ingestion)>,
 }
 
 impl Default for App
@@ -14,32 +127,381 @@ impl Default for App
         Self {
             running: true,
             selected_tab: 0,
+            active_ingestions: Vec::new(),
+            error: None,
+            status: None,
+            form: None,
+            substances: SubstancesTab::default(),
+            stats: StatsTab::default(),
+            settings: SettingsTab::default(),
+            keymap: Keymap::default(),
+            profile: Profile::active(),
+            show_help: false,
+            analyzed: HashMap::new(),
         }
     }
 }
 
 impl App
 {
-    pub fn new() -> Self { Self::default() }
+    /// Application with keymap loaded from configuration directory, default
+    /// keymap is used when the file is invalid.
+    pub fn new() -> Self
+    {
+        let mut app = Self::default();
 
-    pub fn tick(&mut self) {}
+        match Keymap::load()
+        {
+            | Ok(keymap) => app.keymap = keymap,
+            | Err(error) => app.status = Some(format!("{}, default key bindings are used", error)),
+        }
+
+        app
+    }
+
+    pub async fn tick(&mut self)
+    {
+        match self.refresh_active_ingestions().await
+        {
+            | Ok(()) => self.error = None,
+            | Err(error) => self.error = Some(error.to_string()),
+        }
+    }
 
     pub fn quit(&mut self) { self.running = false; }
 
-    pub fn on_key(&mut self, key: char)
+    pub async fn on_key_event(&mut self, key: KeyEvent)
     {
-        match key
+        if self.form.is_some()
+        {
+            return self.on_form_key_event(key).await;
+        }
+
+        if self.show_help
+        {
+            self.show_help = false;
+            return;
+        }
+
+        // Text inputs receive every key, so bindings don't interfere with
+        // typing.
+        if self.selected_tab == SUBSTANCES_TAB && self.substances.searching
+        {
+            self.substances.handle_key(key);
+        }
+        else if self.selected_tab == SETTINGS_TAB && self.settings.editing.is_some()
+        {
+            self.settings.handle_key(key);
+        }
+        else
+        {
+            match self.keymap.action(key)
+            {
+                | Some(action) => self.on_action(action).await,
+                | None => self.forward_key(key),
+            }
+        }
+
+        self.load_selected_tab().await;
+    }
+
+    async fn on_action(&mut self, action: Action)
+    {
+        match action
+        {
+            | Action::Quit => self.quit(),
+            | Action::Help => self.show_help = true,
+            | Action::LogIngestion => self.open_form().await,
+            | Action::NextTab => self.selected_tab = (self.selected_tab + 1) % TAB_COUNT,
+            | Action::PreviousTab =>
+            {
+                self.selected_tab = (self.selected_tab + TAB_COUNT - 1) % TAB_COUNT
+            }
+            | Action::HomeTab => self.selected_tab = HOME_TAB,
+            | Action::SubstancesTab => self.selected_tab = SUBSTANCES_TAB,
+            | Action::StatsTab => self.selected_tab = STATS_TAB,
+            | Action::SettingsTab => self.selected_tab = SETTINGS_TAB,
+            | Action::Search =>
+            {
+                self.selected_tab = SUBSTANCES_TAB;
+                self.forward_key(action.as_key().unwrap());
+            }
+            | _ =>
+            {
+                if let Some(key) = action.as_key()
+                {
+                    self.forward_key(key);
+                }
+            }
+        }
+    }
+
+    /// Pass key to the selected tab.
+    fn forward_key(&mut self, key: KeyEvent)
+    {
+        match self.selected_tab
+        {
+            | SUBSTANCES_TAB => self.substances.handle_key(key),
+            | STATS_TAB => self.stats.handle_key(key),
+            | SETTINGS_TAB => self.settings.handle_key(key),
+            | _ =>
+            {}
+        }
+    }
+
+    /// Select clicked tab or list row, scrolling moves selection. `area` is
+    /// the whole terminal.
+    pub async fn on_mouse_event(&mut self, mouse: MouseEvent, area: Rect)
+    {
+        if self.form.is_some()
+        {
+            return;
+        }
+
+        if self.show_help
+        {
+            self.show_help = mouse.kind != MouseEventKind::Down(MouseButton::Left);
+            return;
+        }
+
+        let position = Position::new(mouse.column, mouse.row);
+        let layout = AppLayout::new(area);
+
+        match mouse.kind
         {
-            | 'q' => self.quit(),
-            | '1'..='4' =>
+            | MouseEventKind::ScrollDown => self.forward_key(KeyEvent::from(KeyCode::Down)),
+            | MouseEventKind::ScrollUp => self.forward_key(KeyEvent::from(KeyCode::Up)),
+            | MouseEventKind::Down(MouseButton::Left) if layout.header.contains(position) =>
             {
-                if let Some(digit) = key.to_digit(10)
+                if let Some(tab) = tab_at(layout.header, position.x)
                 {
-                    self.selected_tab = (digit as usize) - 1;
+                    self.selected_tab = tab;
                 }
             }
+            | MouseEventKind::Down(MouseButton::Left) if layout.sidebar.contains(position) =>
+            {
+                match list_index_at(layout.sidebar, self.selected_tab, position)
+                {
+                    | Some(tab) if tab < TAB_COUNT => self.selected_tab = tab,
+                    | _ =>
+                    {}
+                }
+            }
+            | MouseEventKind::Down(MouseButton::Left) => match self.selected_tab
+            {
+                | SUBSTANCES_TAB => self.substances.handle_click(layout.main, position),
+                | STATS_TAB => self.stats.handle_click(layout.main, position),
+                | SETTINGS_TAB => self.settings.handle_click(layout.main, position),
+                | _ =>
+                {}
+            },
+            | _ => return,
+        }
+
+        self.load_selected_tab().await;
+    }
+
+    /// Insert pasted text into the input which is currently used.
+    pub fn on_paste(&mut self, text: &str)
+    {
+        if let Some(form) = self.form.as_mut()
+        {
+            return form.paste(text);
+        }
+
+        match self.selected_tab
+        {
+            | SUBSTANCES_TAB => self.substances.paste(text),
+            | SETTINGS_TAB => self.settings.paste(text),
             | _ =>
             {}
         }
     }
+
+    /// Load data displayed in the selected tab, tabs keep already loaded
+    /// data so this is cheap to call after every key press.
+    async fn load_selected_tab(&mut self)
+    {
+        let result = match self.selected_tab
+        {
+            | SUBSTANCES_TAB => self.substances.load().await,
+            | STATS_TAB => self.stats.load().await,
+            | _ => Ok(()),
+        };
+
+        if let Err(error) = result
+        {
+            self.error = Some(error.to_string());
+        }
+    }
+
+    async fn open_form(&mut self)
+    {
+        let substances = substance::Entity::find()
+            .order_by_asc(substance::Column::Name)
+            .all(&*DATABASE_CONNECTION)
+            .await
+            .map(|substances| substances.into_iter().map(|s| s.name).collect())
+            .unwrap_or_default();
+
+        self.status = None;
+        self.form = Some(LogIngestionForm::new(substances));
+    }
+
+    async fn on_form_key_event(&mut self, key: KeyEvent)
+    {
+        let Some(form) = self.form.as_mut()
+        else
+        {
+            return;
+        };
+
+        match form.handle_key(key)
+        {
+            | FormAction::None =>
+            {}
+            | FormAction::Cancel => self.form = None,
+            // PubChem lookup would block the event loop until it responds,
+            // names are offered from substances already in the database.
+            | FormAction::Submit(command) => match IngestionService::log_offline(&command).await
+            {
+                | Ok(ingestion) =>
+                {
+                    self.form = None;
+                    self.substances.invalidate(&ingestion.substance_name);
+                    self.stats.invalidate();
+                    self.status = Some(format!(
+                        "Logged {} of {}",
+                        ingestion.display_dosage(Config::current().units.mass),
+                        ingestion.substance_name
+                    ));
+                    self.tick().await;
+                }
+                | Err(error) => form.error = Some(format!("Failed to log ingestion: {}", error)),
+            },
+        }
+    }
+
+    async fn refresh_active_ingestions(&mut self) -> miette::Result<()>
+    {
+        let now = Local::now();
+        let models: Vec<IngestionModel> = ingested_between(now - ACTIVE_INGESTION_LOOKBACK, now)
+            .all(&*DATABASE_CONNECTION)
+            .await
+            .into_diagnostic()?;
+
+        self.analyzed
+            .retain(|id, _| models.iter().any(|model| model.id == *id));
+
+        for model in models
+        {
+            let is_current = self
+                .analyzed
+                .get(&model.id)
+                .is_some_and(|(updated_at, _)| *updated_at == model.updated_at);
+
+            if is_current
+            {
+                continue;
+            }
+
+            let id = model.id;
+            let updated_at = model.updated_at;
+            let stored = Ingestion::from(model);
+            let mut ingestion = AnalyzeIngestion::from(stored.clone()).query().await?;
+
+            // Stored classification accounts for body weight at the time of
+            // ingestion, the same as the one shown by the CLI.
+            ingestion.dosage_classification = stored.dosage_classification;
+            ingestion.dosage_classification_max = stored.dosage_classification_max;
+
+            // Phases stored when ingestion was logged are preferred over the
+            // ones computed from current substance information.
+            let phases: Vec<IngestionPhase> = ingestion_phase::Entity::find()
+                .filter(ingestion_phase::Column::IngestionId.eq(id))
+                .all(&*DATABASE_CONNECTION)
+                .await
+                .into_diagnostic()?
+                .into_iter()
+                .map(IngestionPhase::from)
+                .collect();
+
+            if !phases.is_empty()
+            {
+                ingestion.phases = phases;
+            }
+
+            self.analyzed.insert(id, (updated_at, ingestion));
+        }
+
+        let mut active_ingestions: Vec<ActiveIngestion> = self
+            .analyzed
+            .values()
+            .filter_map(|(_, ingestion)| ActiveIngestion::new(ingestion, now))
+            .collect();
+        active_ingestions.sort_by_key(|active| active.ingestion.ingestion_date);
+
+        self.active_ingestions = active_ingestions;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::ingestion::IngestionPhase;
+    use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+    use crate::substance::route_of_administration::dosage::Dosage;
+    use std::range::Range;
+
+    fn phase(class: PhaseClassification, start: DateTime<Local>, end: DateTime<Local>)
+    -> IngestionPhase
+    {
+        IngestionPhase {
+            id: None,
+            class,
+            start_time: Range::from(start..start),
+            end_time: Range::from(end..end),
+            duration: Range::from((end - start)..(end - start)),
+        }
+    }
+
+    #[test]
+    fn test_active_ingestion()
+    {
+        let ingested_at = Local::now() - Duration::hours(1);
+        let ingestion = Ingestion {
+            id: Some(1),
+            substance_name: "caffeine".to_string(),
+            dosage: Dosage::from_miligrams(100.0).into(),
+            route: RouteOfAdministrationClassification::Oral,
+            ingestion_date: ingested_at,
+            dosage_classification: None,
+            dosage_classification_max: None,
+            amount: None,
+            body_weight: None,
+            bioavailability: None,
+            substance: None,
+            phases: vec![
+                phase(PhaseClassification::Onset, ingested_at, ingested_at + Duration::minutes(30)),
+                phase(
+                    PhaseClassification::Peak,
+                    ingested_at + Duration::minutes(30),
+                    ingested_at + Duration::hours(2),
+                ),
+            ],
+        };
+
+        let now = ingested_at + Duration::hours(1);
+        let active = ActiveIngestion::new(&ingestion, now).unwrap();
+
+        assert_eq!(active.current_phase, Some(PhaseClassification::Peak));
+        assert_eq!(active.phase_remaining, Some(Duration::hours(1)));
+        assert_eq!(active.remaining, Duration::hours(1));
+        assert!((active.progress - 0.5).abs() < f64::EPSILON);
+
+        assert!(ActiveIngestion::new(&ingestion, ingested_at + Duration::hours(3)).is_none());
+    }
 }
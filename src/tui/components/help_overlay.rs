@@ -0,0 +1,84 @@
+use crate::tui::keymap::Action;
+use crate::tui::keymap::Keymap;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::log_ingestion_form::centered_rect;
+
+/// Bindings which don't depend on the keymap as they're used while typing.
+const INPUT_BINDINGS: [(&str, &str); 4] = [
+    ("enter", "Confirm input or edit setting"),
+    ("esc", "Cancel input"),
+    ("tab", "Accept suggestion in form"),
+    ("space", "Toggle setting"),
+];
+
+/// Modal listing key bindings of the current keymap.
+pub struct HelpOverlay<'a>
+{
+    keymap: &'a Keymap,
+}
+
+impl<'a> HelpOverlay<'a>
+{
+    pub fn new(keymap: &'a Keymap) -> Self { Self { keymap } }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect)
+    {
+        let key_style = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+
+        let bindings = Action::ORDER.iter().map(|action| {
+            let keys: Vec<String> = self
+                .keymap
+                .keys(*action)
+                .iter()
+                .map(|key| key.to_string())
+                .collect();
+
+            (keys.join(", "), action.description())
+        });
+
+        let mut lines: Vec<Line> = bindings
+            .map(|(keys, description)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<20}", keys), key_style),
+                    Span::raw(description),
+                ])
+            })
+            .collect();
+
+        lines.push(Line::from(""));
+        lines.extend(INPUT_BINDINGS.iter().map(|(key, description)| {
+            Line::from(vec![
+                Span::styled(format!("{:<20}", key), key_style),
+                Span::raw(*description),
+            ])
+        }));
+        lines.push(Line::from(""));
+        lines.push(Line::styled(
+            "Mouse selects tabs and list rows, scroll moves selection",
+            Style::default().fg(Color::DarkGray),
+        ));
+
+        let area = centered_rect(60, lines.len() as u16 + 2, area);
+        let help = Paragraph::new(lines).block(
+            Block::default()
+                .title("Key bindings (any key to close)")
+                .borders(Borders::ALL),
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(help, area);
+    }
+}
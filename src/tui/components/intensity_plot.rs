@@ -1,43 +1,114 @@
-use crate::database::Ingestion;
+use crate::ingestion::Ingestion;
+use crate::ingestion::IngestionPhase;
+use crate::substance::route_of_administration::phase::PhaseClassification;
+use crate::core::config::Config;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+use chrono::DateTime;
+use chrono::Local;
 use ratatui::style::Color;
 use ratatui::style::Style;
+use ratatui::symbols::Marker;
+use ratatui::text::Span;
 use ratatui::widgets::Axis;
 use ratatui::widgets::Block;
 use ratatui::widgets::Borders;
 use ratatui::widgets::Chart;
+use ratatui::widgets::Dataset;
+use ratatui::widgets::GraphType;
+
+const COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
 
 /// A component that renders intensity plots for multiple substance ingestions
 pub struct IntensityPlot<'a>
 {
     ingestions: &'a [Ingestion],
+    /// Points of intensity curve (minutes since the earliest ingestion,
+    /// intensity in percents) for every ingestion.
     phase_data: Vec<Vec<(f64, f64)>>,
+    /// Vertical line marking current time.
+    now_marker: [(f64, f64); 2],
+    origin: DateTime<Local>,
     max_duration: f64,
 }
 
 impl<'a> IntensityPlot<'a>
 {
     /// Creates a new intensity plot for multiple ingestions
-    pub fn new(ingestions: &'a [Ingestion]) -> Self
+    pub fn new(ingestions: &'a [Ingestion], now: DateTime<Local>) -> Self
     {
+        let origin = ingestions
+            .iter()
+            .map(|ingestion| ingestion.ingestion_date)
+            .min()
+            .unwrap_or(now);
+
+        let phase_data: Vec<Vec<(f64, f64)>> = ingestions
+            .iter()
+            .map(|ingestion| intensity_curve(ingestion, origin))
+            .collect();
+
+        let now_minutes = minutes_between(origin, now);
+        let max_duration = phase_data
+            .iter()
+            .flatten()
+            .map(|(minutes, _)| *minutes)
+            .fold(now_minutes, f64::max);
+
         Self {
             ingestions,
-            phase_data: vec![],
-            max_duration: 0.0,
+            phase_data,
+            now_marker: [(now_minutes, 0.0), (now_minutes, 100.0)],
+            origin,
+            max_duration,
         }
     }
 
     /// Renders the intensity plot as a Chart widget
     pub fn render(&'a self) -> Chart<'a>
     {
-        let datasets = Vec::new();
-        let _colors = [
-            Color::Red,
-            Color::Green,
-            Color::Yellow,
-            Color::Blue,
-            Color::Magenta,
-            Color::Cyan,
-        ];
+        let mut datasets: Vec<Dataset> = self
+            .ingestions
+            .iter()
+            .zip(self.phase_data.iter())
+            .enumerate()
+            .map(|(index, (ingestion, points))| {
+                Dataset::default()
+                    .name(format!(
+                        "{} {}",
+                        ingestion.substance_name,
+                        ingestion.dosage.display_in(Config::current().units.mass)
+                    ))
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(COLORS[index % COLORS.len()]))
+                    .data(points)
+            })
+            .collect();
+
+        datasets.push(
+            Dataset::default()
+                .name("now")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::White))
+                .data(&self.now_marker),
+        );
+
+        let max_duration = self.max_duration.max(60.0);
+        let time_label = |minutes: f64| {
+            Span::raw(
+                (self.origin + chrono::Duration::minutes(minutes as i64))
+                    .format("%H:%M")
+                    .to_string(),
+            )
+        };
 
         Chart::new(datasets)
             .block(
@@ -47,15 +118,122 @@ impl<'a> IntensityPlot<'a>
             )
             .x_axis(
                 Axis::default()
-                    .title("Time (minutes)")
+                    .title("Time")
                     .style(Style::default().fg(Color::Gray))
-                    .bounds([0.0, self.max_duration.max(60.0)]),
+                    .bounds([0.0, max_duration])
+                    .labels([
+                        time_label(0.0),
+                        time_label(max_duration / 2.0),
+                        time_label(max_duration),
+                    ]),
             )
             .y_axis(
                 Axis::default()
                     .title("Intensity (%)")
                     .style(Style::default().fg(Color::Gray))
-                    .bounds([0.0, 100.0]),
+                    .bounds([0.0, 100.0])
+                    .labels(["0", "50", "100"]),
             )
     }
 }
+
+fn minutes_between(from: DateTime<Local>, to: DateTime<Local>) -> f64
+{
+    (to - from).num_seconds() as f64 / 60.0
+}
+
+/// Intensity of effects (in percents) at the end of given phase, effects rise
+/// during onset and comeup, are the strongest at peak and fade during
+/// comedown and afterglow.
+fn intensity_after(phase: PhaseClassification) -> f64
+{
+    match phase
+    {
+        | PhaseClassification::Onset => 10.0,
+        | PhaseClassification::Comeup | PhaseClassification::Peak => 100.0,
+        | PhaseClassification::Comedown => 20.0,
+        | PhaseClassification::Afterglow | PhaseClassification::Unknown => 0.0,
+    }
+}
+
+/// Intensity curve of a single ingestion built from its phases, every phase
+/// contributes a point at its end so the curve is linear within a phase.
+fn intensity_curve(ingestion: &Ingestion, origin: DateTime<Local>) -> Vec<(f64, f64)>
+{
+    let mut phases: Vec<&IngestionPhase> = ingestion
+        .phases
+        .iter()
+        .filter(|phase| phase.class != PhaseClassification::Unknown)
+        .collect();
+    phases.sort_by_key(|phase| phase.start_time.start);
+
+    let Some(first) = phases.first()
+    else
+    {
+        return vec![];
+    };
+
+    let mut points = vec![(minutes_between(origin, first.start_time.start), 0.0)];
+
+    for phase in phases
+    {
+        // Phase ends where the next one starts, which is the lower bound of
+        // its end time.
+        points.push((
+            minutes_between(origin, phase.end_time.start),
+            intensity_after(phase.class),
+        ));
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+    use crate::substance::route_of_administration::dosage::Dosage;
+    use chrono::Duration;
+    use std::range::Range;
+
+    fn phase(class: PhaseClassification, start: DateTime<Local>, minutes: i64) -> IngestionPhase
+    {
+        let end = start + Duration::minutes(minutes);
+
+        IngestionPhase {
+            id: None,
+            class,
+            start_time: Range::from(start..start),
+            end_time: Range::from(end..end),
+            duration: Range::from(Duration::minutes(minutes)..Duration::minutes(minutes)),
+        }
+    }
+
+    #[test]
+    fn test_intensity_curve()
+    {
+        let start = Local::now();
+        let ingestion = Ingestion {
+            id: Some(1),
+            substance_name: "caffeine".to_string(),
+            dosage: Dosage::from_miligrams(100.0).into(),
+            route: RouteOfAdministrationClassification::Oral,
+            ingestion_date: start,
+            dosage_classification: None,
+            dosage_classification_max: None,
+            amount: None,
+            body_weight: None,
+            bioavailability: None,
+            substance: None,
+            phases: vec![
+                phase(PhaseClassification::Peak, start + Duration::minutes(30), 60),
+                phase(PhaseClassification::Onset, start, 30),
+            ],
+        };
+
+        let curve = intensity_curve(&ingestion, start - Duration::minutes(10));
+
+        assert_eq!(curve, vec![(10.0, 0.0), (40.0, 10.0), (100.0, 100.0)]);
+    }
+}
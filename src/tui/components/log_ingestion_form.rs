@@ -0,0 +1,499 @@
+use crate::ingestion::LogIngestion;
+use crate::core::config::Config;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::route_of_administration::dosage::Amount;
+use crate::substance::route_of_administration::dosage::Estimate;
+use crate::utils::parse_date_string;
+use clap::ValueEnum;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Wrap;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Maximum amount of substance names suggested below the substance input.
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormField
+{
+    Substance,
+    Dosage,
+    Route,
+    Date,
+}
+
+impl FormField
+{
+    const ORDER: [FormField; 4] = [
+        FormField::Substance,
+        FormField::Dosage,
+        FormField::Route,
+        FormField::Date,
+    ];
+
+    fn label(&self) -> &'static str
+    {
+        match self
+        {
+            | FormField::Substance => "Substance",
+            | FormField::Dosage => "Dosage",
+            | FormField::Route => "Route",
+            | FormField::Date => "Time",
+        }
+    }
+
+    fn next(&self) -> Self
+    {
+        let index = Self::ORDER.iter().position(|f| f == self).unwrap_or(0);
+        Self::ORDER[(index + 1) % Self::ORDER.len()]
+    }
+
+    fn previous(&self) -> Self
+    {
+        let index = Self::ORDER.iter().position(|f| f == self).unwrap_or(0);
+        Self::ORDER[(index + Self::ORDER.len() - 1) % Self::ORDER.len()]
+    }
+}
+
+/// Outcome of a key press handled by the form.
+#[derive(Debug)]
+pub enum FormAction
+{
+    None,
+    Cancel,
+    Submit(LogIngestion),
+}
+
+/// Modal form used to log a new ingestion from the dashboard.
+#[derive(Debug)]
+pub struct LogIngestionForm
+{
+    pub substance: String,
+    pub dosage: String,
+    pub route: RouteOfAdministrationClassification,
+    pub date: String,
+    pub focused: FormField,
+    /// Names of substances known to the application, used for autocomplete.
+    substances: Vec<String>,
+    selected_suggestion: Option<usize>,
+    /// Validation errors displayed below the invalid fields.
+    pub errors: HashMap<FormField, String>,
+    /// Error which isn't related to any field, e.g. failure to save ingestion.
+    pub error: Option<String>,
+}
+
+impl LogIngestionForm
+{
+    pub fn new(substances: Vec<String>) -> Self
+    {
+        Self {
+            substance: String::new(),
+            dosage: String::new(),
+            route: Config::current().default_route,
+            date: "now".to_string(),
+            focused: FormField::Substance,
+            substances,
+            selected_suggestion: None,
+            errors: HashMap::new(),
+            error: None,
+        }
+    }
+
+    /// Substance names matching current input, names starting with the input
+    /// are listed before names which only contain it.
+    pub fn suggestions(&self) -> Vec<&str>
+    {
+        let input = self.substance.trim().to_lowercase();
+
+        if input.is_empty()
+        {
+            return vec![];
+        }
+
+        let (mut prefixed, contained): (Vec<&str>, Vec<&str>) = self
+            .substances
+            .iter()
+            .map(String::as_str)
+            .filter(|name| name.to_lowercase().contains(&input) && name.to_lowercase() != input)
+            .partition(|name| name.to_lowercase().starts_with(&input));
+
+        prefixed.extend(contained);
+        prefixed.truncate(MAX_SUGGESTIONS);
+        prefixed
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> FormAction
+    {
+        match key.code
+        {
+            | KeyCode::Esc => return FormAction::Cancel,
+            | KeyCode::Enter =>
+            {
+                if self.accept_suggestion()
+                {
+                    return FormAction::None;
+                }
+
+                return match self.validate()
+                {
+                    | Some(command) => FormAction::Submit(command),
+                    | None => FormAction::None,
+                };
+            }
+            | KeyCode::Tab =>
+            {
+                self.accept_suggestion();
+                self.focused = self.focused.next();
+            }
+            | KeyCode::BackTab => self.focused = self.focused.previous(),
+            | KeyCode::Down if self.focused == FormField::Substance =>
+            {
+                let suggestions = self.suggestions().len();
+                self.selected_suggestion = match self.selected_suggestion
+                {
+                    | _ if suggestions == 0 =>
+                    {
+                        self.focused = self.focused.next();
+                        None
+                    }
+                    | None => Some(0),
+                    | Some(index) => Some((index + 1).min(suggestions - 1)),
+                };
+            }
+            | KeyCode::Up if self.focused == FormField::Substance =>
+            {
+                self.selected_suggestion = self
+                    .selected_suggestion
+                    .and_then(|index| index.checked_sub(1));
+            }
+            | KeyCode::Down => self.focused = self.focused.next(),
+            | KeyCode::Up => self.focused = self.focused.previous(),
+            | KeyCode::Left if self.focused == FormField::Route => self.cycle_route(-1),
+            | KeyCode::Right if self.focused == FormField::Route => self.cycle_route(1),
+            | KeyCode::Backspace =>
+            {
+                if let Some(input) = self.focused_input()
+                {
+                    input.pop();
+                    self.on_input_changed();
+                }
+            }
+            | KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                if let Some(input) = self.focused_input()
+                {
+                    input.push(c);
+                    self.on_input_changed();
+                }
+            }
+            | _ =>
+            {}
+        }
+
+        FormAction::None
+    }
+
+    /// Insert pasted text into the focused input, line breaks are dropped as
+    /// inputs are single line.
+    pub fn paste(&mut self, text: &str)
+    {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+
+        if let Some(input) = self.focused_input()
+        {
+            input.push_str(&text);
+            self.on_input_changed();
+        }
+    }
+
+    /// Validate all fields and build a command which logs the ingestion,
+    /// errors are stored in the form and `None` is returned if any field is
+    /// invalid.
+    pub fn validate(&mut self) -> Option<LogIngestion>
+    {
+        self.errors.clear();
+        self.error = None;
+
+        let substance_name = self.substance.trim().to_string();
+        if substance_name.is_empty()
+        {
+            self.errors
+                .insert(FormField::Substance, "Substance name is required".to_string());
+        }
+
+        let dosage = match self.dosage.trim()
+        {
+            | "" =>
+            {
+                self.errors
+                    .insert(FormField::Dosage, "Dosage is required".to_string());
+                None
+            }
+            | dosage => Estimate::<Amount>::from_str(dosage)
+                .map_err(|error| self.errors.insert(FormField::Dosage, error))
+                .ok(),
+        };
+
+        let ingestion_date = parse_date_string(self.date.trim())
+            .map_err(|error| {
+                self.errors
+                    .insert(FormField::Date, format!("Invalid time: {}", error))
+            })
+            .ok();
+
+        match (dosage, ingestion_date)
+        {
+            | (Some(dosage), Some(ingestion_date)) if self.errors.is_empty() => Some(LogIngestion {
+                substance_name,
+                dosage,
+                ingestion_date,
+                route_of_administration: self.route,
+                solution: None,
+            }),
+            | _ => None,
+        }
+    }
+
+    fn focused_input(&mut self) -> Option<&mut String>
+    {
+        match self.focused
+        {
+            | FormField::Substance => Some(&mut self.substance),
+            | FormField::Dosage => Some(&mut self.dosage),
+            | FormField::Date => Some(&mut self.date),
+            | FormField::Route => None,
+        }
+    }
+
+    fn on_input_changed(&mut self)
+    {
+        self.errors.remove(&self.focused);
+        self.selected_suggestion = None;
+    }
+
+    /// Replace substance input with highlighted suggestion, returns whether
+    /// any suggestion was accepted.
+    fn accept_suggestion(&mut self) -> bool
+    {
+        if self.focused != FormField::Substance
+        {
+            return false;
+        }
+
+        let suggestion = self
+            .selected_suggestion
+            .and_then(|index| self.suggestions().get(index).map(|s| s.to_string()));
+
+        match suggestion
+        {
+            | Some(suggestion) =>
+            {
+                self.substance = suggestion;
+                self.on_input_changed();
+                true
+            }
+            | None => false,
+        }
+    }
+
+    fn cycle_route(&mut self, step: isize)
+    {
+        let routes = RouteOfAdministrationClassification::value_variants();
+        let index = routes.iter().position(|r| *r == self.route).unwrap_or(0) as isize;
+        let next = (index + step).rem_euclid(routes.len() as isize) as usize;
+        self.route = routes[next];
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect)
+    {
+        let area = centered_rect(60, 18, area);
+        let mut lines = Vec::new();
+
+        for field in FormField::ORDER
+        {
+            let is_focused = self.focused == field;
+            let value = match field
+            {
+                | FormField::Substance => self.substance.clone(),
+                | FormField::Dosage => self.dosage.clone(),
+                | FormField::Route => format!("< {} >", self.route),
+                | FormField::Date => self.date.clone(),
+            };
+            let cursor = if is_focused && field != FormField::Route { "▏" } else { "" };
+            let label_style = match is_focused
+            {
+                | true => Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+                | false => Style::default().fg(Color::Gray),
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<10} ", field.label()), label_style),
+                Span::raw(value),
+                Span::styled(cursor, Style::default().fg(Color::Yellow)),
+            ]));
+
+            if field == FormField::Substance && is_focused
+            {
+                for (index, suggestion) in self.suggestions().into_iter().enumerate()
+                {
+                    let style = match self.selected_suggestion == Some(index)
+                    {
+                        | true => Style::default().fg(Color::Black).bg(Color::Yellow),
+                        | false => Style::default().fg(Color::DarkGray),
+                    };
+                    lines.push(Line::from(vec![
+                        Span::raw(" ".repeat(11)),
+                        Span::styled(suggestion.to_string(), style),
+                    ]));
+                }
+            }
+
+            if let Some(error) = self.errors.get(&field)
+            {
+                lines.push(Line::from(vec![
+                    Span::raw(" ".repeat(11)),
+                    Span::styled(error.clone(), Style::default().fg(Color::Red)),
+                ]));
+            }
+        }
+
+        lines.push(Line::default());
+
+        if let Some(error) = &self.error
+        {
+            lines.push(Line::styled(error.clone(), Style::default().fg(Color::Red)));
+        }
+
+        lines.push(Line::styled(
+            "Enter log | Tab next field | ←/→ change route | Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ));
+
+        let form = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title("Log Ingestion")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow)),
+            );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(form, area);
+    }
+}
+
+/// Area of given width (in percents of the parent) and height (in lines)
+/// placed in the middle of the parent area.
+pub fn centered_rect(width_percent: u16, height: u16, area: Rect) -> Rect
+{
+    let width = (area.width as u32 * width_percent as u32 / 100) as u16;
+    let height = height.min(area.height);
+
+    Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn form() -> LogIngestionForm
+    {
+        LogIngestionForm::new(vec![
+            "caffeine".to_string(),
+            "l-theanine".to_string(),
+            "theacrine".to_string(),
+        ])
+    }
+
+    fn type_text(form: &mut LogIngestionForm, text: &str)
+    {
+        for c in text.chars()
+        {
+            form.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_suggestions()
+    {
+        let mut form = form();
+        type_text(&mut form, "the");
+
+        assert_eq!(form.suggestions(), vec!["theacrine", "l-theanine"]);
+
+        form.handle_key(KeyEvent::from(KeyCode::Down));
+        form.handle_key(KeyEvent::from(KeyCode::Tab));
+
+        assert_eq!(form.substance, "theacrine");
+        assert_eq!(form.focused, FormField::Dosage);
+    }
+
+    #[test]
+    fn test_validation_errors()
+    {
+        let mut form = form();
+        form.date = "not a date".to_string();
+        form.dosage = "lots".to_string();
+
+        assert!(form.validate().is_none());
+        assert!(form.errors.contains_key(&FormField::Substance));
+        assert!(form.errors.contains_key(&FormField::Dosage));
+        assert!(form.errors.contains_key(&FormField::Date));
+    }
+
+    #[test]
+    fn test_paste()
+    {
+        let mut form = form();
+        form.paste("caffeine\n");
+        form.focused = FormField::Route;
+        form.paste("ignored");
+
+        assert_eq!(form.substance, "caffeine");
+    }
+
+    #[test]
+    fn test_valid_form()
+    {
+        let mut form = form();
+        type_text(&mut form, "caffeine");
+        form.focused = FormField::Dosage;
+        type_text(&mut form, "100mg");
+        form.focused = FormField::Route;
+        form.handle_key(KeyEvent::from(KeyCode::Right));
+
+        let command = form.validate().unwrap();
+
+        assert_eq!(command.substance_name, "caffeine");
+        assert_eq!(command.dosage.to_string(), "100 mg");
+
+        form.dosage = "~2 cups".to_string();
+        assert_eq!(form.validate().unwrap().dosage.to_string(), "~2 cups");
+        assert_eq!(
+            command.route_of_administration,
+            RouteOfAdministrationClassification::Rectal
+        );
+    }
+}
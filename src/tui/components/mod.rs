@@ -1 +1,3 @@
+pub mod help_overlay;
 pub mod intensity_plot;
+pub mod log_ingestion_form;
@@ -0,0 +1,396 @@
+use crate::core::config::CONFIG_DIR;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use miette::IntoDiagnostic;
+use miette::WrapErr;
+use miette::miette;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Actions which can be bound to keys, names of variants in snake case are
+/// used as keys of the keymap file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action
+{
+    Quit,
+    Help,
+    LogIngestion,
+    Search,
+    NextTab,
+    PreviousTab,
+    HomeTab,
+    SubstancesTab,
+    StatsTab,
+    SettingsTab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Action
+{
+    /// Actions in order in which they're listed in help.
+    pub const ORDER: [Action; 16] = [
+        Action::Help,
+        Action::Quit,
+        Action::LogIngestion,
+        Action::Search,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::HomeTab,
+        Action::SubstancesTab,
+        Action::StatsTab,
+        Action::SettingsTab,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Top,
+        Action::Bottom,
+    ];
+
+    pub fn description(&self) -> &'static str
+    {
+        match self
+        {
+            | Action::Quit => "Quit",
+            | Action::Help => "Toggle this help",
+            | Action::LogIngestion => "Log ingestion",
+            | Action::Search => "Search substances",
+            | Action::NextTab => "Next tab",
+            | Action::PreviousTab => "Previous tab",
+            | Action::HomeTab => "Go to Home",
+            | Action::SubstancesTab => "Go to Substances",
+            | Action::StatsTab => "Go to Stats",
+            | Action::SettingsTab => "Go to Settings",
+            | Action::Up => "Move up",
+            | Action::Down => "Move down",
+            | Action::Left => "Previous option",
+            | Action::Right => "Next option",
+            | Action::Top => "Go to first item",
+            | Action::Bottom => "Go to last item",
+        }
+    }
+
+    /// Key understood by tabs which is equivalent to the action, tabs handle
+    /// arrow keys only so they don't have to know about the keymap.
+    pub fn as_key(&self) -> Option<KeyEvent>
+    {
+        let code = match self
+        {
+            | Action::Up => KeyCode::Up,
+            | Action::Down => KeyCode::Down,
+            | Action::Left => KeyCode::Left,
+            | Action::Right => KeyCode::Right,
+            | Action::Top => KeyCode::Home,
+            | Action::Bottom => KeyCode::End,
+            | Action::Search => KeyCode::Char('/'),
+            | _ => return None,
+        };
+
+        Some(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+}
+
+/// Key together with modifiers, written as e.g. `ctrl-c`, `G` or `pageup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding
+{
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl From<KeyEvent> for KeyBinding
+{
+    fn from(key: KeyEvent) -> Self
+    {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+        // Shift is already a part of typed character.
+        if !matches!(key.code, KeyCode::Char(_))
+        {
+            modifiers |= key.modifiers & KeyModifiers::SHIFT;
+        }
+
+        Self {
+            code: key.code,
+            modifiers,
+        }
+    }
+}
+
+impl FromStr for KeyBinding
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = s;
+
+        // A single character is a key even when it's a dash.
+        while key.chars().count() > 1
+        {
+            let Some((modifier, rest)) = key.split_once('-')
+            else
+            {
+                break;
+            };
+
+            modifiers |= match modifier.to_lowercase().as_str()
+            {
+                | "ctrl" => KeyModifiers::CONTROL,
+                | "alt" => KeyModifiers::ALT,
+                | "shift" => KeyModifiers::SHIFT,
+                | _ => return Err(format!("Unknown modifier \"{}\" in key \"{}\"", modifier, s)),
+            };
+            key = rest;
+        }
+
+        let code = match key.to_lowercase().as_str()
+        {
+            | _ if key.chars().count() == 1 => KeyCode::Char(key.chars().next().unwrap()),
+            | "up" => KeyCode::Up,
+            | "down" => KeyCode::Down,
+            | "left" => KeyCode::Left,
+            | "right" => KeyCode::Right,
+            | "home" => KeyCode::Home,
+            | "end" => KeyCode::End,
+            | "pageup" => KeyCode::PageUp,
+            | "pagedown" => KeyCode::PageDown,
+            | "enter" => KeyCode::Enter,
+            | "esc" => KeyCode::Esc,
+            | "tab" => KeyCode::Tab,
+            | "backtab" => KeyCode::BackTab,
+            | "backspace" => KeyCode::Backspace,
+            | "space" => KeyCode::Char(' '),
+            | _ => return Err(format!("Unknown key \"{}\"", s)),
+        };
+
+        // Shifted characters are matched by the character itself.
+        if let KeyCode::Char(_) = code
+        {
+            modifiers -= KeyModifiers::SHIFT;
+        }
+
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl fmt::Display for KeyBinding
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if self.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            write!(f, "ctrl-")?;
+        }
+
+        if self.modifiers.contains(KeyModifiers::ALT)
+        {
+            write!(f, "alt-")?;
+        }
+
+        if self.modifiers.contains(KeyModifiers::SHIFT)
+        {
+            write!(f, "shift-")?;
+        }
+
+        match self.code
+        {
+            | KeyCode::Char(' ') => write!(f, "space"),
+            | KeyCode::Char(c) => write!(f, "{}", c),
+            | KeyCode::Up => write!(f, "up"),
+            | KeyCode::Down => write!(f, "down"),
+            | KeyCode::Left => write!(f, "left"),
+            | KeyCode::Right => write!(f, "right"),
+            | KeyCode::PageUp => write!(f, "pageup"),
+            | KeyCode::PageDown => write!(f, "pagedown"),
+            | KeyCode::BackTab => write!(f, "backtab"),
+            | code => write!(f, "{}", code.to_string().to_lowercase()),
+        }
+    }
+}
+
+/// Bindings of keys to actions, defaults can be overridden per action in
+/// `keymap.toml` placed in the configuration directory, e.g.
+///
+/// ```toml
+/// down = ["j", "down", "ctrl-n"]
+/// quit = ["ctrl-c"]
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keymap
+{
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Default for Keymap
+{
+    fn default() -> Self
+    {
+        let defaults: [(Action, &[&str]); 16] = [
+            (Action::Quit, &["q", "esc", "ctrl-c"]),
+            (Action::Help, &["?"]),
+            (Action::LogIngestion, &["n"]),
+            (Action::Search, &["/"]),
+            (Action::NextTab, &["tab"]),
+            (Action::PreviousTab, &["backtab"]),
+            (Action::HomeTab, &["1"]),
+            (Action::SubstancesTab, &["2"]),
+            (Action::StatsTab, &["3"]),
+            (Action::SettingsTab, &["4"]),
+            (Action::Up, &["up", "k"]),
+            (Action::Down, &["down", "j"]),
+            (Action::Left, &["left", "h"]),
+            (Action::Right, &["right", "l"]),
+            (Action::Top, &["home", "g"]),
+            (Action::Bottom, &["end", "G"]),
+        ];
+
+        let bindings = defaults
+            .iter()
+            .flat_map(|(action, keys)| {
+                keys.iter()
+                    .map(move |key| (key.parse().expect("invalid default key binding"), *action))
+            })
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl Keymap
+{
+    /// Location of the keymap file.
+    pub fn path() -> PathBuf { CONFIG_DIR.join("keymap.toml") }
+
+    /// Default keymap with bindings from the keymap file, if it exists.
+    pub fn load() -> miette::Result<Self>
+    {
+        let path = Self::path();
+        let mut keymap = Self::default();
+
+        if !path.exists()
+        {
+            return Ok(keymap);
+        }
+
+        let content = std::fs::read_to_string(&path).into_diagnostic()?;
+        keymap
+            .merge(&content)
+            .wrap_err(format!("Invalid keymap file {}", path.display()))?;
+
+        Ok(keymap)
+    }
+
+    /// Replace bindings of actions listed in TOML document.
+    fn merge(&mut self, content: &str) -> miette::Result<()>
+    {
+        let overrides: HashMap<Action, Vec<String>> = toml::from_str(content).into_diagnostic()?;
+
+        for (action, keys) in overrides
+        {
+            let keys: Vec<KeyBinding> = keys
+                .iter()
+                .map(|key| key.parse().map_err(|error: String| miette!(error)))
+                .collect::<miette::Result<_>>()?;
+
+            self.bindings.retain(|(key, a)| *a != action && !keys.contains(key));
+            self.bindings
+                .extend(keys.into_iter().map(|key| (key, action)));
+        }
+
+        Ok(())
+    }
+
+    pub fn action(&self, key: KeyEvent) -> Option<Action>
+    {
+        let key = KeyBinding::from(key);
+
+        self.bindings
+            .iter()
+            .find(|(binding, _)| *binding == key)
+            .map(|(_, action)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> Vec<KeyBinding>
+    {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent { KeyEvent::new(code, modifiers) }
+
+    #[test]
+    fn test_parse_key_binding()
+    {
+        let binding: KeyBinding = "ctrl-c".parse().unwrap();
+        assert_eq!(binding.code, KeyCode::Char('c'));
+        assert_eq!(binding.modifiers, KeyModifiers::CONTROL);
+
+        let binding: KeyBinding = "-".parse().unwrap();
+        assert_eq!(binding.code, KeyCode::Char('-'));
+
+        assert_eq!(binding.to_string(), "-");
+        assert_eq!("ctrl-pageup".parse::<KeyBinding>().unwrap().to_string(), "ctrl-pageup");
+        assert!("hyper-x".parse::<KeyBinding>().is_err());
+        assert!("f13x".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_default_keymap()
+    {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            keymap.action(key(KeyCode::Char('j'), KeyModifiers::NONE)),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            keymap.action(key(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            Some(Action::Bottom)
+        );
+        assert_eq!(
+            keymap.action(key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(keymap.action(key(KeyCode::Char('x'), KeyModifiers::NONE)), None);
+    }
+
+    #[test]
+    fn test_merge_keymap()
+    {
+        let mut keymap = Keymap::default();
+        keymap.merge("down = [\"ctrl-n\"]\nquit = [\"j\"]").unwrap();
+
+        assert_eq!(
+            keymap.action(key(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            keymap.action(key(KeyCode::Char('j'), KeyModifiers::NONE)),
+            Some(Action::Quit)
+        );
+        assert_eq!(keymap.action(key(KeyCode::Down, KeyModifiers::NONE)), None);
+        assert!(keymap.merge("unknown = [\"x\"]").is_err());
+        assert!(keymap.merge("down = [\"hyper-x\"]").is_err());
+    }
+}
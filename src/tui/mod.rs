@@ -18,6 +18,9 @@ use miette::IntoDiagnostic;
 use ratatui::prelude::*;
 
 mod app;
+mod components;
+mod keymap;
+mod tabs;
 mod ui;
 
 use app::App;
@@ -45,13 +48,25 @@ pub fn restore() -> miette::Result<()>
     Ok(())
 }
 
-pub fn run() -> miette::Result<()>
+/// Run the interactive dashboard until user quits it, terminal is restored
+/// even if the application loop fails.
+pub async fn run() -> miette::Result<()>
 {
     let mut terminal = init()?;
+    let result = run_app(&mut terminal).await;
+
+    restore()?;
+    result
+}
+
+async fn run_app(terminal: &mut Tui) -> miette::Result<()>
+{
     let mut app = App::new();
-    let tick_rate = Duration::from_millis(250);
+    let tick_rate = Duration::from_secs(1);
     let mut last_tick = Instant::now();
 
+    app.tick().await;
+
     while app.running
     {
         terminal
@@ -64,28 +79,27 @@ pub fn run() -> miette::Result<()>
 
         if event::poll(timeout).into_diagnostic()?
         {
-            if let Event::Key(key) = event::read().into_diagnostic()?
+            match event::read().into_diagnostic()?
             {
-                if key.kind == KeyEventKind::Press
+                | Event::Key(key) if key.kind == KeyEventKind::Press => app.on_key_event(key).await,
+                | Event::Mouse(mouse) =>
                 {
-                    match key.code
-                    {
-                        | KeyCode::Char(c) => app.on_key(c),
-                        | KeyCode::Esc => app.quit(),
-                        | _ =>
-                        {}
-                    }
+                    let size = terminal.size().into_diagnostic()?;
+                    app.on_mouse_event(mouse, Rect::new(0, 0, size.width, size.height))
+                        .await
                 }
+                | Event::Paste(text) => app.on_paste(&text),
+                | _ =>
+                {}
             }
         }
 
         if last_tick.elapsed() >= tick_rate
         {
-            app.tick();
+            app.tick().await;
             last_tick = Instant::now();
         }
     }
 
-    restore()?;
     Ok(())
 }
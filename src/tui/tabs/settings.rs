@@ -0,0 +1,398 @@
+use crate::cli::MessageFormat;
+use crate::core::config::Config;
+use crate::substance::route_of_administration::RouteOfAdministrationClassification;
+use crate::substance::route_of_administration::dosage::MassUnit;
+use chrono::NaiveTime;
+use clap::ValueEnum;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::layout::Direction;
+use ratatui::layout::Layout;
+use ratatui::layout::Position;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Paragraph;
+use std::path::PathBuf;
+
+use crate::tui::ui::list_index_at;
+
+const MASS_UNITS: [MassUnit; 4] = [
+    MassUnit::Auto,
+    MassUnit::Microgram,
+    MassUnit::Milligram,
+    MassUnit::Gram,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsField
+{
+    DatabasePath,
+    DefaultRoute,
+    OutputFormat,
+    Bedtime,
+    MassUnit,
+    Offline,
+}
+
+impl SettingsField
+{
+    const ORDER: [SettingsField; 6] = [
+        SettingsField::DatabasePath,
+        SettingsField::DefaultRoute,
+        SettingsField::OutputFormat,
+        SettingsField::Bedtime,
+        SettingsField::MassUnit,
+        SettingsField::Offline,
+    ];
+
+    fn label(&self) -> &'static str
+    {
+        match self
+        {
+            | SettingsField::DatabasePath => "Database path",
+            | SettingsField::DefaultRoute => "Default route",
+            | SettingsField::OutputFormat => "Output format",
+            | SettingsField::Bedtime => "Bedtime",
+            | SettingsField::MassUnit => "Mass unit",
+            | SettingsField::Offline => "Offline mode",
+        }
+    }
+
+    /// Whether value of the field is typed rather than chosen from options.
+    fn is_text(&self) -> bool
+    {
+        matches!(self, SettingsField::DatabasePath | SettingsField::Bedtime)
+    }
+}
+
+/// Editor of the configuration file, every confirmed change is validated and
+/// saved immediately so it takes effect in the running application.
+#[derive(Debug)]
+pub struct SettingsTab
+{
+    config: Config,
+    selected: usize,
+    /// Text of the field which is being edited, `None` when no field is.
+    pub editing: Option<String>,
+    error: Option<String>,
+}
+
+impl Default for SettingsTab
+{
+    fn default() -> Self
+    {
+        Self {
+            config: Config::current(),
+            selected: 0,
+            editing: None,
+            error: None,
+        }
+    }
+}
+
+impl SettingsTab
+{
+    fn selected_field(&self) -> SettingsField { SettingsField::ORDER[self.selected] }
+
+    pub fn handle_key(&mut self, key: KeyEvent)
+    {
+        if self.editing.is_some()
+        {
+            return self.handle_edit_key(key);
+        }
+
+        let field = self.selected_field();
+
+        match key.code
+        {
+            | KeyCode::Down =>
+            {
+                self.selected = (self.selected + 1).min(SettingsField::ORDER.len() - 1)
+            }
+            | KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            | KeyCode::Enter if field.is_text() =>
+            {
+                self.error = None;
+                self.editing = Some(self.value(field));
+            }
+            | KeyCode::Left => self.cycle(field, -1),
+            | KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => self.cycle(field, 1),
+            | _ =>
+            {}
+        }
+    }
+
+    fn handle_edit_key(&mut self, key: KeyEvent)
+    {
+        let Some(text) = self.editing.as_mut()
+        else
+        {
+            return;
+        };
+
+        match key.code
+        {
+            | KeyCode::Esc => self.editing = None,
+            | KeyCode::Backspace =>
+            {
+                text.pop();
+            }
+            | KeyCode::Char(c) => text.push(c),
+            | KeyCode::Enter =>
+            {
+                let text = text.clone();
+                let field = self.selected_field();
+                let mut config = self.config.clone();
+
+                let result = match field
+                {
+                    | SettingsField::DatabasePath =>
+                    {
+                        config.sqlite_path = PathBuf::from(text.trim());
+                        Ok(())
+                    }
+                    | _ => parse_bedtime(&text).map(|bedtime| config.bedtime = bedtime),
+                };
+
+                match result
+                {
+                    | Ok(()) if self.apply(config) => self.editing = None,
+                    | Ok(()) =>
+                    {}
+                    | Err(error) => self.error = Some(error),
+                }
+            }
+            | _ =>
+            {}
+        }
+    }
+
+    /// Insert pasted text into the field which is being edited.
+    pub fn paste(&mut self, text: &str)
+    {
+        if let Some(editing) = self.editing.as_mut()
+        {
+            editing.extend(text.chars().filter(|c| !c.is_control()));
+        }
+    }
+
+    /// Select clicked field, fields can't be changed while one is edited.
+    pub fn handle_click(&mut self, area: Rect, position: Position)
+    {
+        let fields = layout(area)[0];
+
+        if self.editing.is_some()
+        {
+            return;
+        }
+
+        match list_index_at(fields, 0, position)
+        {
+            | Some(index) if index < SettingsField::ORDER.len() => self.selected = index,
+            | _ =>
+            {}
+        }
+    }
+
+    fn cycle(&mut self, field: SettingsField, step: isize)
+    {
+        let mut config = self.config.clone();
+
+        match field
+        {
+            | SettingsField::DefaultRoute =>
+            {
+                config.default_route = cycle(
+                    RouteOfAdministrationClassification::value_variants(),
+                    &config.default_route,
+                    step,
+                )
+            }
+            | SettingsField::OutputFormat =>
+            {
+                // `None` (choose automatically) precedes every format.
+                let formats: Vec<Option<MessageFormat>> = std::iter::once(None)
+                    .chain(MessageFormat::value_variants().iter().cloned().map(Some))
+                    .collect();
+                config.output_format = cycle(&formats, &config.output_format, step);
+            }
+            | SettingsField::MassUnit =>
+            {
+                config.units.mass = cycle(&MASS_UNITS, &config.units.mass, step)
+            }
+            | SettingsField::Offline => config.offline = !config.offline,
+            | SettingsField::DatabasePath | SettingsField::Bedtime => return,
+        }
+
+        self.apply(config);
+    }
+
+    /// Persist configuration and make it current, returns whether it was
+    /// valid.
+    fn apply(&mut self, config: Config) -> bool
+    {
+        match config.clone().apply()
+        {
+            | Ok(()) =>
+            {
+                self.config = config;
+                self.error = None;
+                true
+            }
+            | Err(error) =>
+            {
+                self.error = Some(error.to_string());
+                false
+            }
+        }
+    }
+
+    fn value(&self, field: SettingsField) -> String
+    {
+        match field
+        {
+            | SettingsField::DatabasePath => self.config.sqlite_path.display().to_string(),
+            | SettingsField::DefaultRoute => self.config.default_route.to_string(),
+            | SettingsField::OutputFormat => self
+                .config
+                .output_format
+                .as_ref()
+                .map(|format| format!("{:?}", format).to_lowercase())
+                .unwrap_or_else(|| "auto".to_string()),
+            | SettingsField::Bedtime => self
+                .config
+                .bedtime
+                .map(|bedtime| bedtime.format("%H:%M").to_string())
+                .unwrap_or_default(),
+            | SettingsField::MassUnit => format!("{:?}", self.config.units.mass).to_lowercase(),
+            | SettingsField::Offline => match self.config.offline
+            {
+                | true => "on".to_string(),
+                | false => "off".to_string(),
+            },
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect)
+    {
+        let rows = layout(area);
+
+        let lines: Vec<Line> = SettingsField::ORDER
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let selected = index == self.selected;
+                let value = match (&self.editing, selected)
+                {
+                    | (Some(text), true) => format!("{}█", text),
+                    | _ if field.is_text() => self.value(*field),
+                    | _ => format!("◀ {} ▶", self.value(*field)),
+                };
+
+                let label_style = match selected
+                {
+                    | true => Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                    | false => Style::default(),
+                };
+
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} {:<16}", if selected { ">" } else { " " }, field.label()),
+                        label_style,
+                    ),
+                    Span::raw(value),
+                ])
+            })
+            .collect();
+
+        let settings = Paragraph::new(lines).block(
+            Block::default()
+                .title(format!("Settings | {}", Config::path().display()))
+                .borders(Borders::ALL),
+        );
+
+        frame.render_widget(settings, rows[0]);
+
+        let help = match (&self.error, self.selected_field())
+        {
+            | (Some(error), _) => Line::styled(error.clone(), Style::default().fg(Color::Red)),
+            | (None, SettingsField::DatabasePath) => Line::styled(
+                "Enter to edit, database path is used after restart",
+                Style::default().fg(Color::DarkGray),
+            ),
+            | (None, SettingsField::Bedtime) => Line::styled(
+                "Enter to edit, time as HH:MM or empty to disable",
+                Style::default().fg(Color::DarkGray),
+            ),
+            | (None, _) => Line::styled(
+                "←/→ or Space to change",
+                Style::default().fg(Color::DarkGray),
+            ),
+        };
+
+        frame.render_widget(
+            Paragraph::new(help).block(Block::default().borders(Borders::ALL)),
+            rows[1],
+        );
+    }
+}
+
+/// Areas of list of settings and of help for the selected one.
+fn layout(area: Rect) -> [Rect; 2]
+{
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(8), Constraint::Length(4)])
+        .split(area);
+
+    [rows[0], rows[1]]
+}
+
+fn cycle<T: Clone + PartialEq>(values: &[T], current: &T, step: isize) -> T
+{
+    let index = values.iter().position(|v| v == current).unwrap_or(0) as isize;
+    values[(index + step).rem_euclid(values.len() as isize) as usize].clone()
+}
+
+/// Parse bedtime written as `HH:MM`, empty text disables bedtime.
+fn parse_bedtime(text: &str) -> Result<Option<NaiveTime>, String>
+{
+    let text = text.trim();
+
+    if text.is_empty()
+    {
+        return Ok(None);
+    }
+
+    NaiveTime::parse_from_str(text, "%H:%M")
+        .map(Some)
+        .map_err(|_| format!("Invalid bedtime \"{}\", expected time as HH:MM", text))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_parse_bedtime()
+    {
+        assert_eq!(parse_bedtime(""), Ok(None));
+        assert_eq!(
+            parse_bedtime("23:30"),
+            Ok(NaiveTime::from_hms_opt(23, 30, 0))
+        );
+        assert!(parse_bedtime("25:00").is_err());
+        assert!(parse_bedtime("late").is_err());
+    }
+}
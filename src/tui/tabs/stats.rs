@@ -0,0 +1,402 @@
+use crate::analyzer::history::Period;
+use crate::analyzer::history::daily_activity;
+use crate::analyzer::history::dosage_over_time;
+use crate::analyzer::statistics::SubstanceStatistics;
+use crate::database::entities::ingestion;
+use crate::ingestion::Ingestion;
+use crate::utils::DATABASE_CONNECTION;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Local;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use miette::IntoDiagnostic;
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::layout::Direction;
+use ratatui::layout::Layout;
+use ratatui::layout::Position;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::Bar;
+use ratatui::widgets::BarChart;
+use ratatui::widgets::BarGroup;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Sparkline;
+use sea_orm::ColumnTrait;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+
+use crate::tui::ui::list_index_at;
+use crate::core::config::Config;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+
+/// Period of history included in statistics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeRange
+{
+    #[default]
+    Month,
+    Quarter,
+    HalfYear,
+    Year,
+    All,
+}
+
+impl TimeRange
+{
+    const ORDER: [TimeRange; 5] = [
+        TimeRange::Month,
+        TimeRange::Quarter,
+        TimeRange::HalfYear,
+        TimeRange::Year,
+        TimeRange::All,
+    ];
+
+    fn label(&self) -> &'static str
+    {
+        match self
+        {
+            | TimeRange::Month => "Last 30 days",
+            | TimeRange::Quarter => "Last 90 days",
+            | TimeRange::HalfYear => "Last 180 days",
+            | TimeRange::Year => "Last 365 days",
+            | TimeRange::All => "All time",
+        }
+    }
+
+    /// Earliest date included in the range, `None` means there is no limit.
+    fn start(&self, now: DateTime<Local>) -> Option<DateTime<Local>>
+    {
+        let days = match self
+        {
+            | TimeRange::Month => 30,
+            | TimeRange::Quarter => 90,
+            | TimeRange::HalfYear => 180,
+            | TimeRange::Year => 365,
+            | TimeRange::All => return None,
+        };
+
+        Some(now - Duration::days(days))
+    }
+
+    fn cycle(&self, step: isize) -> Self
+    {
+        let index = Self::ORDER.iter().position(|r| r == self).unwrap_or(0) as isize;
+        Self::ORDER[(index + step).rem_euclid(Self::ORDER.len() as isize) as usize]
+    }
+}
+
+/// Charts of ingestion history of a single substance chosen from the list of
+/// substances which were ever logged.
+#[derive(Debug, Default)]
+pub struct StatsTab
+{
+    pub range: TimeRange,
+    /// Ingestions within selected time range, ordered from the oldest one,
+    /// `None` when they have to be loaded again.
+    ingestions: Option<Vec<Ingestion>>,
+    statistics: Vec<SubstanceStatistics>,
+    selected: usize,
+}
+
+impl StatsTab
+{
+    pub fn handle_key(&mut self, key: KeyEvent)
+    {
+        match key.code
+        {
+            | KeyCode::Down =>
+            {
+                self.selected = (self.selected + 1).min(self.statistics.len().saturating_sub(1))
+            }
+            | KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            | KeyCode::Left => self.set_range(self.range.cycle(-1)),
+            | KeyCode::Right => self.set_range(self.range.cycle(1)),
+            | _ =>
+            {}
+        }
+    }
+
+    /// Select substance clicked on the list.
+    pub fn handle_click(&mut self, area: Rect, position: Position)
+    {
+        let substances = layout(area)[0];
+
+        match list_index_at(substances, self.selected, position)
+        {
+            | Some(index) if index < self.statistics.len() => self.selected = index,
+            | _ =>
+            {}
+        }
+    }
+
+    fn set_range(&mut self, range: TimeRange)
+    {
+        self.range = range;
+        self.invalidate();
+    }
+
+    /// Drop loaded history so it's queried again on the next load.
+    pub fn invalidate(&mut self) { self.ingestions = None; }
+
+    pub async fn load(&mut self) -> miette::Result<()>
+    {
+        if self.ingestions.is_some()
+        {
+            return Ok(());
+        }
+
+        let mut query = ingestion::Entity::find();
+
+        if let Some(start) = self.range.start(Local::now())
+        {
+            query = query.filter(ingestion::Column::IngestedAt.gte(start.to_utc().naive_utc()));
+        }
+
+        let ingestions: Vec<Ingestion> = query
+            .order_by_asc(ingestion::Column::IngestedAt)
+            .all(&*DATABASE_CONNECTION)
+            .await
+            .into_diagnostic()?
+            .into_iter()
+            .map(Ingestion::from)
+            .collect();
+
+        // Keep the same substance selected when time range changes.
+        let selected_name = self
+            .statistics
+            .get(self.selected)
+            .map(|s| s.substance_name.clone());
+
+        self.statistics = SubstanceStatistics::by_substance(&ingestions);
+        self.selected = selected_name
+            .and_then(|name| self.statistics.iter().position(|s| s.substance_name == name))
+            .unwrap_or(0);
+        self.ingestions = Some(ingestions);
+
+        Ok(())
+    }
+
+    fn selected_ingestions(&self) -> Vec<Ingestion>
+    {
+        let Some(statistics) = self.statistics.get(self.selected)
+        else
+        {
+            return vec![];
+        };
+
+        self.ingestions
+            .iter()
+            .flatten()
+            .filter(|i| i.substance_name == statistics.substance_name)
+            .cloned()
+            .collect()
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect)
+    {
+        let columns = layout(area);
+
+        self.render_substances(frame, columns[0]);
+
+        let Some(statistics) = self.statistics.get(self.selected)
+        else
+        {
+            let empty = Paragraph::new("No ingestions in selected time range")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(Block::default().title("Statistics").borders(Borders::ALL));
+            frame.render_widget(empty, columns[1]);
+            return;
+        };
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Percentage(50),
+                Constraint::Length(5),
+                Constraint::Min(8),
+            ])
+            .split(columns[1]);
+
+        let ingestions = self.selected_ingestions();
+
+        self.render_summary(frame, statistics, rows[0]);
+        render_weekly_dosage(frame, &ingestions, rows[1]);
+        self.render_frequency(frame, &ingestions, rows[2]);
+        render_distribution(frame, statistics, rows[3]);
+    }
+
+    fn render_substances(&self, frame: &mut Frame, area: Rect)
+    {
+        let items: Vec<ListItem> = self
+            .statistics
+            .iter()
+            .map(|s| ListItem::new(format!("{} ({})", s.substance_name, s.ingestion_count)))
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().title("Substances").borders(Borders::ALL))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ");
+
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn render_summary(&self, frame: &mut Frame, statistics: &SubstanceStatistics, area: Rect)
+    {
+        let summary = Paragraph::new(Line::from(format!(
+            "{} ingestions, {} in total, {} on average, longest streak {} days",
+            statistics.ingestion_count,
+            statistics.total_dosage.display_in(Config::current().units.mass),
+            statistics.mean_dosage.display_in(Config::current().units.mass),
+            statistics.longest_streak
+        )))
+        .block(
+            Block::default()
+                .title(format!("{} | {} (←/→)", statistics.substance_name, self.range.label()))
+                .borders(Borders::ALL),
+        );
+
+        frame.render_widget(summary, area);
+    }
+
+    fn render_frequency(&self, frame: &mut Frame, ingestions: &[Ingestion], area: Rect)
+    {
+        let today = Local::now().date_naive();
+        let activity = daily_activity(ingestions);
+        let days = area.width.saturating_sub(2) as i64;
+
+        // The most recent days which fit in the area, oldest on the left.
+        let frequency: Vec<u64> = (0..days)
+            .rev()
+            .map(|days_ago| today - Duration::days(days_ago))
+            .map(|date| {
+                activity
+                    .get(&date)
+                    .map(|a| a.ingestion_count as u64)
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let sparkline = Sparkline::default()
+            .data(frequency)
+            .style(Style::default().fg(Color::Green))
+            .block(
+                Block::default()
+                    .title("Ingestions per day")
+                    .borders(Borders::ALL),
+            );
+
+        frame.render_widget(sparkline, area);
+    }
+}
+
+/// Areas of list of substances and of their statistics.
+fn layout(area: Rect) -> [Rect; 2]
+{
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
+        .split(area);
+
+    [columns[0], columns[1]]
+}
+
+fn render_weekly_dosage(frame: &mut Frame, ingestions: &[Ingestion], area: Rect)
+{
+    let weeks = dosage_over_time(ingestions, Period::Week);
+    let capacity = (area.width.saturating_sub(2) / 7).max(1) as usize;
+
+    // The most recent weeks which fit in the area.
+    let bars: Vec<Bar> = weeks
+        .iter()
+        .skip(weeks.len().saturating_sub(capacity))
+        .map(|(week, total)| {
+            Bar::default()
+                .value((total.as_base_units() * 1_000_000.0).round() as u64)
+                .text_value(total.display_in(Config::current().units.mass))
+                .label(Line::from(week.format("%m-%d").to_string()))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(6)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Yellow))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Yellow))
+        .block(Block::default().title("Weekly dosage").borders(Borders::ALL));
+
+    frame.render_widget(chart, area);
+}
+
+fn render_distribution(frame: &mut Frame, statistics: &SubstanceStatistics, area: Rect)
+{
+    let bars: Vec<Bar> = statistics
+        .dosage_distribution
+        .entries()
+        .into_iter()
+        .map(|(classification, count)| {
+            Bar::default()
+                .value(count as u64)
+                .label(Line::from(classification))
+                .style(Style::default().fg(classification_color(classification)))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .data(BarGroup::default().bars(&bars))
+        .direction(Direction::Horizontal)
+        .bar_width(1)
+        .bar_gap(0)
+        .block(
+            Block::default()
+                .title("Dosage classification")
+                .borders(Borders::ALL),
+        );
+
+    frame.render_widget(chart, area);
+}
+
+fn classification_color(classification: &str) -> Color
+{
+    match classification
+    {
+        | "Threshold" => Color::Blue,
+        | "Light" => Color::Green,
+        | "Common" => Color::Yellow,
+        | "Strong" => Color::LightRed,
+        | "Heavy" => Color::Red,
+        | _ => Color::Gray,
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_cycle_time_range()
+    {
+        assert_eq!(TimeRange::Month.cycle(1), TimeRange::Quarter);
+        assert_eq!(TimeRange::Month.cycle(-1), TimeRange::All);
+        assert_eq!(TimeRange::All.cycle(1), TimeRange::Month);
+    }
+}
@@ -0,0 +1,491 @@
+use crate::analyzer::statistics::SubstanceStatistics;
+use crate::database::entities::ingestion;
+use crate::database::entities::substance;
+use crate::ingestion::Ingestion;
+use crate::substance::Substance;
+use crate::substance::repository::get_substance;
+use crate::substance::route_of_administration::dosage::Dosage;
+use crate::substance::route_of_administration::dosage::DosageClassification;
+use crate::substance::route_of_administration::phase::PHASE_ORDER;
+use crate::utils::DATABASE_CONNECTION;
+use clap::ValueEnum;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use miette::IntoDiagnostic;
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::layout::Direction;
+use ratatui::layout::Layout;
+use ratatui::layout::Position;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Paragraph;
+
+use crate::tui::ui::list_index_at;
+use crate::core::config::Config;
+use crate::substance::route_of_administration::dosage::DisplayMass;
+use ratatui::widgets::Wrap;
+use sea_orm::ColumnTrait;
+use sea_orm::EntityTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use std::collections::HashMap;
+
+/// Amount of the most recent ingestions listed in the detail pane.
+const RECENT_INGESTIONS: usize = 10;
+
+/// Everything displayed in the detail pane about a single substance.
+#[derive(Debug, Clone)]
+struct SubstanceDetails
+{
+    substance: Option<Substance>,
+    statistics: Option<SubstanceStatistics>,
+    /// The most recent ingestions of the substance, newest first.
+    recent_ingestions: Vec<Ingestion>,
+}
+
+/// Searchable list of substances known to the application with details of
+/// the selected one.
+#[derive(Debug, Default)]
+pub struct SubstancesTab
+{
+    pub search: String,
+    /// Whether key presses are currently typed into the search input.
+    pub searching: bool,
+    substances: Option<Vec<substance::Model>>,
+    selected: usize,
+    /// Details of substances which were already selected, loading them
+    /// requires multiple queries so they're kept for the whole session.
+    details: HashMap<String, SubstanceDetails>,
+}
+
+impl SubstancesTab
+{
+    /// Substances which name or common names contain the search phrase.
+    fn filtered(&self) -> Vec<&substance::Model>
+    {
+        let search = self.search.trim().to_lowercase();
+
+        self.substances
+            .iter()
+            .flatten()
+            .filter(|substance| {
+                search.is_empty()
+                    || substance.name.to_lowercase().contains(&search)
+                    || substance.common_names.to_lowercase().contains(&search)
+            })
+            .collect()
+    }
+
+    fn selected_substance(&self) -> Option<&substance::Model>
+    {
+        self.filtered().get(self.selected).copied()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent)
+    {
+        if self.searching
+        {
+            match key.code
+            {
+                | KeyCode::Enter | KeyCode::Esc => self.searching = false,
+                | KeyCode::Backspace =>
+                {
+                    self.search.pop();
+                    self.selected = 0;
+                }
+                | KeyCode::Char(c) =>
+                {
+                    self.search.push(c);
+                    self.selected = 0;
+                }
+                | _ =>
+                {}
+            }
+
+            return;
+        }
+
+        let count = self.filtered().len();
+
+        match key.code
+        {
+            | KeyCode::Char('/') => self.searching = true,
+            | KeyCode::Down => self.selected = (self.selected + 1).min(count.saturating_sub(1)),
+            | KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            | KeyCode::Home => self.selected = 0,
+            | KeyCode::End => self.selected = count.saturating_sub(1),
+            | _ =>
+            {}
+        }
+    }
+
+    /// Load list of substances and details of the selected one, if they
+    /// weren't loaded before. Details are not loaded while search phrase is
+    /// being typed as selection changes with every key press.
+    pub async fn load(&mut self) -> miette::Result<()>
+    {
+        if self.substances.is_none()
+        {
+            self.substances = Some(
+                substance::Entity::find()
+                    .order_by_asc(substance::Column::Name)
+                    .all(&*DATABASE_CONNECTION)
+                    .await
+                    .into_diagnostic()?,
+            );
+        }
+
+        let Some(name) = self
+            .selected_substance()
+            .filter(|_| !self.searching)
+            .map(|s| s.name.clone())
+        else
+        {
+            return Ok(());
+        };
+
+        if self.details.contains_key(&name)
+        {
+            return Ok(());
+        }
+
+        let substance = get_substance(&name, &DATABASE_CONNECTION).await?;
+
+        let ingestions: Vec<Ingestion> = ingestion::Entity::find()
+            .filter(ingestion::Column::SubstanceName.eq(name.to_lowercase()))
+            .order_by_desc(ingestion::Column::IngestedAt)
+            .all(&*DATABASE_CONNECTION)
+            .await
+            .into_diagnostic()?
+            .into_iter()
+            .map(Ingestion::from)
+            .collect();
+
+        let statistics =
+            (!ingestions.is_empty()).then(|| SubstanceStatistics::compute(&name, &ingestions));
+
+        self.details.insert(name, SubstanceDetails {
+            substance,
+            statistics,
+            recent_ingestions: ingestions.into_iter().take(RECENT_INGESTIONS).collect(),
+        });
+
+        Ok(())
+    }
+
+    /// Forget personal history of the substance so it's loaded again, e.g.
+    /// after a new ingestion was logged.
+    pub fn invalidate(&mut self, substance_name: &str)
+    {
+        self.details
+            .retain(|name, _| !name.eq_ignore_ascii_case(substance_name));
+    }
+
+    /// Insert pasted text into the search phrase.
+    pub fn paste(&mut self, text: &str)
+    {
+        self.searching = true;
+        self.search
+            .extend(text.chars().filter(|c| !c.is_control()));
+        self.selected = 0;
+    }
+
+    /// Start search when search input is clicked, select substance when it's
+    /// clicked on the list.
+    pub fn handle_click(&mut self, area: Rect, position: Position)
+    {
+        let [search, list, _] = layout(area);
+
+        if search.contains(position)
+        {
+            self.searching = true;
+        }
+        else if let Some(index) = list_index_at(list, self.selected, position)
+        {
+            self.searching = false;
+
+            if index < self.filtered().len()
+            {
+                self.selected = index;
+            }
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect)
+    {
+        let [search, list, details] = layout(area);
+
+        self.render_search(frame, search);
+        self.render_list(frame, list);
+        self.render_details(frame, details);
+    }
+
+    fn render_search(&self, frame: &mut Frame, area: Rect)
+    {
+        let (text, style) = match (self.searching, self.search.is_empty())
+        {
+            | (true, _) => (format!("{}▏", self.search), Style::default().fg(Color::Yellow)),
+            | (false, true) => (
+                "Press '/' to search".to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+            | (false, false) => (self.search.clone(), Style::default()),
+        };
+
+        let search = Paragraph::new(text)
+            .style(style)
+            .block(Block::default().title("Search").borders(Borders::ALL));
+
+        frame.render_widget(search, area);
+    }
+
+    fn render_list(&self, frame: &mut Frame, area: Rect)
+    {
+        let substances = self.filtered();
+        let title = format!("Substances ({})", substances.len());
+        let items: Vec<ListItem> = substances
+            .iter()
+            .map(|substance| ListItem::new(substance.name.clone()))
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ");
+
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn render_details(&self, frame: &mut Frame, area: Rect)
+    {
+        let block = Block::default().title("Details").borders(Borders::ALL);
+
+        let Some(model) = self.selected_substance()
+        else
+        {
+            let empty = Paragraph::new("No substance selected")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            frame.render_widget(empty, area);
+            return;
+        };
+
+        let heading = Style::default().add_modifier(Modifier::BOLD);
+        let dimmed = Style::default().fg(Color::DarkGray);
+        let mut lines = vec![
+            Line::styled(model.name.clone(), heading.fg(Color::Yellow)),
+            Line::styled(model.psychoactive_class.clone(), dimmed),
+        ];
+
+        if !model.common_names.is_empty()
+        {
+            lines.push(Line::from(format!("Also known as: {}", model.common_names)));
+        }
+
+        lines.push(Line::default());
+
+        match self.details.get(&model.name)
+        {
+            | None => lines.push(Line::styled("Loading...", dimmed)),
+            | Some(details) =>
+            {
+                lines.extend(render_routes(details.substance.as_ref()));
+                lines.push(Line::default());
+                lines.extend(render_history(details));
+            }
+        }
+
+        let details = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block);
+
+        frame.render_widget(details, area);
+    }
+}
+
+/// Areas of search input, list of substances and details of the selected one.
+fn layout(area: Rect) -> [Rect; 3]
+{
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(area);
+
+    let list_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(columns[0]);
+
+    [list_area[0], list_area[1], columns[1]]
+}
+
+fn render_routes(substance: Option<&Substance>) -> Vec<Line<'static>>
+{
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let dimmed = Style::default().fg(Color::DarkGray);
+
+    let Some(substance) = substance.filter(|s| !s.routes_of_administration.is_empty())
+    else
+    {
+        return vec![Line::styled(
+            "No information about routes of administration",
+            dimmed,
+        )];
+    };
+
+    let mut routes: Vec<_> = substance.routes_of_administration.iter().collect();
+    routes.sort_by_key(|(classification, _)| classification.to_string());
+
+    let mut lines = Vec::new();
+
+    for (classification, route) in routes
+    {
+        lines.push(Line::styled(classification.to_string(), heading));
+
+        for dosage_classification in DosageClassification::value_variants()
+        {
+            if let Some(range) = route.dosages.get(dosage_classification)
+            {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {:<12}", dosage_classification.to_string()), dimmed),
+                    Span::raw(format!("{} - {}", format_bound(&range.start), format_bound(&range.end))),
+                ]));
+            }
+        }
+
+        for phase in PHASE_ORDER
+        {
+            if let Some(duration) = route.phases.get(&phase)
+            {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {:<12}", phase.to_string()), dimmed),
+                    Span::raw(format!(
+                        "{} - {}",
+                        format_minutes(duration.start.num_minutes()),
+                        format_minutes(duration.end.num_minutes())
+                    )),
+                ]));
+            }
+        }
+    }
+
+    lines
+}
+
+fn render_history(details: &SubstanceDetails) -> Vec<Line<'static>>
+{
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let dimmed = Style::default().fg(Color::DarkGray);
+
+    let mut lines = vec![Line::styled("Your history", heading)];
+
+    let Some(statistics) = &details.statistics
+    else
+    {
+        lines.push(Line::styled("You haven't logged this substance yet", dimmed));
+        return lines;
+    };
+
+    let unit = Config::current().units.mass;
+    lines.push(Line::from(format!(
+        "  {} ingestions, {} in total, {} on average",
+        statistics.ingestion_count,
+        statistics.total_dosage.display_in(unit),
+        statistics.mean_dosage.display_in(unit)
+    )));
+    lines.push(Line::default());
+
+    for ingestion in &details.recent_ingestions
+    {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {}  ", ingestion.ingestion_date.format("%Y-%m-%d %H:%M")),
+                dimmed,
+            ),
+            Span::raw(format!("{:<10} {}", ingestion.display_dosage(unit), ingestion.route)),
+        ]));
+    }
+
+    lines
+}
+
+fn format_bound(dosage: &Option<Dosage>) -> String
+{
+    dosage
+        .as_ref()
+        .map(|dosage| dosage.display_in(Config::current().units.mass))
+        .unwrap_or_else(|| "...".to_string())
+}
+
+fn format_minutes(minutes: Option<f32>) -> String
+{
+    match minutes.map(|m| m.round() as i64)
+    {
+        | None => "?".to_string(),
+        | Some(minutes) if minutes < 60 => format!("{}m", minutes),
+        | Some(minutes) if minutes % 60 == 0 => format!("{}h", minutes / 60),
+        | Some(minutes) => format!("{}h {}m", minutes / 60, minutes % 60),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn substance(name: &str, common_names: &str) -> substance::Model
+    {
+        substance::Model {
+            id: name.to_string(),
+            name: name.to_string(),
+            common_names: common_names.to_string(),
+            pubchem_cid: 0,
+            psychonautwiki_url: None,
+            psychoactive_class: "Stimulants".to_string(),
+            chemical_class: None,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_search_substances()
+    {
+        let mut tab = SubstancesTab {
+            substances: Some(vec![
+                substance("Caffeine", "Coffee,Tea"),
+                substance("Modafinil", "Provigil"),
+                substance("Theacrine", "Teacrine"),
+            ]),
+            ..Default::default()
+        };
+
+        tab.handle_key(KeyEvent::from(KeyCode::Char('/')));
+        for c in "tea".chars()
+        {
+            tab.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        tab.handle_key(KeyEvent::from(KeyCode::Enter));
+
+        let names: Vec<_> = tab.filtered().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Caffeine", "Theacrine"]);
+
+        tab.handle_key(KeyEvent::from(KeyCode::Down));
+        tab.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(tab.selected_substance().unwrap().name, "Theacrine");
+    }
+}
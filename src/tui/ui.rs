@@ -1,55 +1,161 @@
+use crate::substance::route_of_administration::phase::PhaseClassification;
+use crate::ingestion::Ingestion;
+use crate::core::config::Config;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Local;
+use chrono::NaiveTime;
+use chrono::TimeZone;
 use ratatui::layout::Constraint;
 use ratatui::layout::Direction;
 use ratatui::layout::Layout;
+use ratatui::layout::Position;
 use ratatui::layout::Rect;
 use ratatui::style::Color;
+use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::widgets::Block;
 use ratatui::widgets::Borders;
+use ratatui::widgets::Cell;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
 use ratatui::widgets::Paragraph;
+use ratatui::widgets::Row;
+use ratatui::widgets::Table;
 use ratatui::widgets::Tabs;
 use ratatui::Frame;
 
+use super::app::ActiveIngestion;
+use super::components::help_overlay::HelpOverlay;
+use super::keymap::Action;
+use super::components::intensity_plot::IntensityPlot;
 use super::app::App;
+use super::app::HOME_TAB;
+use super::app::SETTINGS_TAB;
+use super::app::STATS_TAB;
+use super::app::SUBSTANCES_TAB;
+
+pub const TABS: [&str; 4] = ["Home", "Substances", "Stats", "Settings"];
+
+/// Areas of the main parts of the screen, used both to render them and to
+/// find out what was clicked.
+pub struct AppLayout
+{
+    pub header: Rect,
+    pub sidebar: Rect,
+    pub main: Rect,
+    pub footer: Rect,
+}
+
+impl AppLayout
+{
+    pub fn new(area: Rect) -> Self
+    {
+        // Create the main layout
+        let main_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Body
+                Constraint::Length(3), // Footer
+            ])
+            .split(area);
+
+        // Create the body layout with sidebar
+        let body_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(20), // Sidebar
+                Constraint::Percentage(80), // Main content
+            ])
+            .split(main_layout[1]);
+
+        Self {
+            header: main_layout[0],
+            sidebar: body_layout[0],
+            main: body_layout[1],
+            footer: main_layout[2],
+        }
+    }
+}
 
 pub fn render(frame: &mut Frame, app: &App)
 {
-    // Create the main layout
-    let main_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Header
-            Constraint::Min(0),    // Body
-            Constraint::Length(3), // Footer
-        ])
-        .split(frame.area());
-
-    // Create the body layout with sidebar
-    let body_layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(20), // Sidebar
-            Constraint::Percentage(80), // Main content
-        ])
-        .split(main_layout[1]);
-
-    render_header(frame, app, main_layout[0]);
-    render_sidebar(frame, app, body_layout[0]);
-    render_main(frame, app, body_layout[1]);
-    render_footer(frame, app, main_layout[2]);
+    let layout = AppLayout::new(frame.area());
+
+    render_header(frame, app, layout.header);
+    render_sidebar(frame, app, layout.sidebar);
+    render_main(frame, app, layout.main);
+    render_footer(frame, app, layout.footer);
+
+    if let Some(form) = &app.form
+    {
+        form.render(frame, frame.area());
+    }
+
+    if app.show_help
+    {
+        HelpOverlay::new(&app.keymap).render(frame, frame.area());
+    }
+}
+
+/// Tab which title in the header is at given column.
+pub fn tab_at(area: Rect, column: u16) -> Option<usize>
+{
+    // Titles are padded with a space on both sides and separated with a
+    // single character divider.
+    let mut start = area.x + 1;
+
+    for (index, title) in TABS.iter().enumerate()
+    {
+        let end = start + title.chars().count() as u16 + 2;
+
+        if (start..end).contains(&column)
+        {
+            return Some(index);
+        }
+
+        start = end + 1;
+    }
+
+    None
+}
+
+/// Index of the item of bordered list which is displayed at given row, the
+/// list is scrolled just enough for the selected item to be visible.
+pub fn list_index_at(area: Rect, selected: usize, position: Position) -> Option<usize>
+{
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+
+    if !inner.contains(position)
+    {
+        return None;
+    }
+
+    let height = inner.height as usize;
+    let offset = (selected + 1).saturating_sub(height);
+
+    Some(offset + (position.y - inner.y) as usize)
 }
 
 fn render_header(frame: &mut Frame, app: &App, area: Rect)
 {
-    let titles = ["Home", "Substances", "Stats", "Settings"]
+    let titles = TABS
         .iter()
         .map(|t| Line::from(Span::styled(*t, Style::default().fg(Color::White))))
         .collect::<Vec<_>>();
 
+    let title = match app.profile.is_default()
+    {
+        | true => "Neuronek".to_string(),
+        | false => format!("Neuronek | {}", app.profile.name),
+    };
+
     let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL).title("Neuronek"))
+        .block(Block::default().borders(Borders::ALL).title(title))
         .select(app.selected_tab)
         .style(Style::default().fg(Color::White))
         .highlight_style(Style::default().fg(Color::Yellow));
@@ -57,21 +163,227 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect)
     frame.render_widget(tabs, area);
 }
 
-fn render_sidebar(frame: &mut Frame, _app: &App, area: Rect)
+fn render_sidebar(frame: &mut Frame, app: &App, area: Rect)
 {
-    let sidebar = Block::default().title("Navigation").borders(Borders::ALL);
-    frame.render_widget(sidebar, area);
+    let items = TABS
+        .iter()
+        .enumerate()
+        .map(|(index, title)| ListItem::new(format!("{} {}", index + 1, title)))
+        .collect::<Vec<_>>();
+
+    let sidebar = List::new(items)
+        .block(Block::default().title("Navigation").borders(Borders::ALL))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default().with_selected(Some(app.selected_tab));
+    frame.render_stateful_widget(sidebar, area, &mut state);
 }
 
-fn render_main(frame: &mut Frame, _app: &App, area: Rect)
+fn render_main(frame: &mut Frame, app: &App, area: Rect)
 {
-    let main = Block::default().title("Content").borders(Borders::ALL);
-    frame.render_widget(main, area);
+    match app.selected_tab
+    {
+        | HOME_TAB => render_home(frame, app, area),
+        | SUBSTANCES_TAB => app.substances.render(frame, area),
+        | STATS_TAB => app.stats.render(frame, area),
+        | SETTINGS_TAB => app.settings.render(frame, area),
+        | tab =>
+        {
+            let main = Block::default()
+                .title(TABS.get(tab).copied().unwrap_or("Content"))
+                .borders(Borders::ALL);
+            frame.render_widget(main, area);
+        }
+    }
 }
 
-fn render_footer(frame: &mut Frame, _app: &App, area: Rect)
+fn render_home(frame: &mut Frame, app: &App, area: Rect)
 {
-    let footer = Paragraph::new("Press 'q' to quit | Use 1-4 to switch tabs")
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(footer, area);
+    let now = Local::now();
+    let bedtime = Config::current()
+        .bedtime
+        .and_then(|bedtime| next_bedtime(bedtime, now));
+
+    let title = match bedtime
+    {
+        | Some(bedtime) => format!(
+            "Active Ingestions | bedtime in {}",
+            format_remaining(bedtime - now)
+        ),
+        | None => "Active Ingestions".to_string(),
+    };
+
+    let block = Block::default().title(title).borders(Borders::ALL);
+
+    if app.active_ingestions.is_empty()
+    {
+        let empty = Paragraph::new("No active ingestions")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(area);
+
+    let header = Row::new([
+        "Substance",
+        "Dosage",
+        "Route",
+        "Ingested",
+        "Phase",
+        "Phase Ends In",
+        "Progress",
+        "Ends In",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows = app
+        .active_ingestions
+        .iter()
+        .map(|active| render_active_ingestion(active, bedtime));
+
+    let table = Table::new(rows, [
+        Constraint::Fill(2),
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(13),
+        Constraint::Length(10),
+        Constraint::Length(8),
+    ])
+    .header(header)
+    .block(block);
+
+    frame.render_widget(table, layout[0]);
+
+    let ingestions: Vec<Ingestion> = app
+        .active_ingestions
+        .iter()
+        .map(|active| active.ingestion.clone())
+        .collect();
+    let plot = IntensityPlot::new(&ingestions, now);
+
+    frame.render_widget(plot.render(), layout[1]);
+}
+
+/// Ingestions which effects last past bedtime have their remaining time
+/// highlighted.
+fn render_active_ingestion(active: &ActiveIngestion, bedtime: Option<DateTime<Local>>)
+-> Row<'static>
+{
+    let ends_at = Local::now() + active.remaining;
+    let remaining_style = match bedtime
+    {
+        | Some(bedtime) if ends_at > bedtime => Style::default().fg(Color::Red),
+        | _ => Style::default(),
+    };
+
+    let ingestion = &active.ingestion;
+    let phase = active
+        .current_phase
+        .map(|phase| {
+            Cell::from(phase.to_string()).style(Style::default().fg(phase_color(phase)))
+        })
+        .unwrap_or_else(|| Cell::from("-"));
+
+    Row::new([
+        Cell::from(ingestion.substance_name.clone()),
+        Cell::from(ingestion.display_dosage(Config::current().units.mass)),
+        Cell::from(ingestion.route.to_string()),
+        Cell::from(ingestion.ingestion_date.format("%H:%M").to_string()),
+        phase,
+        Cell::from(
+            active
+                .phase_remaining
+                .map(format_remaining)
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        Cell::from(progress_bar(active.progress, 5)),
+        Cell::from(format_remaining(active.remaining)).style(remaining_style),
+    ])
+}
+
+/// The nearest occurrence of bedtime after given moment.
+fn next_bedtime(bedtime: NaiveTime, now: DateTime<Local>) -> Option<DateTime<Local>>
+{
+    let today = now.date_naive().and_time(bedtime);
+    let date = match today > now.naive_local()
+    {
+        | true => today,
+        | false => today + Duration::days(1),
+    };
+
+    Local.from_local_datetime(&date).earliest()
+}
+
+/// Colors of phases as described in `docs/tui/terminal.md`.
+fn phase_color(phase: PhaseClassification) -> Color
+{
+    match phase
+    {
+        | PhaseClassification::Onset => Color::Rgb(3, 102, 214),
+        | PhaseClassification::Comeup => Color::Rgb(40, 167, 69),
+        | PhaseClassification::Peak => Color::Rgb(215, 58, 73),
+        | PhaseClassification::Comedown => Color::Rgb(255, 211, 61),
+        | PhaseClassification::Afterglow | PhaseClassification::Unknown =>
+        {
+            Color::Rgb(106, 115, 125)
+        }
+    }
+}
+
+fn format_remaining(remaining: Duration) -> String
+{
+    format!(
+        "{:02}h {:02}m",
+        remaining.num_hours(),
+        remaining.num_minutes() % 60
+    )
+}
+
+fn progress_bar(progress: f64, width: usize) -> String
+{
+    let filled = ((progress * width as f64).round() as usize).min(width);
+    format!("{}{}", "▰".repeat(filled), "▱".repeat(width - filled))
+}
+
+fn render_footer(frame: &mut Frame, app: &App, area: Rect)
+{
+    let footer = match (&app.error, &app.status)
+    {
+        | (Some(error), _) => Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red)),
+        | (None, Some(status)) =>
+        {
+            Paragraph::new(status.as_str()).style(Style::default().fg(Color::Green))
+        }
+        | (None, None) =>
+        {
+            let key = |action: Action| {
+                app.keymap
+                    .keys(action)
+                    .first()
+                    .map(|key| key.to_string())
+                    .unwrap_or_default()
+            };
+
+            Paragraph::new(format!(
+                "Press '{}' for help | '{}' to quit | '{}' to log ingestion",
+                key(Action::Help),
+                key(Action::Quit),
+                key(Action::LogIngestion)
+            ))
+        }
+    };
+
+    frame.render_widget(footer.block(Block::default().borders(Borders::ALL)), area);
 }
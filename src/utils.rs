@@ -2,15 +2,20 @@ use crate::cli::MessageFormat;
 use crate::core::config::CONFIG;
 use crate::core::config::Config;
 use crate::database::Migrator;
+use crate::database::migrator::SCHEMA_VERSION;
+use crate::database::migrator::find_migration;
+use crate::database::maintenance;
 use async_std::task::block_on;
 use atty::Stream;
 use chrono::Local;
 use chrono_english::Dialect;
 use miette::IntoDiagnostic;
+use miette::miette;
 use sea_orm::Database;
 use sea_orm::DatabaseConnection;
 use sea_orm::prelude::async_trait;
 use sea_orm_migration::IntoSchemaManagerConnection;
+use sea_orm_migration::MigrationName;
 use sea_orm_migration::MigratorTrait;
 use std::env::temp_dir;
 use std::io::stdout;
@@ -36,7 +41,8 @@ pub struct AppContext<'a>
 lazy_static::lazy_static! {
     #[derive(Clone, Debug)]
     pub static ref DATABASE_CONNECTION: DatabaseConnection = {
-        let sqlite_path = format!("sqlite://{}", CONFIG.sqlite_path.clone().to_str().expect("Invalid UTF-8 in path"));
+        let config = Config::current();
+        let sqlite_path = format!("sqlite://{}", config.sqlite_path.to_str().expect("Invalid UTF-8 in path"));
 
         debug!("Opening database connection to {}", sqlite_path);
 
@@ -49,7 +55,7 @@ lazy_static::lazy_static! {
                 if error.to_string().contains("unable to open database file") {
                     warn!("Database file not found or inaccessible at {}, attempting to initialize...", sqlite_path);
 
-                    if let Err(init_error) = initialize_sqlite_by_path(&CONFIG.sqlite_path) {
+                    if let Err(init_error) = initialize_sqlite_by_path(&config.sqlite_path) {
                         error!("Failed to initialize the database: {}", init_error);
                         panic!("Critical: Unable to initialize the database file at {}. Error: {}", sqlite_path, init_error);
                     }
@@ -106,6 +112,8 @@ pub async fn migrate_database(database_connection: &DatabaseConnection) -> miett
         None
     };
 
+    check_schema_version(database_connection).await?;
+
     let pending_migrations =
         Migrator::get_pending_migrations(&database_connection.into_schema_manager_connection())
             .await
@@ -114,6 +122,21 @@ pub async fn migrate_database(database_connection: &DatabaseConnection) -> miett
     if !pending_migrations.is_empty()
     {
         info!("There are {} database pending.", pending_migrations.len());
+
+        // Fresh database has nothing worth backing up.
+        let applied_migrations =
+            Migrator::get_applied_migrations(&database_connection.into_schema_manager_connection())
+                .await
+                .into_diagnostic()?;
+
+        if !applied_migrations.is_empty()
+        {
+            let backup =
+                maintenance::migration_backup(database_connection, &Config::current().sqlite_path)
+                    .await?;
+            info!("Database backed up to {} before migration", backup.display());
+        }
+
         info!("Applying database into {:?}", database_connection);
 
         if let Some(spinner) = &spinner
@@ -134,6 +157,42 @@ pub async fn migrate_database(database_connection: &DatabaseConnection) -> miett
     Ok(())
 }
 
+/// Refuse to use a journal migrated by a newer version of neuronek, schema
+/// version of a journal is the number of its applied migrations, so the
+/// journal is newer when it contains migrations unknown to this version.
+pub async fn check_schema_version(database_connection: &DatabaseConnection) -> miette::Result<()>
+{
+    let unknown_migrations: Vec<String> = Migrator::get_migration_models(database_connection)
+        .await
+        .into_diagnostic()?
+        .into_iter()
+        .map(|migration| migration.version)
+        .filter(|name| find_migration(name).is_none())
+        .collect();
+
+    if unknown_migrations.is_empty()
+    {
+        return Ok(());
+    }
+
+    Err(miette!(
+        help = format!(
+            "Update neuronek, revert the journal with `neuronek db migrate down --to {}` using \
+             the newer version or restore a backup from {}",
+            Migrator::migrations()
+                .last()
+                .map(|migration| migration.name().to_string())
+                .unwrap_or_default(),
+            maintenance::backups_dir(&Config::current().sqlite_path).display()
+        ),
+        "Journal {} was migrated by a newer version of neuronek (unknown migrations {}), this \
+         version supports schema up to {}",
+        Config::current().sqlite_path.display(),
+        unknown_migrations.join(", "),
+        SCHEMA_VERSION
+    ))
+}
+
 
 pub fn parse_date_string(humanized_input: &str) -> miette::Result<chrono::DateTime<chrono::Local>>
 {
@@ -0,0 +1,41 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::TestJournal;
+use predicates::prelude::*;
+
+#[test]
+fn test_calendar_per_day_aggregates() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    let output = journal
+        .neuronek(&["--format", "json", "calendar"])?
+        .args(["--year", "2024"])
+        .args(["--substance", "caffeine"])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let calendar: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let days = calendar["days"].as_array().unwrap();
+
+    assert_eq!(calendar["year"], 2024);
+    assert_eq!(days.len(), 366);
+    assert_eq!(days[0]["date"], "2024-01-01");
+
+    Ok(())
+}
+
+#[test]
+fn test_calendar_dosage_requires_substance() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    journal
+        .neuronek(&["calendar"])?
+        .args(["--metric", "dosage"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("same substance"));
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::TestJournal;
+use predicates::prelude::*;
+
+#[test]
+fn test_chart_substance_by_week() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    journal
+        .neuronek(&["ingestion", "log"])?
+        .args(["-s", "caffeine"])
+        .args(["-d", "100mg"])
+        .assert()
+        .success();
+
+    let output = journal
+        .neuronek(&["--format", "json", "chart", "caffeine"])?
+        .args(["--by", "week"])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let chart: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    assert_eq!(chart["period"], "week");
+    assert!(!chart["series"].as_array().unwrap().is_empty());
+    assert_eq!(chart["usage_grid"].as_array().unwrap().len(), 7);
+
+    Ok(())
+}
+
+#[test]
+fn test_chart_unknown_substance() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    journal
+        .neuronek(&["chart", "never-ingested-substance"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No ingestions"));
+
+    Ok(())
+}
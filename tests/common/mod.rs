@@ -0,0 +1,56 @@
+// Not every test uses all of the helpers.
+#![allow(dead_code)]
+
+use assert_cmd::prelude::*;
+use std::path::PathBuf;
+use std::process::Command;
+use tempfile::TempDir;
+
+pub type TestResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
+
+/// Journal and configuration owned by a single test. Both live in a
+/// temporary directory removed once the journal is dropped, so tests can't
+/// see each other's ingestions.
+pub struct TestJournal
+{
+    directory: TempDir,
+}
+
+impl TestJournal
+{
+    pub fn new() -> TestResult<Self>
+    {
+        Ok(Self {
+            directory: tempfile::Builder::new().prefix("neuronek-").tempdir()?,
+        })
+    }
+
+    /// Path of a file in the test's directory.
+    pub fn path(&self, name: &str) -> PathBuf { self.directory.path().join(name) }
+
+    pub fn database(&self) -> PathBuf { self.path("journal.db") }
+
+    pub fn config(&self) -> PathBuf { self.path("config.toml") }
+
+    /// Command running neuronek with given arguments against the journal.
+    pub fn neuronek(&self, args: &[&str]) -> TestResult<Command>
+    {
+        let mut cmd = Command::cargo_bin("neuronek")?;
+        cmd.env("NEURONEK_DATABASE", self.database())
+            .env("NEURONEK_CONFIG", self.config())
+            .env("NEURONEK_OFFLINE", "true")
+            .env_remove("NEURONEK_PROFILE")
+            .args(args);
+        Ok(cmd)
+    }
+
+    /// Ingestions listed by neuronek as JSON.
+    pub fn ingestions(&self) -> TestResult<Vec<serde_json::Value>>
+    {
+        let output = self
+            .neuronek(&["--format", "json", "ingestion", "list"])?
+            .output()?;
+
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::TestJournal;
+use predicates::prelude::*;
+
+#[test]
+fn test_config_set_and_get() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+
+    journal
+        .neuronek(&["config", "set", "units.mass", "gram"])?
+        .assert()
+        .success();
+
+    journal
+        .neuronek(&["--format", "pretty", "config", "get", "units.mass"])?
+        .assert()
+        .success()
+        .stdout("gram\n");
+
+    journal
+        .neuronek(&["--format", "pretty", "config", "get", "units.mass"])?
+        .env("NEURONEK_UNITS_MASS", "milligram")
+        .assert()
+        .success()
+        .stdout("milligram\n");
+
+    assert!(std::fs::read_to_string(journal.config())?.contains("mass = \"gram\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_config_path_flag() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    let path = journal.path("other.toml");
+
+    journal
+        .neuronek(&["--config"])?
+        .arg(&path)
+        .args(["config", "path"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", path.display()));
+
+    // Options are read the same way as by the parser of the command line.
+    journal
+        .neuronek(&[&format!("--config={}", path.display()), "config", "path"])?
+        .assert()
+        .success()
+        .stdout(format!("{}\n", path.display()));
+    journal
+        .neuronek(&["config", "path", "--config"])?
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(format!("{}\n", path.display()));
+
+    Ok(())
+}
+
+#[test]
+fn test_boolean_environment_variables() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+
+    for (value, offline) in [("1", "true"), ("no", "false"), ("ON", "true")]
+    {
+        journal
+            .neuronek(&["--format", "pretty", "config", "get", "offline"])?
+            .env("NEURONEK_OFFLINE", value)
+            .assert()
+            .success()
+            .stdout(format!("{}\n", offline));
+    }
+
+    journal
+        .neuronek(&["config", "get", "offline"])?
+        .env("NEURONEK_OFFLINE", "maybe")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Use true, false, yes, no, on, off, 1 or 0"));
+
+    Ok(())
+}
+
+#[test]
+fn test_config_rejects_invalid_values() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+
+    journal
+        .neuronek(&["config", "set", "unknown", "value"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown configuration key"));
+
+    journal
+        .neuronek(&["config", "set", "default_route", "teleportation"])?
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_config_file_is_an_error() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    std::fs::write(journal.config(), "databse = \"journal.db\"\n")?;
+
+    journal
+        .neuronek(&["ingestion", "list"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid configuration file"));
+
+    std::fs::write(journal.config(), "units = \"gram\"\n")?;
+
+    journal
+        .neuronek(&["ingestion", "list"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid configuration file"));
+
+    std::fs::write(journal.config(), "version = 2\n")?;
+
+    journal
+        .neuronek(&["ingestion", "list"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has version 2"));
+
+    std::fs::write(journal.config(), "version = 1\n")?;
+
+    journal.neuronek(&["ingestion", "list"])?.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn test_preferred_mass_unit_only_in_pretty_output() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    let neuronek = |args: &[&str]| -> Result<_, Box<dyn std::error::Error>> {
+        let mut cmd = journal.neuronek(args)?;
+        cmd.env("NEURONEK_UNITS_MASS", "gram");
+        Ok(cmd)
+    };
+
+    neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "100mg"])?
+        .assert()
+        .success();
+
+    neuronek(&["--format", "pretty", "ingestion", "list"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0.10 g"));
+    neuronek(&["--format", "json", "ingestion", "list"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"100 mg\""));
+    neuronek(&["export", "csv"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0.10 g").not());
+
+    Ok(())
+}
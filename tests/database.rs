@@ -0,0 +1,194 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::TestJournal;
+use predicates::prelude::*;
+use sea_orm::ConnectionTrait;
+
+fn migrations(journal: &TestJournal) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>
+{
+    let output = journal
+        .neuronek(&["--format", "json", "db", "migrate", "status"])?
+        .output()?;
+
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+#[test]
+fn test_backup_and_restore() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    let backup = journal.path("backup.db");
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "100mg"])?
+        .assert()
+        .success();
+
+    journal
+        .neuronek(&["db", "backup"])?
+        .arg(&backup)
+        .assert()
+        .success();
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "50mg"])?
+        .assert()
+        .success();
+    assert_eq!(journal.ingestions()?.len(), 2);
+
+    journal
+        .neuronek(&["db", "restore"])?
+        .arg(&backup)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pre-restore"));
+    assert_eq!(journal.ingestions()?.len(), 1);
+
+    journal.neuronek(&["db", "check"])?.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn test_restore_migrates_older_backup() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    let backup = journal.path("older.db");
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "100mg"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["db", "migrate", "down", "--to", "20250211000000_fix_dosage_classification"])?
+        .assert()
+        .success();
+    std::fs::copy(journal.database(), &backup)?;
+    journal
+        .neuronek(&["db", "migrate", "up"])?
+        .assert()
+        .success();
+
+    journal
+        .neuronek(&["db", "restore"])?
+        .arg(&backup)
+        .assert()
+        .success();
+
+    assert!(
+        migrations(&journal)?
+            .iter()
+            .all(|migration| migration["applied"] == true)
+    );
+    assert_eq!(journal.ingestions()?.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_restore_rejects_invalid_backup() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    let invalid = journal.path("invalid.db");
+    std::fs::write(&invalid, "not a database")?;
+
+    journal
+        .neuronek(&["db", "restore"])?
+        .arg(&invalid)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a valid database"));
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate_down_and_up() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "100mg"])?
+        .assert()
+        .success();
+    let phase_count = || -> Result<usize, Box<dyn std::error::Error>> {
+        let output = journal
+            .neuronek(&["--format", "json", "ingestion", "view", "1"])?
+            .output()?;
+        let ingestion: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(ingestion["phases"].as_array().map(Vec::len).unwrap_or(0))
+    };
+    let phases = phase_count()?;
+    assert!(phases > 0);
+
+    // Every migration after the target is reverted.
+    let target = "20250127045746_add_ingestion_phase";
+    let migrations_before = migrations(&journal)?;
+    let position = migrations_before
+        .iter()
+        .position(|migration| migration["name"] == target)
+        .unwrap();
+    let reverted = migrations_before.len() - position - 1;
+    assert!(reverted > 0);
+
+    journal
+        .neuronek(&["db", "migrate", "down", "--to", target])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Reverted {} migrations", reverted)));
+
+    let pending = migrations(&journal)?
+        .iter()
+        .filter(|migration| migration["applied"] == false)
+        .count();
+    assert_eq!(pending, reverted);
+
+    journal
+        .neuronek(&["db", "migrate", "down", "--to", "m20220101_000001_create_table"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("can't be reverted"));
+
+    journal
+        .neuronek(&["db", "migrate", "up"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Applied {} migrations", reverted)));
+    assert_eq!(phase_count()?, phases);
+
+    journal.neuronek(&["db", "check"])?.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn test_newer_database_is_refused() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    journal
+        .neuronek(&["ingestion", "list"])?
+        .assert()
+        .success();
+
+    // Migration applied by a newer version of neuronek.
+    async_std::task::block_on(async {
+        let connection =
+            sea_orm::Database::connect(format!("sqlite://{}", journal.database().display()))
+                .await?;
+        connection
+            .execute_unprepared(
+                "INSERT INTO seaql_migrations (version, applied_at) \
+                 VALUES ('29990101000000_from_the_future', 0)",
+            )
+            .await
+    })?;
+
+    journal
+        .neuronek(&["ingestion", "list"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("was migrated by a newer"));
+
+    Ok(())
+}
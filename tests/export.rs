@@ -0,0 +1,63 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::TestJournal;
+use predicates::prelude::*;
+
+#[test]
+fn test_export_formats() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Exported journal has to contain exactly the logged ingestions.
+    let journal = TestJournal::new()?;
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "100mg", "-t", "2025-01-01 08:00"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "50mg", "-t", "2025-01-03 08:00"])?
+        .assert()
+        .success();
+
+    let output = journal.neuronek(&["export", "json"])?.output()?;
+    let export: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(export["format"], "neuronek-journal");
+    assert_eq!(export["version"], 3);
+    assert_eq!(export["ingestions"].as_array().unwrap().len(), 2);
+    assert_eq!(export["ingestions"][0]["dosage_mg"], 100.0);
+
+    let output = journal
+        .neuronek(&["export", "json", "--from", "2025-01-02", "--to", "2025-01-04"])?
+        .output()?;
+    let export: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(export["ingestions"].as_array().unwrap().len(), 1);
+    assert_eq!(export["ingestions"][0]["dosage_mg"], 50.0);
+
+    journal
+        .neuronek(&["export", "csv"])?
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("ingestion_id,substance_name,"));
+
+    let dump = journal.path("journal.sql");
+    journal
+        .neuronek(&["export", "sql", "--output"])?
+        .arg(&dump)
+        .assert()
+        .success();
+    let sql = std::fs::read_to_string(&dump)?;
+    assert!(sql.contains("CREATE TABLE IF NOT EXISTS"));
+    assert_eq!(sql.matches("INSERT INTO ingestion (").count(), 2);
+
+    let output = journal.neuronek(&["export", "ical"])?.output()?;
+    let calendar = String::from_utf8(output.stdout)?;
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+    assert!(calendar.contains("SUMMARY:caffeine 100 mg (oral)"));
+
+    let output = journal.neuronek(&["export", "ical", "--phases"])?.output()?;
+    let calendar = String::from_utf8(output.stdout)?;
+    assert!(calendar.matches("BEGIN:VEVENT").count() > 2);
+
+    Ok(())
+}
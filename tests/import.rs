@@ -0,0 +1,228 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::TestJournal;
+use predicates::prelude::*;
+use sea_orm::ConnectionTrait;
+
+#[test]
+fn test_import_exported_journal() -> Result<(), Box<dyn std::error::Error>>
+{
+    let source = TestJournal::new()?;
+    let target = TestJournal::new()?;
+
+    for dosage in ["100mg", "50mg"]
+    {
+        source
+            .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", dosage])?
+            .assert()
+            .success();
+    }
+
+    for format in ["json", "csv"]
+    {
+        source
+            .neuronek(&["export", format, "--output"])?
+            .arg(source.path(&format!("journal.{}", format)))
+            .assert()
+            .success();
+    }
+
+    target
+        .neuronek(&["import", "--dry-run"])?
+        .arg(source.path("journal.json"))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Would import 2 ingestions"));
+    assert_eq!(target.ingestions()?.len(), 0);
+
+    target
+        .neuronek(&["import"])?
+        .arg(source.path("journal.json"))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Imported 2 ingestions, skipped 0"));
+    assert_eq!(target.ingestions()?.len(), 2);
+    assert!(
+        target
+            .ingestions()?
+            .iter()
+            .all(|ingestion| ingestion["dosage_classification"] != "n/a")
+    );
+
+    // The same ingestions exported to CSV are recognized as duplicates.
+    target
+        .neuronek(&["import", "--as", "csv"])?
+        .arg(source.path("journal.csv"))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Imported 0 ingestions, skipped 2"));
+    assert_eq!(target.ingestions()?.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_import_relinks_solutions() -> Result<(), Box<dyn std::error::Error>>
+{
+    let source = TestJournal::new()?;
+
+    source
+        .neuronek(&["solution", "create", "lsd-10ml", "-s", "lsd"])?
+        .args(["--dissolved", "100ug", "--volume", "10ml"])
+        .assert()
+        .success();
+    source
+        .neuronek(&["ingestion", "log", "--solution", "lsd-10ml", "--volume", "0.5ml"])?
+        .assert()
+        .success();
+
+    for format in ["json", "csv"]
+    {
+        source
+            .neuronek(&["export", format, "--output"])?
+            .arg(source.path(&format!("journal.{}", format)))
+            .assert()
+            .success();
+
+        // Solution is created in a journal which doesn't have it.
+        let target = TestJournal::new()?;
+        target
+            .neuronek(&["import"])?
+            .arg(source.path(&format!("journal.{}", format)))
+            .assert()
+            .success();
+
+        let ingestions = target.ingestions()?;
+        assert_eq!(ingestions[0]["substance_name"], "lsd");
+        assert_eq!(ingestions[0]["dosage"], "0.5 ml (5.00 µg)");
+
+        target
+            .neuronek(&["solution", "update", "lsd-10ml", "--dissolved", "200ug"])?
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "1 ingestions of lsd-10ml keep dosage computed with previous concentration 10.0 \
+                 µg/ml",
+            ));
+    }
+
+    let sql = source.path("journal.sql");
+    source
+        .neuronek(&["export", "sql", "--output"])?
+        .arg(&sql)
+        .assert()
+        .success();
+    assert!(std::fs::read_to_string(&sql)?.contains("INSERT OR IGNORE INTO solution"));
+
+    Ok(())
+}
+
+#[test]
+fn test_import_psychonautwiki_journal() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    let export = journal.path("psychonautwiki.json");
+    std::fs::write(
+        &export,
+        r#"{
+            "experiences": [
+                {
+                    "title": "Morning",
+                    "ingestions": [
+                        {
+                            "substanceName": "Caffeine",
+                            "time": 1700000000000,
+                            "administrationRoute": "ORAL",
+                            "dose": 100.0,
+                            "units": "mg"
+                        },
+                        {
+                            "substanceName": "Caffeine",
+                            "time": 1700003600000,
+                            "administrationRoute": "SUBCUTANEOUS",
+                            "dose": 50.0,
+                            "units": "mg"
+                        },
+                        {
+                            "substanceName": "Caffeine",
+                            "time": 1700007200000,
+                            "administrationRoute": "ORAL",
+                            "dose": 2.0,
+                            "units": "cups"
+                        }
+                    ]
+                }
+            ]
+        }"#,
+    )?;
+
+    journal
+        .neuronek(&["import"])?
+        .arg(&export)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("route subcutaneous has no equivalent"))
+        .stderr(predicate::str::contains("Can't convert 2 cups of Caffeine"))
+        .stderr(predicate::str::contains("Imported 1 ingestions"));
+    assert_eq!(journal.ingestions()?.len(), 1);
+
+    // Cups are imported once their mass is known.
+    journal
+        .neuronek(&["substance", "unit", "set", "caffeine", "1 cup", "95mg"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["import"])?
+        .arg(&export)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Imported 1 ingestions, skipped 1 duplicates"));
+    assert!(
+        journal
+            .ingestions()?
+            .iter()
+            .any(|ingestion| ingestion["dosage"] == "2 cups (190 mg)")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_failed_import_is_rolled_back() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    assert_eq!(journal.ingestions()?.len(), 0);
+
+    // Database refuses the last ingestion after the first one is inserted.
+    async_std::task::block_on(async {
+        let connection =
+            sea_orm::Database::connect(format!("sqlite://{}", journal.database().display()))
+                .await?;
+        connection
+            .execute_unprepared(
+                "CREATE TRIGGER reject_ingestion BEFORE INSERT ON ingestion \
+                 WHEN NEW.substance_name = 'rejected' \
+                 BEGIN SELECT RAISE(ABORT, 'rejected by test'); END",
+            )
+            .await
+    })?;
+
+    let file = journal.path("journal.csv");
+    std::fs::write(
+        &file,
+        "substance_name,route_of_administration,dosage_mg,ingested_at\n\
+         caffeine,oral,100,2025-01-01T08:00:00Z\n\
+         rejected,oral,100,2025-01-02T08:00:00Z\n",
+    )?;
+
+    journal
+        .neuronek(&["import"])?
+        .arg(&file)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("rejected by test"));
+    assert_eq!(journal.ingestions()?.len(), 0);
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::TestJournal;
+
+#[test]
+fn test_list_ingestion_ndjson() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    journal
+        .neuronek(&["ingestion", "log"])?
+        .args(["-s", "caffeine"])
+        .args(["-d", "100mg"])
+        .assert()
+        .success();
+
+    let output = journal
+        .neuronek(&["--format", "ndjson", "ingestion", "list"])?
+        .args(["--limit", "5"])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(lines.len(), 1);
+
+    for line in lines
+    {
+        let record: serde_json::Value = serde_json::from_str(line)?;
+        assert!(record.is_object());
+        assert_eq!(record["substance_name"], "caffeine");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_list_ingestion_filters() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+
+    for dosage in ["200mg", "400mg"]
+    {
+        journal
+            .neuronek(&["ingestion", "log"])?
+            .args(["-s", "ibuprofen"])
+            .args(["-d", dosage])
+            .assert()
+            .success();
+    }
+
+    let output = journal
+        .neuronek(&["--format", "json", "ingestion", "list"])?
+        .args(["--substance", "ibuprofen"])
+        .args(["--min", "300mg"])
+        .args(["--from", "1 hour ago"])
+        .args(["--sort", "dosage", "--order", "asc"])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let records: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["substance_name"], "ibuprofen");
+    assert_eq!(records[0]["dosage"], "400 mg");
+
+    Ok(())
+}
+
+#[test]
+fn test_list_ingestion_invalid_dosage_range() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    journal
+        .neuronek(&["ingestion", "list"])?
+        .args(["--min", "1g", "--max", "10mg"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("greater than maximum"));
+
+    Ok(())
+}
+
+/// Journal with an exact, ranged and unknown dosage of caffeine.
+fn journal_with_uncertain_dosages() -> Result<TestJournal, Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+
+    for dosage in ["100mg", "100-300mg", "unknown", "250mg"]
+    {
+        journal
+            .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", dosage])?
+            .assert()
+            .success();
+    }
+
+    Ok(journal)
+}
+
+fn listed_dosages(
+    journal: &TestJournal,
+    args: &[&str],
+) -> Result<Vec<String>, Box<dyn std::error::Error>>
+{
+    let output = journal
+        .neuronek(&["--format", "json", "ingestion", "list"])?
+        .args(args)
+        .output()?;
+    let records: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+
+    Ok(records
+        .iter()
+        .map(|record| record["dosage"].as_str().unwrap().to_string())
+        .collect())
+}
+
+#[test]
+fn test_list_ingestion_dosage_filters_of_ranges() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Ranges match only when they lie within the filters, unknown dosages never do.
+    let journal = journal_with_uncertain_dosages()?;
+
+    assert_eq!(listed_dosages(&journal, &["--max", "200mg"])?, ["100 mg"]);
+    assert_eq!(listed_dosages(&journal, &["--min", "150mg"])?, ["250 mg"]);
+    assert_eq!(
+        listed_dosages(&journal, &["--min", "100mg", "--max", "300mg"])?.len(),
+        3
+    );
+
+    // Unknown dosages are valid, just not comparable.
+    journal.neuronek(&["db", "check"])?.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn test_list_ingestion_sorted_by_uncertain_dosage() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Ranges are ordered by their middle, unknown dosages are always last.
+    let journal = journal_with_uncertain_dosages()?;
+
+    assert_eq!(
+        listed_dosages(&journal, &["--sort", "dosage", "--order", "asc"])?,
+        ["100 mg", "100-300 mg", "250 mg", "unknown"]
+    );
+    assert_eq!(
+        listed_dosages(&journal, &["--sort", "dosage", "--order", "desc"])?,
+        ["250 mg", "100-300 mg", "100 mg", "unknown"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_list_ingestion_search_by_classification() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+
+    for dosage in ["100mg", "1000mg"]
+    {
+        journal
+            .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", dosage])?
+            .assert()
+            .success();
+    }
+
+    // Search is case insensitive, like for substances and routes.
+    assert_eq!(listed_dosages(&journal, &["--search", "common"])?, ["100 mg"]);
+    assert_eq!(listed_dosages(&journal, &["--search", "HEAVY"])?, ["1.00 g"]);
+
+    Ok(())
+}
@@ -1,15 +1,154 @@
+mod common;
+
 use assert_cmd::prelude::*;
-use std::process::Command;
+use common::TestJournal;
+use predicates::prelude::*;
 
 #[test]
 fn test_log_ingestion() -> Result<(), Box<dyn std::error::Error>>
 {
-    let mut cmd = Command::cargo_bin("neuronek")?;
-    cmd.env("RUST_TEST", "1")  // Set test environment variable
-        .arg("ingestion")
+    let journal = TestJournal::new()?;
+
+    let mut cmd = journal.neuronek(&[])?;
+    cmd.arg("ingestion")
         .arg("log")
         .arg("-s caffeine")
         .arg("-d 100mg");
     cmd.assert().success();
     Ok(())
 }
+
+#[test]
+fn test_log_ingestion_in_other_units() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Amounts other than mass are stored together with their converted mass.
+    let journal = TestJournal::new()?;
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "vitamin d3", "-d", "1000IU"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "2 cups"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Can't convert 2 cups of caffeine"));
+    journal
+        .neuronek(&["substance", "unit", "set", "caffeine", "1 cup", "95mg"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "2 cups"])?
+        .assert()
+        .success();
+
+    let ingestions = journal.ingestions()?;
+    let dosages: Vec<&str> = ingestions
+        .iter()
+        .filter_map(|ingestion| ingestion["dosage"].as_str())
+        .collect();
+    assert!(dosages.contains(&"1000 IU (25.0 µg)"));
+    assert!(dosages.contains(&"2 cups (190 mg)"));
+
+    Ok(())
+}
+
+#[test]
+fn test_log_ingestion_of_solution() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Ingestions of a solution keep their dosage when the solution changes.
+    let journal = TestJournal::new()?;
+
+    journal
+        .neuronek(&["solution", "create", "lsd-10ml", "-s", "lsd"])?
+        .args(["--dissolved", "100ug", "--volume", "10ml"])
+        .assert()
+        .success();
+    journal
+        .neuronek(&["ingestion", "log", "--solution", "lsd-10ml", "--volume", "1mg"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has to be a volume"));
+    journal
+        .neuronek(&["ingestion", "log", "--solution", "lsd-10ml", "--volume", "0.5ml"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["solution", "update", "lsd-10ml", "--dissolved", "200ug"])?
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("previous concentration 10.0 µg/ml"));
+
+    let ingestions = journal.ingestions()?;
+    assert_eq!(ingestions[0]["substance_name"], "lsd");
+    assert_eq!(ingestions[0]["dosage"], "0.5 ml (5.00 µg)");
+
+    Ok(())
+}
+
+#[test]
+fn test_log_ingestion_of_solution_like_logged_mass() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Dosage computed from a solution is classified as the same mass logged
+    // directly, 50 mg of caffeine is common.
+    let journal = TestJournal::new()?;
+
+    journal
+        .neuronek(&["solution", "create", "caf10", "-s", "caffeine"])?
+        .args(["--dissolved", "100mg", "--volume", "10ml"])
+        .assert()
+        .success();
+    journal
+        .neuronek(&["ingestion", "log", "--solution", "caf10", "--volume", "5ml"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "50mg"])?
+        .assert()
+        .success();
+
+    for ingestion in journal.ingestions()?
+    {
+        assert_eq!(ingestion["dosage_classification"], "Common");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_log_ingestion_with_uncertain_dosage() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Dosage ranges are classified by both of their bounds.
+    let journal = TestJournal::new()?;
+
+    for (dosage, date) in [("unknown", "08:00"), ("~200mg", "09:00"), ("100-200mg", "10:00")]
+    {
+        journal
+            .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", dosage, "-t", date])?
+            .assert()
+            .success();
+    }
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "200-100mg"])?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is above its upper bound"));
+
+    let dosages: Vec<_> = journal
+        .ingestions()?
+        .iter()
+        .map(|ingestion| {
+            (
+                ingestion["dosage"].as_str().unwrap().to_string(),
+                ingestion["dosage_classification"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+
+    assert!(dosages.contains(&("100-200 mg".to_string(), "Common - Strong".to_string())));
+    assert!(dosages.contains(&("~200 mg".to_string(), "Strong".to_string())));
+    assert!(dosages.contains(&("unknown".to_string(), "n/a".to_string())));
+
+    Ok(())
+}
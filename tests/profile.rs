@@ -0,0 +1,73 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+fn neuronek(home: &Path) -> Result<Command, Box<dyn std::error::Error>>
+{
+    let mut cmd = Command::cargo_bin("neuronek")?;
+    cmd.env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env_remove("NEURONEK_CONFIG")
+        .env_remove("NEURONEK_DATABASE")
+        .env_remove("NEURONEK_PROFILE");
+    Ok(cmd)
+}
+
+#[test]
+fn test_profiles_have_separate_journals() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Profiles are kept under the XDG directories, so the test gets its own.
+    let directory = tempfile::tempdir()?;
+    let home = directory.path();
+
+    neuronek(home)?
+        .args(["profile", "create", "experiment"])
+        .assert()
+        .success();
+
+    neuronek(home)?
+        .args(["profile", "create", "experiment"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+
+    neuronek(home)?
+        .args(["--profile", "experiment", "ingestion", "log"])
+        .args(["-s", "caffeine", "-d", "100mg"])
+        .assert()
+        .success();
+
+    let output = neuronek(home)?
+        .args(["--format", "json", "--profile", "experiment", "ingestion", "list"])
+        .output()?;
+    let ingestions: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(ingestions.as_array().unwrap().len(), 1);
+
+    let output = neuronek(home)?
+        .args(["--format", "json", "ingestion", "list"])
+        .output()?;
+    let ingestions: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert!(ingestions.as_array().unwrap().is_empty());
+
+    neuronek(home)?
+        .args(["profile", "switch", "experiment"])
+        .assert()
+        .success();
+
+    let output = neuronek(home)?
+        .args(["--format", "json", "profile", "list"])
+        .output()?;
+    let profiles: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(profiles[0]["name"], "default");
+    assert_eq!(profiles[1]["name"], "experiment");
+    assert_eq!(profiles[1]["active"], true);
+
+    neuronek(home)?
+        .args(["--profile", "missing", "journal"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("doesn't exist"));
+
+    Ok(())
+}
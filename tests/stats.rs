@@ -0,0 +1,70 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::TestJournal;
+
+#[test]
+fn test_stats_for_substance() -> Result<(), Box<dyn std::error::Error>>
+{
+    let journal = TestJournal::new()?;
+    journal
+        .neuronek(&["ingestion", "log"])?
+        .args(["-s", "caffeine"])
+        .args(["-d", "100mg"])
+        .assert()
+        .success();
+
+    let output = journal
+        .neuronek(&["--format", "json", "stats"])?
+        .args(["--substance", "caffeine"])
+        .output()?;
+
+    assert!(output.status.success());
+
+    let statistics: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+
+    assert_eq!(statistics.len(), 1);
+    assert_eq!(statistics[0]["substance_name"], "caffeine");
+    assert_eq!(statistics[0]["ingestion_count"], 1);
+    assert!(statistics[0]["dosage_distribution"].is_object());
+
+    Ok(())
+}
+
+#[test]
+fn test_stats_effective_dosage() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Effective dosage accounts for bioavailability of every route.
+    let journal = TestJournal::new()?;
+    let statistics = || -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let output = journal.neuronek(&["--format", "json", "stats"])?.output()?;
+        Ok(serde_json::from_slice(&output.stdout)?)
+    };
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "ketamine", "-d", "100mg", "-r", "oral"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["ingestion", "log", "-s", "ketamine", "-d", "40mg", "-r", "insufflated"])?
+        .assert()
+        .success();
+
+    let totals = statistics()?;
+    assert_eq!(totals[0]["total_dosage"], "140 mg");
+    assert_eq!(totals[0]["total_effective_dosage"], "35.0 mg");
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "ketamine", "-d", "20mg", "-r", "smoked"])?
+        .assert()
+        .success();
+    assert!(statistics()?[0]["total_effective_dosage"].is_null());
+
+    journal
+        .neuronek(&["substance", "bioavailability", "set", "ketamine", "smoked", "30%"])?
+        .assert()
+        .success();
+    assert_eq!(statistics()?[0]["total_effective_dosage"], "41.0 mg");
+
+    Ok(())
+}
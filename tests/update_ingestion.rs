@@ -1,19 +1,64 @@
+mod common;
+
 use assert_cmd::prelude::*;
-use std::process::Command;
+use common::TestJournal;
 
 #[test]
 fn test_update_ingestion() -> Result<(), Box<dyn std::error::Error>>
 {
-    let mut cmd = Command::cargo_bin("neuronek")?;
+    let journal = TestJournal::new()?;
+
+    let mut cmd = journal.neuronek(&[])?;
     cmd.arg("ingestion")
         .arg("log")
         .arg("-s caffeine")
         .arg("-d 100mg");
     cmd.assert().success();
 
-    let mut cmd2 = Command::cargo_bin("neuronek")?;
+    let mut cmd2 = journal.neuronek(&[])?;
     cmd2.arg("ingestion").arg("update").arg("1").arg("-d 200mg");
     cmd2.assert().success();
 
     Ok(())
 }
+
+#[test]
+fn test_update_ingestion_reclassifies_dosage() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Classification of the logged dosage doesn't outlive the dosage itself.
+    let journal = TestJournal::new()?;
+    let classification = || -> Result<String, Box<dyn std::error::Error>> {
+        let ingestions = journal.ingestions()?;
+        Ok(ingestions[0]["dosage_classification"].as_str().unwrap().to_string())
+    };
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "100-150mg"])?
+        .assert()
+        .success();
+    assert_eq!(classification()?, "Common - Strong");
+
+    journal
+        .neuronek(&["ingestion", "update", "1", "-d", "400mg"])?
+        .assert()
+        .success();
+    assert_eq!(classification()?, "Strong");
+
+    journal
+        .neuronek(&["substance", "unit", "set", "caffeine", "1 cup", "10mg"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["ingestion", "update", "1", "-d", "2 cups"])?
+        .assert()
+        .success();
+    assert_eq!(classification()?, "Threshold");
+
+    journal
+        .neuronek(&["ingestion", "update", "1", "-n", "unknown-substance"])?
+        .assert()
+        .success();
+    assert_eq!(classification()?, "n/a");
+
+    Ok(())
+}
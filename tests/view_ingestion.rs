@@ -1,21 +1,24 @@
+mod common;
+
 use assert_cmd::prelude::*;
+use common::TestJournal;
 use predicates::prelude::*;
-use std::process::Command;
+use sea_orm::ConnectionTrait;
 
 #[test]
 fn test_show_ingestion() -> Result<(), Box<dyn std::error::Error>>
 {
-    let mut cmd = Command::cargo_bin("neuronek")?;
-    cmd.env("RUST_TEST", "1")
-        .arg("ingestion")
+    let journal = TestJournal::new()?;
+
+    let mut cmd = journal.neuronek(&[])?;
+    cmd.arg("ingestion")
         .arg("log")
         .arg("-s caffeine")
         .arg("-d 100mg");
     cmd.assert().success();
 
-    let mut cmd = Command::cargo_bin("neuronek")?;
-    cmd.env("RUST_TEST", "1")
-        .arg("ingestion")
+    let mut cmd = journal.neuronek(&[])?;
+    cmd.arg("ingestion")
         .arg("view")
         .arg("1");
     cmd.assert()
@@ -28,9 +31,10 @@ fn test_show_ingestion() -> Result<(), Box<dyn std::error::Error>>
 #[test]
 fn test_show_nonexistent_ingestion() -> Result<(), Box<dyn std::error::Error>>
 {
-    let mut cmd = Command::cargo_bin("neuronek")?;
-    cmd.env("RUST_TEST", "1")
-        .arg("ingestion")
+    let journal = TestJournal::new()?;
+
+    let mut cmd = journal.neuronek(&[])?;
+    cmd.arg("ingestion")
         .arg("view")
         .arg("999999");
     cmd.assert()
@@ -39,3 +43,90 @@ fn test_show_nonexistent_ingestion() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[test]
+fn test_show_ingestion_per_kg_of_body_weight() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Ingestions are normalised with the body weight measured last before them.
+    let journal = TestJournal::new()?;
+
+    journal
+        .neuronek(&["weight", "log", "80kg", "-t", "2025-01-01 08:00"])?
+        .assert()
+        .success();
+    journal
+        .neuronek(&["weight", "log", "50", "-t", "2025-03-01 08:00"])?
+        .assert()
+        .success();
+    journal.neuronek(&["weight", "log", "0kg"])?.assert().failure();
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "200mg", "-t", "2025-02-01 08:00"])?
+        .assert()
+        .success();
+
+    let output = journal
+        .neuronek(&["--format", "json", "ingestion", "view", "1"])?
+        .output()?;
+    let ingestion: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(ingestion["dosage"], "200 mg");
+    assert_eq!(ingestion["dosage_per_kg"], "2.50 mg/kg");
+    assert_eq!(ingestion["body_weight"], "80 kg");
+
+    Ok(())
+}
+
+#[test]
+fn test_weight_reclassifies_ingestions() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Ingestions logged before their body weight is known are classified once
+    // it's logged, and again when it changes.
+    let journal = TestJournal::new()?;
+    let classification = || -> Result<String, Box<dyn std::error::Error>> {
+        let ingestions = journal.ingestions()?;
+        Ok(ingestions[0]["dosage_classification"].as_str().unwrap().to_string())
+    };
+
+    // Journal is created by the first command.
+    journal.neuronek(&["weight", "list"])?.assert().success();
+
+    // Caffeine is described only per kilogram of body weight, 200mg is common
+    // at 100kg and strong at 40kg.
+    async_std::task::block_on(async {
+        let connection =
+            sea_orm::Database::connect(format!("sqlite://{}", journal.database().display()))
+                .await?;
+        connection
+            .execute_unprepared(
+                "UPDATE substance_route_of_administration_dosage SET unit = 'mg/kg of body weight', \
+                 lower_bound_amount = lower_bound_amount / 50, \
+                 upper_bound_amount = upper_bound_amount / 50 \
+                 WHERE routeOfAdministrationId IN (SELECT id FROM substance_route_of_administration \
+                 WHERE substanceName = 'Caffeine' AND name = 'oral')",
+            )
+            .await
+    })?;
+
+    journal
+        .neuronek(&["ingestion", "log", "-s", "caffeine", "-d", "200mg", "-t", "2025-02-01 08:00"])?
+        .assert()
+        .success();
+    assert_eq!(classification()?, "n/a");
+
+    journal
+        .neuronek(&["weight", "log", "100kg", "-t", "2025-03-01 08:00"])?
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Classified 1 ingestions again"));
+    assert_eq!(classification()?, "Common");
+
+    journal
+        .neuronek(&["weight", "log", "40kg", "-t", "2025-01-15 08:00"])?
+        .assert()
+        .success();
+    assert_eq!(classification()?, "Strong");
+
+    journal.neuronek(&["weight", "delete", "2"])?.assert().success();
+    assert_eq!(classification()?, "Common");
+
+    Ok(())
+}